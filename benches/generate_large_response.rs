@@ -0,0 +1,39 @@
+use std::hint::black_box;
+use std::io::sink;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use cwmp::generate;
+use cwmp::protocol::{
+    BodyElement, CwmpVersion, Envelope, GenerateOptions, GetParameterValuesResponse,
+    HeaderElement, ParameterValue, ID,
+};
+
+fn criterion_benchmark(c: &mut Criterion) {
+    // roughly a full TR-181 dump
+    let parameters = (1..=20_000)
+        .map(|i| {
+            ParameterValue::new(
+                &format!("Device.Hosts.Host.{i}.PhysAddress"),
+                "xsd:string",
+                "00:11:22:33:44:55",
+            )
+        })
+        .collect();
+    let e: Envelope = Envelope::new(
+        Some(CwmpVersion::new(1, 0)),
+        vec![HeaderElement::ID(ID::new(true, "1234"))],
+        vec![BodyElement::GetParameterValuesResponse(
+            GetParameterValuesResponse::new(parameters),
+        )],
+    );
+    let options = GenerateOptions::default();
+    c.bench_function("generate large get_parameter_values_response", |b| {
+        b.iter(|| generate(black_box(&e)));
+    });
+    c.bench_function("write_to large get_parameter_values_response", |b| {
+        b.iter(|| black_box(&e).write_to(sink(), &options));
+    });
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
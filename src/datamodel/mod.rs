@@ -0,0 +1,493 @@
+//! In-memory TR-181/TR-098 parameter tree.
+//!
+//! A `DataModel` holds objects, multi-instance tables and parameters indexed
+//! by their path, and answers the `GetParameter*`, `SetParameter*`, `AddObject`
+//! and `DeleteObject` RPCs directly, faults included. It backs CPE simulators
+//! (`session` makes it a `CpeHandler`) as well as ACS side caches of a device.
+use std::collections::BTreeMap;
+use std::ops::Bound;
+
+use chrono::{DateTime, Utc};
+
+use crate::protocol::{
+    AddObject, AddObjectResponse, DeleteObject, DeleteObjectResponse, Fault, FaultCode,
+    GetParameterAttributes, GetParameterAttributesResponse, GetParameterNames,
+    GetParameterNamesResponse, GetParameterValues, GetParameterValuesResponse, ParameterAttribute,
+    ParameterInfoStruct, ParameterValue, SetParameterAttributes, SetParameterAttributesResponse,
+    SetParameterValues, SetParameterValuesFault, SetParameterValuesResponse,
+};
+
+mod node;
+
+pub use node::{Node, NodeKind};
+
+/// The path segment standing for the instance number in table templates,
+/// as in `Device.IP.Interface.{i}.Enable`
+pub const INSTANCE: &str = "{i}";
+
+/// A path indexed tree of objects, tables and parameters.
+///
+/// Object paths end with a dot (`Device.DeviceInfo.`), parameter names
+/// don't (`Device.DeviceInfo.SoftwareVersion`). Missing parent objects are
+/// created on insertion. The objects and parameters of a table instance are
+/// described once, below the `{i}` template of the table, and copied into
+/// each instance created by `AddObject`.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct DataModel {
+    nodes: BTreeMap<String, Node>,
+}
+
+// the object a path belongs to, "A.B.C" and "A.B.C." both give "A.B."
+fn parent(path: &str) -> Option<&str> {
+    let trimmed = path.strip_suffix('.').unwrap_or(path);
+    trimmed.rfind('.').map(|i| &path[..=i])
+}
+
+// template paths are not visible to the ACS
+fn is_template(path: &str) -> bool {
+    path.contains(INSTANCE)
+}
+
+fn same_type(a: &str, b: &str) -> bool {
+    a.strip_prefix("xsd:").unwrap_or(a) == b.strip_prefix("xsd:").unwrap_or(b)
+}
+
+// true if the value can be stored in a parameter of the given type
+fn valid_value(r#type: &str, value: &str) -> bool {
+    match r#type.strip_prefix("xsd:").unwrap_or(r#type) {
+        "boolean" => matches!(value, "0" | "1" | "true" | "false"),
+        "int" => value.parse::<i32>().is_ok(),
+        "unsignedInt" => value.parse::<u32>().is_ok(),
+        "long" => value.parse::<i64>().is_ok(),
+        "unsignedLong" => value.parse::<u64>().is_ok(),
+        "dateTime" => value.parse::<DateTime<Utc>>().is_ok(),
+        _ => true,
+    }
+}
+
+// the faults are the answers of the RPCs, as in `CpeHandler`
+#[allow(clippy::result_large_err)]
+impl DataModel {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert a single instance object, the path ending with a dot
+    pub fn insert_object(&mut self, path: &str, writable: bool) {
+        self.ensure_parents(path);
+        self.nodes
+            .entry(path.to_string())
+            .and_modify(|n| n.writable = writable)
+            .or_insert_with(|| Node::new(NodeKind::Object, writable));
+    }
+
+    /// Insert a multi-instance object, along with its `{i}` template. A
+    /// writable table accepts `AddObject`, and its instances `DeleteObject`.
+    pub fn insert_table(&mut self, path: &str, writable: bool) {
+        self.ensure_parents(path);
+        self.nodes
+            .entry(path.to_string())
+            .and_modify(|n| {
+                if !matches!(n.kind, NodeKind::Table { .. }) {
+                    n.kind = NodeKind::Table { next_instance: 1 };
+                }
+                n.writable = writable;
+            })
+            .or_insert_with(|| Node::new(NodeKind::Table { next_instance: 1 }, writable));
+        self.insert_object(&format!("{path}{INSTANCE}."), writable);
+    }
+
+    pub fn insert_parameter(&mut self, name: &str, r#type: &str, value: &str, writable: bool) {
+        self.ensure_parents(name);
+        let kind = NodeKind::Parameter {
+            r#type: r#type.to_string(),
+            value: value.to_string(),
+        };
+        self.nodes
+            .insert(name.to_string(), Node::new(kind, writable));
+    }
+
+    // create the missing objects above a path, the ones followed by an
+    // instance number or the template segment being tables
+    fn ensure_parents(&mut self, path: &str) {
+        let body = path.strip_suffix('.').unwrap_or(path);
+        for (idx, _) in body.match_indices('.') {
+            let segment = body[idx + 1..].split('.').next().unwrap_or_default();
+            let instance = segment.parse::<u32>().ok();
+            let node = self
+                .nodes
+                .entry(path[..=idx].to_string())
+                .or_insert_with(|| {
+                    let kind = if segment == INSTANCE || instance.is_some() {
+                        NodeKind::Table { next_instance: 1 }
+                    } else {
+                        NodeKind::Object
+                    };
+                    Node::new(kind, false)
+                });
+            if let (NodeKind::Table { next_instance }, Some(n)) = (&mut node.kind, instance) {
+                *next_instance = (*next_instance).max(n.saturating_add(1));
+            }
+        }
+    }
+
+    /// Create a new instance of a table, regardless of it being writable,
+    /// returning its instance number. None if the path is not a table.
+    pub fn add_instance(&mut self, table: &str) -> Option<u32> {
+        if is_template(table) {
+            return None;
+        }
+        let instance = match self.nodes.get_mut(table) {
+            Some(Node {
+                kind: NodeKind::Table { next_instance },
+                ..
+            }) => {
+                let n = *next_instance;
+                *next_instance = n.saturating_add(1);
+                n
+            }
+            _ => return None,
+        };
+        let template = format!("{table}{INSTANCE}.");
+        let path = format!("{table}{instance}.");
+        let copies: Vec<(String, Node)> = self
+            .nodes
+            .range::<str, _>((Bound::Included(template.as_str()), Bound::Unbounded))
+            .take_while(|(k, _)| k.starts_with(&template))
+            .map(|(k, n)| (format!("{path}{}", &k[template.len()..]), n.clone()))
+            .collect();
+        if copies.is_empty() {
+            self.nodes.insert(path, Node::new(NodeKind::Object, true));
+        } else {
+            self.nodes.extend(copies);
+        }
+        Some(instance)
+    }
+
+    #[must_use]
+    pub fn node(&self, path: &str) -> Option<&Node> {
+        self.nodes.get(path).filter(|_| !is_template(path))
+    }
+
+    /// The value of a parameter
+    #[must_use]
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.node(name).and_then(Node::value)
+    }
+
+    /// Set the value of a parameter, regardless of it being writable.
+    /// Returns false if there is no such parameter.
+    pub fn set_value(&mut self, name: &str, value: &str) -> bool {
+        if is_template(name) {
+            return false;
+        }
+        match self.nodes.get_mut(name).map(|n| &mut n.kind) {
+            Some(NodeKind::Parameter { value: v, .. }) => {
+                *v = value.to_string();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// The visible paths of the model, in order
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.subtree("").map(|(k, _)| k.as_str())
+    }
+
+    /// Store the values of a `GetParameterValuesResponse` or an Inform,
+    /// as an ACS caching the data model of a CPE would
+    pub fn store_values(&mut self, parameters: &[ParameterValue]) {
+        for p in parameters {
+            match self.nodes.get_mut(&p.name.0).map(|n| &mut n.kind) {
+                Some(NodeKind::Parameter { r#type, value }) => {
+                    r#type.clone_from(&p.r#type.0);
+                    value.clone_from(&p.value.0);
+                }
+                _ => self.insert_parameter(&p.name.0, &p.r#type.0, &p.value.0, false),
+            }
+        }
+    }
+
+    /// Store the objects, parameters and writable flags of a `GetParameterNamesResponse`
+    pub fn store_names(&mut self, names: &[ParameterInfoStruct]) {
+        for info in names {
+            let writable = info.writable != 0;
+            match self.nodes.get_mut(&info.name.0) {
+                Some(n) => n.writable = writable,
+                None if info.name.0.ends_with('.') => self.insert_object(&info.name.0, writable),
+                None => self.insert_parameter(&info.name.0, "", "", writable),
+            }
+        }
+    }
+
+    // the visible nodes at and below a path, "" being the whole model
+    fn subtree<'a>(&'a self, path: &'a str) -> impl Iterator<Item = (&'a String, &'a Node)> {
+        self.nodes
+            .range::<str, _>((Bound::Included(path), Bound::Unbounded))
+            .take_while(move |(k, _)| k.starts_with(path))
+            .filter(|(k, _)| !is_template(k))
+    }
+
+    // the parameters a name from a request refers to: the parameter itself,
+    // or every parameter below a partial path ending with a dot
+    fn resolve<'a>(&'a self, name: &'a str) -> Result<Vec<(&'a String, &'a Node)>, Fault> {
+        if name.is_empty() || name.ends_with('.') {
+            if !name.is_empty() && self.node(name).is_none() {
+                return Err(Fault::from_code(FaultCode::InvalidParameterName));
+            }
+            Ok(self
+                .subtree(name)
+                .filter(|(_, n)| n.is_parameter())
+                .collect())
+        } else {
+            match self.nodes.get_key_value(name) {
+                Some((k, n)) if n.is_parameter() && !is_template(k) => Ok(vec![(k, n)]),
+                _ => Err(Fault::from_code(FaultCode::InvalidParameterName)),
+            }
+        }
+    }
+
+    // update the ParameterKey of the device after a successful change
+    fn set_parameter_key(&mut self, parameter_key: &str) {
+        let keys: Vec<String> = self
+            .nodes
+            .keys()
+            .filter(|k| k.ends_with('.') && parent(k).is_none())
+            .map(|root| format!("{root}ManagementServer.ParameterKey"))
+            .collect();
+        for key in keys {
+            self.set_value(&key, parameter_key);
+        }
+    }
+
+    /// Answer a `GetParameterValues`
+    ///
+    /// # Errors
+    ///
+    /// A 9005 fault if a name is not a parameter nor an object of the model
+    pub fn get_parameter_values(
+        &self,
+        request: &GetParameterValues,
+    ) -> Result<GetParameterValuesResponse, Fault> {
+        let mut parameters = vec![];
+        for name in &request.parameternames {
+            for (name, node) in self.resolve(&name.0)? {
+                if let NodeKind::Parameter { r#type, value } = &node.kind {
+                    parameters.push(ParameterValue::new(name, r#type, value));
+                }
+            }
+        }
+        Ok(GetParameterValuesResponse::new(parameters))
+    }
+
+    /// Answer a `GetParameterNames`, honouring `NextLevel` and partial paths
+    ///
+    /// # Errors
+    ///
+    /// A 9005 fault if the path is not in the model, a 9003 fault if
+    /// `NextLevel` is set for a parameter name
+    pub fn get_parameter_names(
+        &self,
+        request: &GetParameterNames,
+    ) -> Result<GetParameterNamesResponse, Fault> {
+        let path = request.parameter_path.0.as_str();
+        let next_level = request.next_level != 0;
+        let info = |(k, n): (&String, &Node)| ParameterInfoStruct::new(k, u8::from(n.writable));
+
+        if !path.is_empty() && !path.ends_with('.') {
+            return match self.nodes.get_key_value(path) {
+                Some(found) if found.1.is_parameter() && !is_template(path) => {
+                    if next_level {
+                        Err(Fault::from_code(FaultCode::InvalidArguments))
+                    } else {
+                        Ok(GetParameterNamesResponse::new(vec![info(found)]))
+                    }
+                }
+                _ => Err(Fault::from_code(FaultCode::InvalidParameterName)),
+            };
+        }
+        if !path.is_empty() && self.node(path).is_none() {
+            return Err(Fault::from_code(FaultCode::InvalidParameterName));
+        }
+        let parameter_list = self
+            .subtree(path)
+            .filter(|(k, _)| {
+                !next_level
+                    || match parent(k) {
+                        Some(p) => p == path,
+                        None => path.is_empty(),
+                    }
+            })
+            .map(info)
+            .collect();
+        Ok(GetParameterNamesResponse::new(parameter_list))
+    }
+
+    /// Answer a `GetParameterAttributes`
+    ///
+    /// # Errors
+    ///
+    /// A 9005 fault if a name is not a parameter nor an object of the model
+    pub fn get_parameter_attributes(
+        &self,
+        request: &GetParameterAttributes,
+    ) -> Result<GetParameterAttributesResponse, Fault> {
+        let mut parameters = vec![];
+        for name in &request.parameternames {
+            for (name, node) in self.resolve(&name.0)? {
+                let access_list: Vec<&str> = node.access_list.iter().map(AsRef::as_ref).collect();
+                parameters.push(ParameterAttribute::new(
+                    name,
+                    &node.notification.to_string(),
+                    &access_list,
+                ));
+            }
+        }
+        Ok(GetParameterAttributesResponse::new(parameters))
+    }
+
+    /// Answer a `SetParameterAttributes`, applying all the changes or none
+    ///
+    /// # Errors
+    ///
+    /// A 9005 fault if a name is not a parameter nor an object of the model
+    pub fn set_parameter_attributes(
+        &mut self,
+        request: &SetParameterAttributes,
+    ) -> Result<SetParameterAttributesResponse, Fault> {
+        let mut changes = vec![];
+        for s in &request.parameter_list {
+            let names: Vec<String> = self
+                .resolve(&s.name.0)?
+                .into_iter()
+                .map(|(k, _)| k.clone())
+                .collect();
+            changes.push((s, names));
+        }
+        for (s, names) in changes {
+            for name in names {
+                if let Some(node) = self.nodes.get_mut(&name) {
+                    if s.notification_change != 0 {
+                        node.notification = s.notification;
+                    }
+                    if s.access_list_change != 0 {
+                        node.access_list = s.access_list.iter().map(|a| a.0.clone()).collect();
+                    }
+                }
+            }
+        }
+        Ok(SetParameterAttributesResponse {})
+    }
+
+    /// Answer a `SetParameterValues`, applying all the values or none
+    ///
+    /// # Errors
+    ///
+    /// A 9003 fault with a `SetParameterValuesFault` for each rejected
+    /// parameter: 9005 for unknown names, 9008 for non-writable parameters,
+    /// 9006 and 9007 for values of the wrong type
+    pub fn set_parameter_values(
+        &mut self,
+        request: &SetParameterValues,
+    ) -> Result<SetParameterValuesResponse, Fault> {
+        let mut fault = Fault::from_code(FaultCode::InvalidArguments);
+        for p in &request.parameter_list {
+            let code = match self.node(&p.name.0) {
+                Some(Node {
+                    kind: NodeKind::Parameter { r#type, .. },
+                    writable,
+                    ..
+                }) => {
+                    if !writable {
+                        Some(FaultCode::NonWritableParameter)
+                    } else if !p.r#type.0.is_empty() && !same_type(&p.r#type.0, r#type) {
+                        Some(FaultCode::InvalidParameterType)
+                    } else if !valid_value(r#type, &p.value.0) {
+                        Some(FaultCode::InvalidParameterValue)
+                    } else {
+                        None
+                    }
+                }
+                _ => Some(FaultCode::InvalidParameterName),
+            };
+            if let Some(code) = code {
+                fault.add_set_parameter_values_fault(SetParameterValuesFault::new(
+                    &p.name.0,
+                    code.code(),
+                    code.description(),
+                ));
+            }
+        }
+        if !fault.detail.set_parameter_values_fault.is_empty() {
+            return Err(fault);
+        }
+        for p in &request.parameter_list {
+            self.set_value(&p.name.0, &p.value.0);
+        }
+        if let Some(key) = &request.parameter_key {
+            self.set_parameter_key(&key.0);
+        }
+        Ok(SetParameterValuesResponse::new(0))
+    }
+
+    /// Answer an `AddObject`, creating the instance from the table template
+    ///
+    /// # Errors
+    ///
+    /// A 9005 fault if the object name is not a table, a 9008 fault if the
+    /// table is not writable
+    pub fn add_object(&mut self, request: &AddObject) -> Result<AddObjectResponse, Fault> {
+        let table = request.object_name.0.as_str();
+        match self.node(table) {
+            Some(Node {
+                kind: NodeKind::Table { .. },
+                writable: false,
+                ..
+            }) => return Err(Fault::from_code(FaultCode::NonWritableParameter)),
+            Some(Node {
+                kind: NodeKind::Table { .. },
+                ..
+            }) => {}
+            _ => return Err(Fault::from_code(FaultCode::InvalidParameterName)),
+        }
+        let instance = self
+            .add_instance(table)
+            .ok_or_else(|| Fault::from_code(FaultCode::InvalidParameterName))?;
+        self.set_parameter_key(&request.parameter_key.0);
+        Ok(AddObjectResponse::new(instance, "0"))
+    }
+
+    /// Answer a `DeleteObject`, removing the instance and everything below it
+    ///
+    /// # Errors
+    ///
+    /// A 9005 fault if the object name is not a table instance, a 9008 fault
+    /// if the instance is not writable
+    pub fn delete_object(&mut self, request: &DeleteObject) -> Result<DeleteObjectResponse, Fault> {
+        let path = request.object_name.0.as_str();
+        let in_table = parent(path)
+            .and_then(|p| self.nodes.get(p))
+            .is_some_and(|p| matches!(p.kind, NodeKind::Table { .. }));
+        match self.node(path) {
+            Some(n) if in_table && n.kind == NodeKind::Object => {
+                if !n.writable {
+                    return Err(Fault::from_code(FaultCode::NonWritableParameter));
+                }
+            }
+            _ => return Err(Fault::from_code(FaultCode::InvalidParameterName)),
+        }
+        let doomed: Vec<String> = self
+            .nodes
+            .range::<str, _>((Bound::Included(path), Bound::Unbounded))
+            .take_while(|(k, _)| k.starts_with(path))
+            .map(|(k, _)| k.clone())
+            .collect();
+        for k in doomed {
+            self.nodes.remove(&k);
+        }
+        self.set_parameter_key(&request.parameter_key.0);
+        Ok(DeleteObjectResponse::new("0"))
+    }
+}
@@ -0,0 +1,49 @@
+/// What a path of the data model refers to
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum NodeKind {
+    /// A single instance object, like `Device.DeviceInfo.`
+    Object,
+    /// A multi-instance object (a table), like `Device.IP.Interface.`,
+    /// with the instance number the next `AddObject` will use
+    Table { next_instance: u32 },
+    /// A parameter with its xsd type, like `xsd:string`, and value
+    Parameter { r#type: String, value: String },
+}
+
+/// An object or parameter of a `DataModel`
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Node {
+    pub kind: NodeKind,
+    /// For a parameter: it can be set by the ACS. For a table: `AddObject`
+    /// is allowed. For a table instance: `DeleteObject` is allowed.
+    pub writable: bool,
+    /// 0 (off), 1 (passive) or 2 (active) notification
+    pub notification: u8,
+    pub access_list: Vec<String>,
+}
+
+impl Node {
+    #[must_use]
+    pub fn new(kind: NodeKind, writable: bool) -> Self {
+        Node {
+            kind,
+            writable,
+            notification: 0,
+            access_list: vec![],
+        }
+    }
+
+    #[must_use]
+    pub fn is_parameter(&self) -> bool {
+        matches!(self.kind, NodeKind::Parameter { .. })
+    }
+
+    /// The value of a parameter, None for objects
+    #[must_use]
+    pub fn value(&self) -> Option<&str> {
+        match &self.kind {
+            NodeKind::Parameter { value, .. } => Some(value),
+            _ => None,
+        }
+    }
+}
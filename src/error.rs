@@ -1,6 +1,6 @@
 use thiserror::Error;
 
-use crate::protocol::GenerateError;
+use crate::protocol::{GenerateError, StructuralError};
 
 #[derive(Debug, Error)]
 pub enum Cwmp {
@@ -8,4 +8,137 @@ pub enum Cwmp {
     ParseError(#[from] xml::reader::Error),
     #[error("Generate error: {0}")]
     GenerateError(GenerateError),
+    #[error("Structural error: {0}")]
+    StructuralError(StructuralError),
+    #[error("Session error: {0}")]
+    SessionError(#[from] Session),
+    #[error("Transport error: {0}")]
+    TransportError(#[from] Transport),
+    #[error("Authentication error: {0}")]
+    AuthError(#[from] Auth),
+    #[error("Validation error: {0}")]
+    ValidationError(#[from] Validation),
+    #[error("Limit exceeded: {0}")]
+    LimitExceeded(#[from] Limit),
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[cfg(feature = "borrowed")]
+    #[error("Parse error: {0}")]
+    XmlError(#[from] quick_xml::Error),
+}
+
+#[derive(Debug, Error, PartialEq, Eq, Clone)]
+pub enum Session {
+    #[error("the session must be started by an Inform, got {0}")]
+    InformExpected(String),
+    #[error("the session has already ended")]
+    Finished,
+    #[error("{0} is not expected at this point of the session")]
+    Unexpected(String),
+    #[error("{response} is not a response to the outstanding {request}")]
+    ResponseMismatch { request: String, response: String },
+    #[error("ID {received} does not match the outstanding request ID {expected}")]
+    IdMismatch { expected: String, received: String },
+    #[error("an envelope must carry exactly one body element, got {0}")]
+    BodyElementCount(usize),
+}
+
+/// A TR-069 rule broken by an envelope, as found by `Envelope::validate`
+#[derive(Debug, Error, PartialEq, Eq, Clone)]
+pub enum Violation {
+    #[error("an envelope must carry exactly one body element, got {0}")]
+    BodyElementCount(usize),
+    #[error("NoMoreRequests can only be sent by a CPE, not with {0}")]
+    NoMoreRequestsFromAcs(String),
+    #[error("HoldRequests can only be sent by an ACS, not with {0}")]
+    HoldRequestsFromCpe(String),
+    #[error("the unknown header {0} must be understood")]
+    MustUnderstand(String),
+    #[error("the request {0} has no ID header")]
+    MissingId(String),
+    #[error("UseCWMPVersion {used} does not match the envelope version {envelope}")]
+    VersionMismatch { used: String, envelope: String },
+}
+
+/// A message refused by a builder, because a field breaks a TR-069 rule
+#[derive(Debug, Error, PartialEq, Eq, Clone)]
+pub enum Validation {
+    #[error("the mandatory {0} is missing")]
+    Missing(String),
+    #[error("{field} is {length} characters long, at most {max} are allowed")]
+    TooLong {
+        field: String,
+        length: usize,
+        max: usize,
+    },
+    #[error("file type {file_type} cannot be used with {rpc}")]
+    WrongFileType { file_type: String, rpc: String },
+    #[error("{field} must have {min} to {max} entries, got {count}")]
+    Count {
+        field: String,
+        count: usize,
+        min: usize,
+        max: usize,
+    },
+}
+
+/// One of the `ParseLimits` an envelope goes beyond, with its value
+#[derive(Debug, Error, PartialEq, Eq, Clone, Copy)]
+pub enum Limit {
+    #[error("the envelope is larger than {0} bytes")]
+    Bytes(usize),
+    #[error("elements are nested more than {0} deep")]
+    Depth(usize),
+    #[error("an element has more than {0} child elements")]
+    Elements(usize),
+    #[error("the text of an element is longer than {0} bytes")]
+    TextLength(usize),
+    #[error("an element has more than {0} attributes")]
+    Attributes(usize),
+    #[error("the value of an attribute is longer than {0} bytes")]
+    AttributeLength(usize),
+}
+
+#[derive(Debug, Error, PartialEq, Eq, Clone)]
+pub enum Value {
+    #[error("{value:?} is not a valid {xsd_type}")]
+    Invalid { xsd_type: String, value: String },
+    #[error("{value} is out of range for {xsd_type}")]
+    OutOfRange { xsd_type: String, value: String },
+}
+
+#[derive(Debug, Error)]
+pub enum Transport {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("malformed HTTP message: {0}")]
+    Malformed(String),
+    #[error("unexpected HTTP status {0}")]
+    Status(u16),
+    #[error("the HTTP body is larger than {0} bytes")]
+    TooLarge(usize),
+    #[error("an HTTP header line is longer than {0} bytes")]
+    LineTooLong(usize),
+    #[error("an HTTP message has more than {0} headers")]
+    TooManyHeaders(usize),
+}
+
+#[derive(Debug, Error, PartialEq, Eq, Clone)]
+pub enum Auth {
+    #[error("no credentials were given")]
+    Missing,
+    #[error("malformed authentication header: {0}")]
+    Malformed(String),
+    #[error("unsupported authentication scheme or algorithm {0}")]
+    Unsupported(String),
+    #[error("wrong user name or password for {0}")]
+    Rejected(String),
+    #[error("the nonce has expired")]
+    StaleNonce,
+    #[error("the nonce was not issued here")]
+    UnknownNonce,
+    #[error("nonce count or request id {0} has already been used")]
+    Replay(u32),
+    #[error("the credentials are for {0}, not the URI requested")]
+    UriMismatch(String),
 }
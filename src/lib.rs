@@ -1,11 +1,21 @@
 extern crate xml;
 
-use xml::reader::{ParserConfig, XmlEvent};
+use xml::common::Position;
+use xml::reader::{ParserConfig, ParserConfig2, XmlEvent};
 
 // import the protocol defs into global scope
-use protocol::{Envelope, State};
+use protocol::{Envelope, ParseLimits, ParseOptions, State};
 pub mod protocol;
 pub mod error;
+pub mod session;
+pub mod datamodel;
+#[cfg(feature = "transport")]
+pub mod transport;
+#[cfg(feature = "borrowed")]
+pub use protocol::{parse_inform_ref, parse_inform_ref_with};
+mod parser;
+pub use parser::EnvelopeParser;
+use parser::Scanned;
 
 #[cfg(doctest)]
 #[macro_use]
@@ -23,44 +33,95 @@ pub fn parse(xml: &str) -> Result<Envelope, error::Cwmp> {
     parse_bytes(xml.as_bytes())
 }
 
-/// parse a CWMP XML envelope as a slice and convert it to a rust struct
+/// parse a CWMP XML envelope as a slice and convert it to a rust struct, within
+/// the default `ParseLimits`
 /// 
 /// # Errors
 /// 
 /// Returns a `core::Error` if the envelope cannot be parsed from the XML
 pub fn parse_bytes(xml: &[u8]) -> Result<Envelope, error::Cwmp> {
-     let config = ParserConfig::new()
-        .trim_whitespace(false)
-        .whitespace_to_characters(true);
-    let parser = config.create_reader(xml);
-    let mut state: State = State::new();
-    for e in parser {
-        match e {
-            Ok(XmlEvent::StartElement {
-                ref name,
-                ref attributes,
-                ref namespace,
-            }) => {
-                // the cwmp version is part of the namespaces
-                // call the start handler for the element name
-                state.start_handler(name, attributes, namespace);
-            }
-            Ok(XmlEvent::EndElement { ref name }) => {
-                state.end_handler(name);
-            }
-            Ok(XmlEvent::Characters(ref s)) => {
-                // store the last seen text in the state.
-                state.characters(s);
-            }
-            Err(e) => {
-                return Err(error::Cwmp::ParseError(e));
-            }
-            _ => {}
+    parse_with(xml, &ParseOptions::default())
+}
+
+/// Parses some XML supposedly being a CWMP Envelope, rejecting anything that
+/// does not have the structure of a CWMP envelope instead of silently
+/// returning a partial `Envelope`
+/// 
+/// # Errors
+/// 
+/// Returns `error::Cwmp::StructuralError` for unknown elements, missing mandatory
+/// elements and integers or dateTimes that cannot be parsed, and
+/// `error::Cwmp::ParseError` if the XML itself is malformed
+pub fn parse_strict(xml: &str) -> Result<Envelope, error::Cwmp> {
+    parse_with(xml.as_bytes(), &ParseOptions::new().strict(true))
+}
+
+/// parse a CWMP XML envelope as a slice using the given `ParseOptions`
+/// 
+/// # Errors
+/// 
+/// Returns an error if the envelope cannot be parsed from the XML, or, in strict
+/// mode, if the envelope is structurally invalid, and `error::Cwmp::LimitExceeded`
+/// if it goes beyond the `ParseLimits` of the options
+pub fn parse_with(xml: &[u8], options: &ParseOptions) -> Result<Envelope, error::Cwmp> {
+    if xml.len() > options.limits.max_bytes {
+        return Err(error::Limit::Bytes(options.limits.max_bytes).into());
+    }
+    let mut parser = parser_config(&options.limits).create_reader(Scanned::new(xml));
+    let mut state: State = State::with_options(options.clone());
+    loop {
+        let e = parser.next();
+        state.position = parser.position();
+        let event = e.map_err(|e| parser.source().error(e, &options.limits))?;
+        if dispatch(&mut state, &event)? {
+            break;
         }
     }
     Ok(state.envelope)
 }
 
+// the reader configuration every parser uses, stopping at the limits before
+// it buffers more than they allow
+fn parser_config(limits: &ParseLimits) -> ParserConfig2 {
+    ParserConfig::new()
+        .trim_whitespace(false)
+        .whitespace_to_characters(true)
+        .max_data_length(limits.max_text_length)
+        .max_attributes(limits.max_attributes)
+        .max_attribute_length(limits.max_attribute_length)
+}
+
+// hand an event of the reader to the state, true at the end of the document
+fn dispatch(state: &mut State, event: &XmlEvent) -> Result<bool, error::Cwmp> {
+    match event {
+        XmlEvent::StartElement {
+            name,
+            attributes,
+            namespace,
+        } => {
+            // the cwmp version is part of the namespaces
+            // call the start handler for the element name
+            state.start_handler(name, attributes, namespace);
+        }
+        XmlEvent::EndElement { name } => {
+            state.end_handler(name);
+        }
+        XmlEvent::Characters(s) => {
+            // store the last seen text in the state.
+            state.characters(s);
+        }
+        XmlEvent::EndDocument => return Ok(true),
+        _ => {}
+    }
+    if let Some(limit) = state.limit_exceeded.take() {
+        return Err(error::Cwmp::LimitExceeded(limit));
+    }
+    match state.error.take() {
+        Some(e) => Err(error::Cwmp::StructuralError(e)),
+        None => Ok(false),
+    }
+}
+
 ///
 /// Generate CWMP XML from a rust Evenlope struct
 /// 
@@ -109,6 +170,83 @@ mod tests {
         }
     }
 
+    // an envelope with unknown body elements and unknown arguments, at any
+    // depth, which strict parsing refuses
+    #[derive(Debug, Clone)]
+    struct WithUnknown(Envelope);
+
+    impl quickcheck::Arbitrary for WithUnknown {
+        fn arbitrary(g: &mut quickcheck::Gen) -> Self {
+            let mut e = Envelope::arbitrary(g);
+            for be in &mut e.body {
+                for extra in be.extra_slots() {
+                    if bool::arbitrary(g) {
+                        extra.push(XmlElement::arbitrary(g));
+                    }
+                }
+            }
+            if bool::arbitrary(g) {
+                e.body.push(BodyElement::Unknown(XmlElement::arbitrary(g)));
+            }
+            WithUnknown(e)
+        }
+    }
+
+    #[quickcheck]
+    fn gen_and_parse_unknown(e: WithUnknown) -> bool {
+        let WithUnknown(e) = e;
+        match generate(&e).map(|xml| parse(&xml)) {
+            Ok(Ok(r)) if r == e => true,
+            r => {
+                println!("gen_and_parse_unknown: {r:?} != {e:?}");
+                false
+            }
+        }
+    }
+
+    #[test]
+    fn gen_and_parse_strict() {
+        // strict mode wants elements the model keeps optional, like the CurrentTime of an Inform
+        fn complete(mut e: Envelope) -> Envelope {
+            let time = Some(gen_utc_date(2014, 11, 28, 12, 0, 9));
+            for b in &mut e.body {
+                match b {
+                    BodyElement::Inform(i) => i.current_time = i.current_time.or(time),
+                    BodyElement::SetParameterValues(s) => {
+                        s.parameter_key.get_or_insert_with(|| "k".into());
+                    }
+                    BodyElement::TransferComplete(t) => {
+                        (t.start_time, t.complete_time) = (t.start_time.or(time), t.complete_time.or(time));
+                    }
+                    BodyElement::DownloadResponse(r) => {
+                        (r.start_time, r.complete_time) = (r.start_time.or(time), r.complete_time.or(time));
+                    }
+                    BodyElement::UploadResponse(r) => {
+                        (r.start_time, r.complete_time) = (r.start_time.or(time), r.complete_time.or(time));
+                    }
+                    _ => {}
+                }
+            }
+            e
+        }
+        fn prop(e: Envelope) -> bool {
+            let e = complete(e);
+            match generate(&e) {
+                Ok(xml) => match parse_strict(&xml) {
+                    Ok(r) => r == e,
+                    Err(err) => {
+                        println!("gen_and_parse_strict ERROR DURING PARSE: {err}");
+                        false
+                    }
+                },
+                Err(_) => false,
+            }
+        }
+        quickcheck::QuickCheck::new()
+            .tests(10)
+            .quickcheck(prop as fn(Envelope) -> bool);
+    }
+
     #[test]
     fn bytes() {
         let e: Envelope = Envelope::new(
@@ -149,6 +287,1356 @@ mod tests {
         }
     }
 
+    #[test]
+    fn set_parameter_values_fault() {
+        let mut f = Fault::new("Client", "CWMP fault", 9003, "Invalid arguments");
+        f.add_set_parameter_values_fault(SetParameterValuesFault::new(
+            "Device.ManagementServer.PeriodicInformInterval",
+            9007,
+            "Invalid parameter value",
+        ));
+        f.add_set_parameter_values_fault(SetParameterValuesFault::new(
+            "Device.DeviceInfo.Manufacturer",
+            9008,
+            "Attempt to set a non-writable parameter",
+        ));
+        let e = Envelope::new(
+            Some(CwmpVersion::new(1, 0)),
+            vec![HeaderElement::ID(ID::new(true, "1234"))],
+            vec![BodyElement::Fault(f)],
+        );
+        let xml = generate(&e).unwrap();
+        let parsed = parse(&xml).unwrap();
+        assert_eq!(parsed, e);
+        match &parsed.body[0] {
+            BodyElement::Fault(f) => {
+                assert_eq!(f.detail.fault_code(), FaultCode::InvalidArguments);
+                assert_eq!(
+                    f.detail.set_parameter_values_fault[1].code(),
+                    FaultCode::NonWritableParameter
+                );
+            }
+            _ => panic!("expected a Fault"),
+        }
+    }
+
+    #[test]
+    fn fault_codes() {
+        for code in (8000..=8006).chain(9000..=9032) {
+            let fc = FaultCode::from(code);
+            assert!(!matches!(fc, FaultCode::Other(_)), "{code} is not mapped");
+            assert_eq!(u32::from(fc), code);
+        }
+        assert_eq!(FaultCode::from(8850), FaultCode::AcsVendor(8850));
+        assert_eq!(FaultCode::from(9850), FaultCode::CpeVendor(9850));
+        assert_eq!(FaultCode::from(1234), FaultCode::Other(1234));
+        assert!(FaultCode::AcsRetryRequest.is_acs_fault());
+        assert!(FaultCode::CpeVendor(9801).is_cpe_fault());
+    }
+
+    fn over_the_wire(e: &Envelope) -> Envelope {
+        parse(&generate(e).unwrap()).unwrap()
+    }
+
+    fn cpe_inform(id: &str) -> Envelope {
+        Envelope::new(
+            Some(CwmpVersion::new(1, 2)),
+            vec![HeaderElement::ID(ID::new(true, id))],
+            vec![BodyElement::Inform(Inform::new(
+                DeviceId::new("The Company", "AA1234", "IAD_001", "S99998888"),
+                vec![EventStruct::new("2 PERIODIC", "")],
+                1,
+                gen_utc_date(2014, 11, 28, 12, 0, 9),
+                0,
+                vec![],
+            ))],
+        )
+    }
+
+    #[test]
+    fn acs_session() {
+        use crate::session::{AcsSession, SessionAction};
+
+        let mut session = AcsSession::new();
+        session.queue(BodyElement::GetParameterValues(GetParameterValues::new(&[
+            "Device.DeviceInfo.SoftwareVersion",
+        ])));
+
+        let reply = match session.handle(Some(&over_the_wire(&cpe_inform("42")))) {
+            Ok(SessionAction::Send(e)) => over_the_wire(&e),
+            r => panic!("expected an InformResponse, got {r:?}"),
+        };
+        assert_eq!(reply.cwmp_version, Some(CwmpVersion::new(1, 2)));
+        assert_eq!(reply.header, vec![HeaderElement::ID(ID::new(true, "42"))]);
+        assert_eq!(reply.body, vec![BodyElement::InformResponse(InformResponse::new(1))]);
+        assert_eq!(session.device_id().unwrap().serial_number.0, "S99998888");
+
+        let tc = Envelope::new(
+            Some(CwmpVersion::new(1, 2)),
+            vec![HeaderElement::ID(ID::new(true, "43"))],
+            vec![BodyElement::TransferComplete(TransferComplete::new(
+                "cmdkey",
+                FaultStruct::new(0, ""),
+                Some(gen_utc_date(2014, 11, 28, 12, 0, 9)),
+                Some(gen_utc_date(2014, 11, 28, 12, 0, 19)),
+            ))],
+        );
+        match session.handle(Some(&over_the_wire(&tc))) {
+            Ok(SessionAction::Send(e)) => assert!(matches!(
+                e.body[0],
+                BodyElement::TransferCompleteResponse(_)
+            )),
+            r => panic!("expected a TransferCompleteResponse, got {r:?}"),
+        }
+
+        // empty POST, the ACS sends its queued request
+        let request = match session.handle(None) {
+            Ok(SessionAction::Send(e)) => over_the_wire(&e),
+            r => panic!("expected a GetParameterValues, got {r:?}"),
+        };
+        assert_eq!(session.pending(), 0);
+        let id = match &request.header[0] {
+            HeaderElement::ID(id) => id.id.0.clone(),
+            h => panic!("expected an ID header, got {h:?}"),
+        };
+
+        let response = Envelope::new(
+            Some(CwmpVersion::new(1, 2)),
+            vec![HeaderElement::ID(ID::new(true, &id))],
+            vec![BodyElement::GetParameterValuesResponse(
+                GetParameterValuesResponse::new(vec![ParameterValue::new(
+                    "Device.DeviceInfo.SoftwareVersion",
+                    "xsd:string",
+                    "1.0",
+                )]),
+            )],
+        );
+        assert_eq!(session.handle(Some(&over_the_wire(&response))), Ok(SessionAction::End));
+        assert!(session.is_finished());
+        assert_eq!(session.cpe_requests().len(), 1);
+        assert_eq!(session.exchanges().len(), 1);
+        assert_eq!(session.exchanges()[0].id, id);
+        assert_eq!(session.exchanges()[0].response, response.body[0]);
+        assert_eq!(session.handle(None), Err(error::Session::Finished));
+    }
+
+    #[test]
+    fn acs_session_errors() {
+        use crate::session::{AcsSession, SessionAction};
+
+        let mut session = AcsSession::new();
+        assert_eq!(
+            session.handle(None),
+            Err(error::Session::InformExpected("an empty envelope".to_string()))
+        );
+
+        let mut session = AcsSession::new();
+        session.queue(BodyElement::Reboot(Reboot::new("reboot")));
+        assert!(session.handle(Some(&cpe_inform("1"))).is_ok());
+        let id = match session.handle(None) {
+            Ok(SessionAction::Send(e)) => match &e.header[0] {
+                HeaderElement::ID(id) => id.id.0.clone(),
+                h => panic!("expected an ID header, got {h:?}"),
+            },
+            r => panic!("expected a Reboot, got {r:?}"),
+        };
+        let wrong_kind = Envelope::new(
+            None,
+            vec![HeaderElement::ID(ID::new(true, &id))],
+            vec![BodyElement::FactoryResetResponse(FactoryResetResponse)],
+        );
+        assert_eq!(
+            session.clone().handle(Some(&wrong_kind)),
+            Err(error::Session::ResponseMismatch {
+                request: "Reboot".to_string(),
+                response: "FactoryResetResponse".to_string(),
+            })
+        );
+        let wrong_id = Envelope::new(
+            None,
+            vec![HeaderElement::ID(ID::new(true, "not-it"))],
+            vec![BodyElement::RebootResponse(RebootResponse {})],
+        );
+        assert_eq!(
+            session.clone().handle(Some(&wrong_id)),
+            Err(error::Session::IdMismatch {
+                expected: id,
+                received: "not-it".to_string(),
+            })
+        );
+        // a fault is a valid answer to any request
+        let fault = Envelope::new(
+            None,
+            vec![],
+            vec![BodyElement::Fault(Fault::new(
+                "Client",
+                "CWMP fault",
+                9001,
+                "Request denied",
+            ))],
+        );
+        assert_eq!(session.handle(Some(&fault)), Ok(SessionAction::End));
+    }
+
+    #[test]
+    fn acs_cpe_conversation() {
+        use crate::session::{AcsSession, CpeSession, SessionAction, SimulatedDevice};
+
+        let mut device = SimulatedDevice::new();
+        device.add_parameter(ParameterValue::new("Device.DeviceInfo.SoftwareVersion", "xsd:string", "1.0"), false);
+        device.add_parameter(ParameterValue::new("Device.ManagementServer.PeriodicInformInterval", "xsd:unsignedInt", "3600"), true);
+        let mut cpe = CpeSession::new(DeviceId::new("The Company", "AA1234", "IAD_001", "S99998888"), device);
+        cpe.add_event(EventStruct::new("7 TRANSFER COMPLETE", ""));
+        cpe.queue(BodyElement::TransferComplete(TransferComplete::new(
+            "cmdkey",
+            FaultStruct::new(0, ""),
+            Some(gen_utc_date(2014, 11, 28, 12, 0, 9)),
+            Some(gen_utc_date(2014, 11, 28, 12, 0, 19)),
+        )));
+
+        let mut acs = AcsSession::new();
+        acs.queue(BodyElement::GetParameterValues(GetParameterValues::new(&["Device.DeviceInfo."])));
+        acs.queue(BodyElement::SetParameterValues(SetParameterValues::new(None, &[
+            &ParameterValue::new("Device.ManagementServer.PeriodicInformInterval", "xsd:unsignedInt", "60"),
+        ])));
+        acs.queue(BodyElement::SetParameterValues(SetParameterValues::new(None, &[
+            &ParameterValue::new("Device.DeviceInfo.SoftwareVersion", "xsd:string", "2.0"),
+        ])));
+        acs.queue(BodyElement::Reboot(Reboot::new("reboot")));
+
+        let mut to_acs = Some(over_the_wire(&cpe.start().unwrap()));
+        loop {
+            let to_cpe = match acs.handle(to_acs.as_ref()).unwrap() {
+                SessionAction::Send(e) => Some(over_the_wire(&e)),
+                SessionAction::End => None,
+            };
+            match cpe.handle(to_cpe.as_ref()).unwrap() {
+                SessionAction::Send(e) => to_acs = Some(over_the_wire(&e)),
+                SessionAction::End => break,
+            }
+        }
+
+        assert!(acs.is_finished() && cpe.is_finished());
+        assert_eq!(acs.cpe_requests().len(), 1);
+        assert_eq!(cpe.exchanges().len(), 2);
+        assert_eq!(cpe.acs_requests().len(), 4);
+
+        let exchanges = acs.exchanges();
+        assert_eq!(exchanges.len(), 4);
+        match &exchanges[0].response {
+            BodyElement::GetParameterValuesResponse(r) => assert_eq!(r.parameters[0].value.0, "1.0"),
+            r => panic!("expected a GetParameterValuesResponse, got {r:?}"),
+        }
+        assert_eq!(exchanges[1].response, BodyElement::SetParameterValuesResponse(SetParameterValuesResponse::new(0)));
+        match &exchanges[2].response {
+            BodyElement::Fault(f) => {
+                assert_eq!(f.detail.fault_code(), FaultCode::InvalidArguments);
+                assert_eq!(f.detail.set_parameter_values_fault[0].code(), FaultCode::NonWritableParameter);
+            }
+            r => panic!("expected a Fault, got {r:?}"),
+        }
+
+        let device = cpe.into_handler();
+        assert_eq!(device.reboots, vec!["reboot".to_string()]);
+        assert_eq!(device.parameter("Device.ManagementServer.PeriodicInformInterval").unwrap().value.0, "60");
+        assert_eq!(device.parameter("Device.DeviceInfo.SoftwareVersion").unwrap().value.0, "1.0");
+    }
+
+    #[test]
+    fn data_model() {
+        use crate::datamodel::DataModel;
+
+        let mut dm = DataModel::new();
+        dm.insert_parameter("Device.DeviceInfo.SoftwareVersion", "xsd:string", "1.0", false);
+        dm.insert_parameter("Device.ManagementServer.ParameterKey", "xsd:string", "", false);
+        dm.insert_table("Device.IP.Interface.", true);
+        dm.insert_parameter("Device.IP.Interface.{i}.Enable", "xsd:boolean", "false", true);
+        dm.insert_parameter("Device.IP.Interface.{i}.Name", "xsd:string", "", false);
+
+        let names = |dm: &DataModel, path: &str, next_level: u32| -> Vec<String> {
+            dm.get_parameter_names(&GetParameterNames::new(path, next_level))
+                .unwrap()
+                .parameter_list
+                .into_iter()
+                .map(|p| p.name.0)
+                .collect()
+        };
+        assert_eq!(names(&dm, "", 1), vec!["Device."]);
+        assert_eq!(names(&dm, "Device.", 1), vec!["Device.DeviceInfo.", "Device.IP.", "Device.ManagementServer."]);
+        assert_eq!(names(&dm, "Device.DeviceInfo.", 0), vec!["Device.DeviceInfo.", "Device.DeviceInfo.SoftwareVersion"]);
+        assert_eq!(names(&dm, "Device.IP.Interface.", 1), Vec::<String>::new());
+
+        let added = dm.add_object(&AddObject::new("Device.IP.Interface.", "key1")).unwrap();
+        assert_eq!(added.instance_number, 1);
+        assert_eq!(dm.add_object(&AddObject::new("Device.IP.Interface.", "key2")).unwrap().instance_number, 2);
+        assert_eq!(dm.value("Device.ManagementServer.ParameterKey"), Some("key2"));
+        assert_eq!(names(&dm, "Device.IP.Interface.", 1), vec!["Device.IP.Interface.1.", "Device.IP.Interface.2."]);
+
+        let values = dm.get_parameter_values(&GetParameterValues::new(&["Device.IP.Interface.2."])).unwrap();
+        assert_eq!(values.parameters, vec![
+            ParameterValue::new("Device.IP.Interface.2.Enable", "xsd:boolean", "false"),
+            ParameterValue::new("Device.IP.Interface.2.Name", "xsd:string", ""),
+        ]);
+        let unknown = dm.get_parameter_values(&GetParameterValues::new(&["Device.Nope."])).unwrap_err();
+        assert_eq!(unknown.detail.fault_code(), FaultCode::InvalidParameterName);
+
+        let set = |names: &[(&str, &str)]| {
+            let pvs: Vec<ParameterValue> = names.iter().map(|(n, v)| ParameterValue::new(n, "", v)).collect();
+            SetParameterValues::new(Some("key3"), &pvs.iter().collect::<Vec<_>>())
+        };
+        let fault = dm
+            .set_parameter_values(&set(&[
+                ("Device.IP.Interface.1.Enable", "true"),
+                ("Device.IP.Interface.1.Name", "eth0"),
+                ("Device.IP.Interface.7.Enable", "true"),
+                ("Device.IP.Interface.2.Enable", "maybe"),
+            ]))
+            .unwrap_err();
+        let codes: Vec<FaultCode> = fault.detail.set_parameter_values_fault.iter().map(SetParameterValuesFault::code).collect();
+        assert_eq!(codes, vec![FaultCode::NonWritableParameter, FaultCode::InvalidParameterName, FaultCode::InvalidParameterValue]);
+        assert_eq!(dm.value("Device.IP.Interface.1.Enable"), Some("false"));
+        assert!(dm.set_parameter_values(&set(&[("Device.IP.Interface.1.Enable", "true")])).is_ok());
+        assert_eq!(dm.value("Device.IP.Interface.1.Enable"), Some("true"));
+        assert_eq!(dm.value("Device.ManagementServer.ParameterKey"), Some("key3"));
+
+        assert!(dm.delete_object(&DeleteObject::new("Device.IP.Interface.1.", "")).is_ok());
+        assert_eq!(dm.value("Device.IP.Interface.1.Enable"), None);
+        let fault = dm.delete_object(&DeleteObject::new("Device.DeviceInfo.", "")).unwrap_err();
+        assert_eq!(fault.detail.fault_code(), FaultCode::InvalidParameterName);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        fn prop(e: Envelope) -> bool {
+            let json = serde_json::to_string(&e).unwrap();
+            serde_json::from_str::<Envelope>(&json).unwrap() == e
+        }
+        quickcheck::QuickCheck::new()
+            .tests(5)
+            .quickcheck(prop as fn(Envelope) -> bool);
+
+        let pv = ParameterValue::new("Device.DeviceInfo.SoftwareVersion", "xsd:string", "1.0");
+        assert_eq!(
+            serde_json::to_value(&pv).unwrap(),
+            serde_json::json!({"name": "Device.DeviceInfo.SoftwareVersion", "type": "xsd:string", "value": "1.0"})
+        );
+        let dr = DownloadResponse::new("0", gen_utc_date(2014, 11, 28, 12, 0, 9), gen_utc_date(2014, 11, 28, 12, 0, 19));
+        assert_eq!(serde_json::to_value(&dr).unwrap()["start_time"], "2014-11-28T12:00:09Z");
+    }
+
+    #[test]
+    fn write_to() {
+        let e = cpe_inform("1");
+        let mut xml = Vec::new();
+        e.write_to(&mut xml, &GenerateOptions::default()).unwrap();
+        assert_eq!(String::from_utf8(xml).unwrap(), generate(&e).unwrap());
+
+        let mut compact = Vec::new();
+        e.write_to(&mut compact, &GenerateOptions::new().indent(false)).unwrap();
+        let compact = String::from_utf8(compact).unwrap();
+        assert!(!compact.contains('\n'));
+        assert_eq!(parse(&compact).unwrap(), e);
+    }
+
+    #[test]
+    fn generate_options() {
+        let e = cpe_inform("1");
+        let options = GenerateOptions::new()
+            .declaration(false)
+            .encoding_style(false)
+            .array_xsi_type(true)
+            .envelope_prefix("soapenv")
+            .encoding_prefix("soapenc")
+            .cwmp_prefix("cwmp12");
+        let mut xml = Vec::new();
+        e.write_to(&mut xml, &options).unwrap();
+        let xml = String::from_utf8(xml).unwrap();
+        assert!(xml.starts_with("<soapenv:Envelope"));
+        assert!(xml.contains("<soapenv:Body>"));
+        assert!(xml.contains("<cwmp12:Inform>"));
+        assert!(xml.contains(r#"soapenc:arrayType="cwmp12:EventStruct[1]""#));
+        assert!(xml.contains(r#"xsi:type="soapenc:Array""#));
+        assert!(!xml.contains("SOAP-EN"));
+        assert!(!xml.contains("encodingStyle"));
+        assert_eq!(parse(&xml).unwrap(), e);
+
+        let default = generate(&e).unwrap();
+        assert!(default.starts_with("<?xml"));
+        assert!(default.contains("SOAP-ENV:encodingStyle"));
+        assert!(!default.contains("xsi:type=\"SOAP-ENC:Array\""));
+    }
+
+    #[quickcheck]
+    fn param_value_round_trip(v: ParamValue) -> bool {
+        let pv = ParameterValue::typed("Device.X", &v);
+        pv.param_value() == v && ParameterValue::typed("Device.X", &pv.param_value()) == pv
+    }
+
+    // a valid raw value, in any of the forms xsd allows for its type
+    #[derive(Debug, Clone)]
+    struct RawValue(ParameterValue);
+
+    impl quickcheck::Arbitrary for RawValue {
+        fn arbitrary(g: &mut quickcheck::Gen) -> Self {
+            let v = ParamValue::arbitrary(g);
+            let (mut t, mut raw) = (v.xsd_type().to_string(), v.to_string());
+            if bool::arbitrary(g) {
+                match &v {
+                    ParamValue::Int(_) | ParamValue::UnsignedInt(_) | ParamValue::Long(_) | ParamValue::UnsignedLong(_) => {
+                        raw = match raw.strip_prefix('-') {
+                            Some(digits) => format!(" -00{digits}\n"),
+                            None => format!("+00{raw} "),
+                        };
+                    }
+                    ParamValue::Boolean(b) => raw = if *b { "1" } else { "0" }.to_string(),
+                    ParamValue::DateTime(Some(dt)) => raw = dt.with_timezone(&chrono::FixedOffset::east_opt(7200).unwrap()).to_rfc3339(),
+                    ParamValue::Base64(_) => t = "xsd:base64Binary".to_string(),
+                    ParamValue::HexBinary(_) => raw = raw.to_uppercase(),
+                    _ => {}
+                }
+            }
+            if bool::arbitrary(g) {
+                t = t.trim_start_matches("xsd:").to_string();
+            }
+            RawValue(ParameterValue::new("Device.X", &t, &raw))
+        }
+    }
+
+    #[quickcheck]
+    fn param_value_raw_round_trip(raw: RawValue) -> bool {
+        let RawValue(pv) = raw;
+        let v = pv.param_value();
+        ParamValue::parse(&pv.r#type.0, &pv.value.0).is_ok()
+            && v.validate().is_ok()
+            && ParameterValue::typed("Device.X", &v) == pv
+    }
+
+    #[test]
+    fn param_values() {
+        use crate::error::Value;
+
+        let pv = |t: &str, v: &str| ParameterValue::new("Device.X", t, v).param_value();
+        assert_eq!(pv("xsd:unsignedInt", "60"), ParamValue::UnsignedInt(60));
+        assert_eq!(pv("xsd:boolean", "false"), ParamValue::Boolean(false));
+        assert_eq!(pv("xsd:dateTime", UNKNOWN_TIME), ParamValue::DateTime(None));
+        assert_eq!(
+            pv("xsd:dateTime", "2014-11-28T12:00:09Z"),
+            ParamValue::DateTime(Some(gen_utc_date(2014, 11, 28, 12, 0, 9)))
+        );
+        assert_eq!(pv("xsd:base64", "AQI="), ParamValue::Base64(vec![1, 2]));
+        assert_eq!(pv("xsd:hexBinary", "0aff"), ParamValue::HexBinary(vec![10, 255]));
+        // other valid forms stay as written, and ParamValue::parse reads them
+        for (t, v) in [
+            ("xsd:boolean", "1"),
+            ("xsd:dateTime", "2014-11-28T14:00:09+02:00"),
+            ("unsignedInt", "5"),
+            ("xsd:base64Binary", "AQI="),
+            ("xsd:int", "007"),
+        ] {
+            assert_eq!(pv(t, v), ParamValue::Unknown(t.to_string(), v.to_string()));
+            assert_eq!(ParameterValue::typed("Device.X", &pv(t, v)), ParameterValue::new("Device.X", t, v));
+            assert!(ParamValue::parse(t, v).is_ok() && pv(t, v).validate().is_ok());
+        }
+        assert_eq!(
+            ParamValue::parse("xsd:dateTime", "2014-11-28T14:00:09+02:00"),
+            Ok(ParamValue::DateTime(Some(gen_utc_date(2014, 11, 28, 12, 0, 9))))
+        );
+        assert_eq!(ParamValue::parse("unsignedInt", "5"), Ok(ParamValue::UnsignedInt(5)));
+        assert_eq!(ParamValue::parse("xsd:base64Binary", "AQI="), Ok(ParamValue::Base64(vec![1, 2])));
+        assert_eq!(ParamValue::parse("xsd:int", "007"), Ok(ParamValue::Int(7)));
+        // invalid values are kept as written
+        assert_eq!(pv("xsd:unsignedInt", "-1"), ParamValue::Unknown("xsd:unsignedInt".to_string(), "-1".to_string()));
+
+        assert_eq!(ParamValue::parse("xsd:boolean", "1"), Ok(ParamValue::Boolean(true)));
+        assert_eq!(ParamValue::parse("xsd:int", " -5 "), Ok(ParamValue::Int(-5)));
+        assert!(matches!(ParamValue::parse("xsd:unsignedInt", "-1"), Err(Value::OutOfRange { .. })));
+        assert!(matches!(ParamValue::parse("xsd:unsignedInt", "4294967296"), Err(Value::OutOfRange { .. })));
+        assert!(matches!(ParamValue::parse("xsd:int", "ten"), Err(Value::Invalid { .. })));
+        assert!(matches!(ParamValue::parse("xsd:hexBinary", "abc"), Err(Value::Invalid { .. })));
+        assert!(pv("xsd:unsignedInt", "-1").validate().is_err());
+
+        let mut gpvr = GetParameterValuesResponse::new(vec![]);
+        gpvr.add_param_value("Device.ManagementServer.PeriodicInformInterval", &ParamValue::UnsignedInt(300));
+        let xml = generate(&Envelope::new(None, vec![], vec![BodyElement::GetParameterValuesResponse(gpvr)])).unwrap();
+        match &parse(&xml).unwrap().body[0] {
+            BodyElement::GetParameterValuesResponse(r) => assert_eq!(
+                r.param_value("Device.ManagementServer.PeriodicInformInterval"),
+                Some(ParamValue::UnsignedInt(300))
+            ),
+            b => panic!("unexpected {b:?}"),
+        }
+    }
+
+    #[test]
+    fn event_codes() {
+        for code in [
+            "0 BOOTSTRAP", "1 BOOT", "2 PERIODIC", "3 SCHEDULED", "4 VALUE CHANGE", "5 KICKED",
+            "6 CONNECTION REQUEST", "7 TRANSFER COMPLETE", "8 DIAGNOSTICS COMPLETE",
+            "9 REQUEST DOWNLOAD", "10 AUTONOMOUS TRANSFER COMPLETE", "11 DU STATE CHANGE COMPLETE",
+            "12 AUTONOMOUS DU STATE CHANGE COMPLETE", "13 WAKEUP", "14 HEARTBEAT", "M Reboot",
+            "M ScheduleInform", "M Download", "M ScheduleDownload", "M Upload", "M ChangeDUState",
+        ] {
+            let ec = EventCode::from(code);
+            assert!(!matches!(ec, EventCode::Other(_)), "{code} is not mapped");
+            assert_eq!(ec.to_string(), code);
+        }
+        let vendor = EventCode::from("X 00D09E Reconfigured");
+        assert_eq!(vendor, EventCode::Vendor { oui: "00D09E".to_string(), event: "Reconfigured".to_string() });
+        assert_eq!(vendor.to_string(), "X 00D09E Reconfigured");
+        assert_eq!(EventCode::from("15 SOMETHING"), EventCode::Other("15 SOMETHING".to_string()));
+        assert!(EventCode::MDownload.is_multiple());
+
+        let inform = Inform {
+            event: vec![
+                EventStruct::new("0 BOOTSTRAP", ""),
+                EventStruct::new("7 TRANSFER COMPLETE", ""),
+                EventStruct::new("M Download", "fw-1"),
+                EventStruct::new("M Download", "cfg-2"),
+            ],
+            ..Inform::default()
+        };
+        assert!(inform.is_bootstrap());
+        assert!(inform.has_event(&EventCode::TransferComplete));
+        assert!(!inform.has_event(&EventCode::Boot));
+        assert_eq!(inform.command_keys_for(&EventCode::MDownload), vec!["fw-1", "cfg-2"]);
+        assert_eq!(inform.event_codes()[0], EventCode::Bootstrap);
+    }
+
+    #[cfg(feature = "transport")]
+    #[test]
+    fn http_transport() {
+        use std::io::BufReader;
+        use std::net::{TcpListener, TcpStream};
+
+        use crate::session::{AcsSession, CpeSession, SessionAction, SimulatedDevice};
+        use crate::transport::{cpe_request, http1, response_cookie, response_envelope, AcsServer};
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let url = format!("http://{addr}/acs");
+        let acs = std::thread::spawn(move || {
+            let mut server = AcsServer::new(|_: &Envelope| {
+                let mut session = AcsSession::new();
+                session.queue(BodyElement::GetParameterValues(GetParameterValues::new(&["Device.DeviceInfo.SoftwareVersion"])));
+                session
+            });
+            let (stream, _) = listener.accept().unwrap();
+            server.serve(&stream).unwrap();
+            assert_eq!(server.active(), 0);
+            server.take_finished()
+        });
+
+        let mut data_model = crate::datamodel::DataModel::new();
+        data_model.insert_parameter("Device.DeviceInfo.SoftwareVersion", "xsd:string", "1.0", false);
+        let mut cpe = CpeSession::new(DeviceId::new("The Company", "AA1234", "IAD_001", "S99998888"), SimulatedDevice::with_data_model(data_model));
+        let stream = TcpStream::connect(addr).unwrap();
+        let mut reader = BufReader::new(&stream);
+        let mut cookie = None;
+        let mut to_acs = Some(cpe.start().unwrap());
+        let mut statuses = vec![];
+        loop {
+            let request = cpe_request(&url, to_acs.as_ref(), cookie.as_deref(), &GenerateOptions::default()).unwrap();
+            if matches!(&to_acs, Some(e) if e.is_inform()) {
+                assert_eq!(transport::soap_action(&request).as_deref(), Some("Inform"));
+            }
+            http1::write_request(&mut (&stream), &request).unwrap();
+            let response = http1::read_response(&mut reader).unwrap();
+            statuses.push(response.status().as_u16());
+            cookie = response_cookie(&response).or(cookie);
+            match cpe.handle(response_envelope(&response).unwrap().as_ref()).unwrap() {
+                // an envelope without body elements is the empty POST
+                SessionAction::Send(e) => to_acs = if e.body.is_empty() { None } else { Some(e) },
+                SessionAction::End => break,
+            }
+        }
+        drop(reader);
+        stream.shutdown(std::net::Shutdown::Both).unwrap();
+
+        assert_eq!(statuses, vec![200, 200, 204]);
+        assert!(cookie.is_some());
+        let finished = acs.join().unwrap();
+        assert_eq!(finished.len(), 1);
+        assert!(finished[0].is_finished());
+        match &finished[0].exchanges()[0].response {
+            BodyElement::GetParameterValuesResponse(r) => assert_eq!(r.parameters[0].value.0, "1.0"),
+            r => panic!("expected a GetParameterValuesResponse, got {r:?}"),
+        }
+    }
+
+    #[cfg(feature = "transport")]
+    #[test]
+    fn http_transport_errors() {
+        use crate::session::AcsSession;
+        use crate::transport::{acs_response, envelope_from_body, session_cookie, AcsServer, SESSION_COOKIE};
+
+        let mut server = AcsServer::new(|_: &Envelope| AcsSession::new());
+        let get = http::Request::get("/acs").body(vec![]).unwrap();
+        assert_eq!(server.handle(&get).status(), 405);
+        let empty = http::Request::post("/acs").body(vec![]).unwrap();
+        assert_eq!(server.handle(&empty).status(), 400);
+        let garbage = http::Request::post("/acs").body(b"<nope".to_vec()).unwrap();
+        assert_eq!(server.handle(&garbage).status(), 400);
+        // a session must start with an Inform
+        let not_inform = generate(&Envelope::new(None, vec![], vec![BodyElement::GetRPCMethods(GetRPCMethods {})])).unwrap();
+        let request = http::Request::post("/acs").body(not_inform.into_bytes()).unwrap();
+        assert_eq!(server.handle(&request).status(), 400);
+        assert_eq!(server.active(), 0);
+        assert_eq!(server.take_finished().len(), 1);
+
+        assert_eq!(envelope_from_body(b" \r\n").unwrap(), None);
+        let request = http::Request::post("/acs")
+            .header("Cookie", format!("a=b; {SESSION_COOKIE}=1234"))
+            .body(())
+            .unwrap();
+        assert_eq!(session_cookie(&request).as_deref(), Some("1234"));
+        let response = acs_response(&crate::session::SessionAction::End, None, &GenerateOptions::default()).unwrap();
+        assert_eq!(response.status(), 204);
+        assert!(response.body().is_empty());
+        assert!(response.headers().get(http::header::CONTENT_LENGTH).is_none());
+        let mut written = vec![];
+        crate::transport::http1::write_response(&mut written, &response).unwrap();
+        assert!(!String::from_utf8(written).unwrap().to_ascii_lowercase().contains("content-length"));
+    }
+
+    #[cfg(feature = "transport")]
+    #[test]
+    fn http_limits() {
+        use std::io::{BufRead, BufReader, Write};
+        use std::net::{TcpListener, TcpStream};
+        use std::time::Duration;
+
+        use crate::error::Transport;
+        use crate::session::AcsSession;
+        use crate::transport::{http1, AcsServer};
+
+        // nothing is allocated for a body beyond the limit
+        let huge = b"POST /acs HTTP/1.1\r\nContent-Length: 18446744073709551615\r\n\r\n";
+        assert!(matches!(http1::read_request(&mut &huge[..]), Err(Transport::TooLarge(_))));
+        let chunked = b"POST /acs HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n4\r\nabcd\r\nffffffffffffffff\r\n";
+        assert!(matches!(http1::read_request(&mut &chunked[..]), Err(Transport::TooLarge(_))));
+        let small = b"POST /acs HTTP/1.1\r\nContent-Length: 5\r\n\r\nhello";
+        assert!(matches!(http1::read_request_with(&mut &small[..], 4), Err(Transport::TooLarge(4))));
+        assert_eq!(http1::read_request_with(&mut &small[..], 5).unwrap().unwrap().body(), b"hello");
+        let long_line = format!("POST /acs HTTP/1.1\r\nX-Long: {}\r\n\r\n", "a".repeat(http1::MAX_LINE_LENGTH));
+        assert!(matches!(http1::read_request(&mut long_line.as_bytes()), Err(Transport::LineTooLong(_))));
+        let many = format!("POST /acs HTTP/1.1\r\n{}\r\n", "X-Many: 1\r\n".repeat(http1::MAX_HEADERS + 1));
+        assert!(matches!(http1::read_request(&mut many.as_bytes()), Err(Transport::TooManyHeaders(_))));
+
+        // the ACS answers with a 413 before reading the body
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let acs = std::thread::spawn(move || {
+            let mut server = AcsServer::new(|_: &Envelope| AcsSession::new());
+            server.set_parse_options(ParseOptions::new().limits(ParseLimits::new().max_bytes(1024)));
+            let (stream, _) = listener.accept().unwrap();
+            server.serve(&stream)
+        });
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.write_all(b"POST /acs HTTP/1.1\r\nContent-Length: 1025\r\n\r\n").unwrap();
+        let mut status = String::new();
+        BufReader::new(&stream).read_line(&mut status).unwrap();
+        assert!(status.starts_with("HTTP/1.1 413"), "{status}");
+        assert!(matches!(acs.join().unwrap(), Err(Transport::TooLarge(1024))));
+
+        let inform = read_to_string("./tests/samples/inform_1.xml").unwrap().into_bytes();
+        let mut server = AcsServer::new(|_: &Envelope| AcsSession::new());
+        server.set_parse_options(ParseOptions::new().limits(ParseLimits::new().max_bytes(inform.len() - 1)));
+        assert_eq!(server.handle(&http::Request::post("/acs").body(inform.clone()).unwrap()).status(), 413);
+
+        // idle sessions end
+        let mut server = AcsServer::new(|_: &Envelope| AcsSession::new());
+        assert_eq!(server.handle(&http::Request::post("/acs").body(inform.clone()).unwrap()).status(), 200);
+        assert_eq!(server.expire_idle(), 0);
+        assert_eq!(server.active(), 1);
+        server.set_idle_timeout(Duration::ZERO);
+        assert_eq!(server.expire_idle(), 1);
+        assert_eq!(server.active(), 0);
+        assert_eq!(server.take_finished().len(), 1);
+    }
+
+    #[cfg(feature = "transport")]
+    #[test]
+    fn http_auth() {
+        use std::collections::HashMap;
+        use std::time::Duration;
+
+        use crate::error::Auth;
+        use crate::session::AcsSession;
+        use crate::transport::auth::{basic_authorization, device_username, Algorithm, Authenticator, ClientAuth, DigestChallenge};
+        use crate::transport::AcsServer;
+
+        let username = device_username(&DeviceId::new("The Company", "AA1234", "IAD_001", "S99998888"));
+        assert_eq!(username, "AA1234-S99998888");
+        let credentials: HashMap<String, String> = [(username.clone(), "secret".to_string())].into();
+
+        for algorithm in [Algorithm::Md5, Algorithm::Sha256] {
+            let mut server = Authenticator::new("acs").algorithm(algorithm);
+            let challenge = server.challenge();
+            assert_eq!(DigestChallenge::parse(&challenge).unwrap().algorithm, algorithm);
+            let mut client = ClientAuth::new(&username, "secret");
+            assert_eq!(client.authorization("POST", "/acs"), None);
+            client.challenge(&challenge).unwrap();
+            let first = client.authorization("POST", "/acs").unwrap();
+            assert_eq!(server.verify("POST", "/acs", Some(&first), &credentials), Ok(username.clone()));
+            // the nonce count goes up, so the same nonce can be used again
+            let second = client.authorization("POST", "/acs").unwrap();
+            assert_eq!(server.verify("POST", "/acs", Some(&second), &credentials), Ok(username.clone()));
+            assert_eq!(server.verify("POST", "/acs", Some(&first), &credentials), Err(Auth::Replay(1)));
+
+            let mut wrong = ClientAuth::new(&username, "guess");
+            wrong.challenge(&server.challenge()).unwrap();
+            let header = wrong.authorization("POST", "/acs").unwrap();
+            assert_eq!(server.verify("POST", "/acs", Some(&header), &credentials), Err(Auth::Rejected(username.clone())));
+        }
+
+        let mut server = Authenticator::new("acs");
+        assert_eq!(server.verify("POST", "/acs", None, &credentials), Err(Auth::Missing));
+        let mut other = Authenticator::new("acs");
+        let mut client = ClientAuth::new(&username, "secret");
+        client.challenge(&other.challenge()).unwrap();
+        let header = client.authorization("POST", "/acs").unwrap();
+        assert_eq!(server.verify("POST", "/acs", Some(&header), &credentials), Err(Auth::UnknownNonce));
+
+        let mut expiring = Authenticator::new("acs").nonce_lifetime(Duration::ZERO);
+        client.challenge(&expiring.challenge()).unwrap();
+        std::thread::sleep(Duration::from_millis(2));
+        let header = client.authorization("POST", "/acs").unwrap();
+        let err = expiring.verify("POST", "/acs", Some(&header), &credentials).unwrap_err();
+        assert_eq!(err, Auth::StaleNonce);
+        assert!(DigestChallenge::parse(&expiring.challenge_for(&err)).unwrap().stale);
+
+        // the digest is bound to the URI requested
+        let mut server = Authenticator::new("acs");
+        client.challenge(&server.challenge()).unwrap();
+        let header = client.authorization("POST", "/acs").unwrap();
+        assert_eq!(server.verify("POST", "/other", Some(&header), &credentials), Err(Auth::UriMismatch("/acs".to_string())));
+        assert_eq!(server.verify("POST", "http://acs.example.com/acs", Some(&header), &credentials), Ok(username.clone()));
+
+        // unanswered challenges do not pile up, the oldest nonce goes first
+        let mut capped = Authenticator::new("acs").max_nonces(2);
+        client.challenge(&capped.challenge()).unwrap();
+        let oldest = client.authorization("POST", "/acs").unwrap();
+        for _ in 0..2 {
+            client.challenge(&capped.challenge()).unwrap();
+        }
+        assert_eq!(capped.verify("POST", "/acs", Some(&oldest), &credentials), Err(Auth::UnknownNonce));
+        let newest = client.authorization("POST", "/acs").unwrap();
+        assert_eq!(capped.verify("POST", "/acs", Some(&newest), &credentials), Ok(username.clone()));
+
+        let basic = basic_authorization(&username, "secret");
+        assert!(matches!(server.verify("POST", "/acs", Some(&basic), &credentials), Err(Auth::Unsupported(_))));
+        let mut server = Authenticator::new("acs").allow_basic(true);
+        assert_eq!(server.verify("POST", "/acs", Some(&basic), &credentials), Ok(username.clone()));
+        client.challenge(&server.basic_challenge()).unwrap();
+        assert_eq!(client.authorization("POST", "/acs"), Some(basic));
+
+        // multi-byte characters where the scheme is expected are malformed, not a panic
+        assert!(server.verify("POST", "/acs", Some("Basic\u{e9}abc"), &credentials).is_err());
+        assert!(server.verify("POST", "/acs", Some("Bas\u{e9}"), &credentials).is_err());
+        assert!(client.challenge("Basi\u{e9}").is_err());
+        assert!(client.challenge("\u{e9}").is_err());
+
+        // the ACS only starts a session once the CPE authenticated
+        let mut acs = AcsServer::new(|_: &Envelope| AcsSession::new());
+        acs.require_authentication(Authenticator::new("acs"), credentials);
+        let inform = read_to_string("./tests/samples/inform_1.xml").unwrap().into_bytes();
+        let response = acs.handle(&http::Request::post("/acs").body(inform.clone()).unwrap());
+        assert_eq!(response.status(), 401);
+        assert_eq!(acs.active(), 0);
+        let mut client = ClientAuth::new(&username, "secret");
+        client.challenge(response.headers()["WWW-Authenticate"].to_str().unwrap()).unwrap();
+        let request = http::Request::post("/acs")
+            .header("Authorization", client.authorization("POST", "/acs").unwrap())
+            .body(inform)
+            .unwrap();
+        assert_eq!(acs.handle(&request).status(), 200);
+        assert_eq!(acs.active(), 1);
+    }
+
+    #[cfg(feature = "transport")]
+    #[test]
+    fn connection_request() {
+        use std::collections::HashMap;
+        use std::net::{TcpListener, UdpSocket};
+        use std::time::Duration;
+
+        use crate::error::{Auth, Transport};
+        use crate::transport::auth::{Authenticator, ClientAuth};
+        use crate::transport::connectionrequest::{
+            connection_request_url, send_connection_request, udp_connection_request_address, ConnectionRequestServer, UdpConnectionRequest,
+            UdpConnectionRequestListener,
+        };
+
+        let envelope = parse(&read_to_string("./tests/samples/inform_1.xml").unwrap()).unwrap();
+        let Some(BodyElement::Inform(inform)) = envelope.body.first() else {
+            panic!("expected an Inform");
+        };
+        assert!(connection_request_url(inform).is_some_and(|url| url.starts_with("http://")));
+        assert_eq!(udp_connection_request_address(inform), None);
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/cr", listener.local_addr().unwrap());
+        let cpe = std::thread::spawn(move || {
+            let credentials: HashMap<String, String> = [("acs".to_string(), "secret".to_string())].into();
+            let mut server = ConnectionRequestServer::new(Authenticator::new("cpe"), credentials);
+            let mut requested = vec![];
+            for _ in 0..2 {
+                let (stream, _) = listener.accept().unwrap();
+                server.serve(&stream).unwrap();
+                requested.push(server.take_request());
+            }
+            requested
+        });
+        let timeout = Duration::from_secs(5);
+        send_connection_request(&url, &mut ClientAuth::new("acs", "secret"), timeout).unwrap();
+        let err = send_connection_request(&url, &mut ClientAuth::new("acs", "guess"), timeout).unwrap_err();
+        assert!(matches!(err, error::Cwmp::TransportError(Transport::Status(401))));
+        assert_eq!(cpe.join().unwrap(), vec![true, false]);
+
+        let request = UdpConnectionRequest::new(1_700_000_000, 1, "acs", "secret");
+        assert!(request.verify("secret"));
+        assert!(!request.verify("guess"));
+        let upper = UdpConnectionRequest { sig: request.sig.to_ascii_uppercase(), ..request.clone() };
+        assert!(upper.verify("secret"));
+        let message = request.to_message("192.0.2.1:7547");
+        assert!(message.starts_with("GET http://192.0.2.1:7547?ts=1700000000&id=1&un=acs&cn="));
+        assert_eq!(UdpConnectionRequest::parse(message.as_bytes()).unwrap(), request);
+        assert!(UdpConnectionRequest::parse(b"GET / HTTP/1.1\r\n\r\n").is_err());
+
+        let mut cpe_listener = UdpConnectionRequestListener::new("acs", "secret");
+        assert_eq!(cpe_listener.accept(message.as_bytes()).unwrap(), request);
+        // a retransmission, or a replay
+        assert!(matches!(cpe_listener.accept(message.as_bytes()), Err(error::Cwmp::AuthError(Auth::Replay(1)))));
+        let forged = UdpConnectionRequest::new(1_700_000_001, 2, "acs", "guess");
+        assert!(matches!(cpe_listener.accept(forged.to_message("x").as_bytes()), Err(error::Cwmp::AuthError(Auth::Rejected(_)))));
+        let older = UdpConnectionRequest::new(1_699_999_999, 3, "acs", "secret");
+        assert!(cpe_listener.accept(older.to_message("x").as_bytes()).is_err());
+
+        let cpe_socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        cpe_socket.set_read_timeout(Some(timeout)).unwrap();
+        let address = cpe_socket.local_addr().unwrap().to_string();
+        let acs_socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let request = UdpConnectionRequest::new(1_700_000_100, 4, "acs", "secret");
+        forged.send(&acs_socket, &address).unwrap();
+        request.send(&acs_socket, &address).unwrap();
+        assert_eq!(cpe_listener.recv(&cpe_socket).unwrap(), request);
+    }
+
+    #[test]
+    fn envelope_validate() {
+        use crate::error::Violation;
+
+        let inform = parse(&read_to_string("./tests/samples/inform_1.xml").unwrap()).unwrap();
+        assert_eq!(inform.validate(), vec![]);
+
+        let gpv = BodyElement::GetParameterValues(GetParameterValues::new(&["Device."]));
+        let e = Envelope::new(Some(CwmpVersion::new(1, 2)), vec![HeaderElement::ID(ID::new(true, "1"))], vec![gpv.clone()]);
+        assert_eq!(e.validate(), vec![]);
+        let e = Envelope::new(Some(CwmpVersion::new(1, 2)), vec![], vec![gpv.clone(), gpv.clone()]);
+        assert_eq!(e.validate(), vec![Violation::BodyElementCount(2), Violation::MissingId("GetParameterValues".to_string())]);
+        assert_eq!(Envelope::new(None, vec![], vec![]).validate(), vec![Violation::BodyElementCount(0)]);
+
+        let e = Envelope::new(
+            Some(CwmpVersion::new(1, 2)),
+            vec![
+                HeaderElement::ID(ID::new(true, "1")),
+                HeaderElement::NoMoreRequests(NoMoreRequests::new(true, 1)),
+                HeaderElement::UseCWMPVersion(UseCWMPVersion::new(true, "1.4".to_string())),
+            ],
+            vec![gpv],
+        );
+        assert_eq!(
+            e.validate(),
+            vec![
+                Violation::NoMoreRequestsFromAcs("GetParameterValues".to_string()),
+                Violation::VersionMismatch { used: "1.4".to_string(), envelope: "1.2".to_string() },
+            ]
+        );
+        let e = Envelope::new(
+            Some(CwmpVersion::new(1, 0)),
+            vec![HeaderElement::HoldRequests(HoldRequests::new(true, true))],
+            vec![BodyElement::RebootResponse(RebootResponse {})],
+        );
+        assert_eq!(e.validate(), vec![Violation::HoldRequestsFromCpe("RebootResponse".to_string())]);
+
+        let xml = r#"<SOAP-ENV:Envelope xmlns:SOAP-ENV="http://schemas.xmlsoap.org/soap/envelope/" xmlns:cwmp="urn:dslforum-org:cwmp-1-0" xmlns:x="urn:x">
+            <SOAP-ENV:Header>
+              <cwmp:ID SOAP-ENV:mustUnderstand="1">1</cwmp:ID>
+              <x:Optional>a</x:Optional>
+              <x:Required SOAP-ENV:mustUnderstand="1">b</x:Required>
+            </SOAP-ENV:Header>
+            <SOAP-ENV:Body><cwmp:Reboot><CommandKey></CommandKey></cwmp:Reboot></SOAP-ENV:Body>
+          </SOAP-ENV:Envelope>"#;
+        let e = parse(xml).unwrap();
+        let HeaderElement::Unknown(optional) = &e.header[1] else { panic!("{:?}", e.header[1]) };
+        assert_eq!((optional.name.as_str(), optional.must_understand), ("Optional", false));
+        assert_eq!(optional.element.text(), "a");
+        assert_eq!(e.validate(), vec![Violation::MustUnderstand("Required".to_string())]);
+    }
+
+    #[test]
+    fn cwmp_version_negotiation() {
+        use crate::session::{AcsSession, CpeSession, SessionAction, SimulatedDevice};
+
+        let v = CwmpVersion::new;
+        assert!(v(1, 10) > v(1, 2) && v(2, 0) > v(1, 4));
+        assert_eq!(CwmpVersion::parse(" 1.2"), Some(v(1, 2)));
+        assert_eq!(CwmpVersion::parse_list("1.0, 1.2,x,1.4"), vec![v(1, 0), v(1, 2), v(1, 4)]);
+        assert_eq!(v(1, 4).to_string(), "1.4");
+        assert_eq!(negotiate(&CwmpVersion::KNOWN, &[v(1, 0), v(1, 2), v(2, 0)]), Some(v(1, 2)));
+        assert_eq!(negotiate(&[v(1, 0)], &[v(1, 4)]), None);
+        assert_eq!(CwmpVersion::introducing("ChangeDUState"), Some(v(1, 2)));
+        assert_eq!(CwmpVersion::introducing("ScheduleDownloadResponse"), Some(v(1, 1)));
+        assert_eq!(CwmpVersion::introducing("X_ABC_Diagnose"), None);
+        assert!(v(1, 0).supports("GetParameterValues") && !v(1, 0).supports("GetAllQueuedTransfers"));
+
+        let header = SupportedCWMPVersions::from_versions(false, &[v(1, 0), v(1, 4)]);
+        assert_eq!(header.value.0, "1.0,1.4");
+        assert_eq!(header.versions(), vec![v(1, 0), v(1, 4)]);
+
+        // the namespace is always cwmp_version, a disagreeing UseCWMPVersion is for validate to report
+        let e = Envelope::new(
+            Some(v(1, 0)),
+            vec![HeaderElement::UseCWMPVersion(UseCWMPVersion::new(true, "1.2".to_string()))],
+            vec![BodyElement::InformResponse(InformResponse::new(1))],
+        );
+        assert!(generate(&e).unwrap().contains("urn:dslforum-org:cwmp-1-0"));
+        assert!(e.validate().contains(&crate::error::Violation::VersionMismatch { used: "1.2".to_string(), envelope: "1.0".to_string() }));
+
+        let mut cpe = CpeSession::new(DeviceId::new("The Company", "AA1234", "IAD_001", "S99998888"), SimulatedDevice::new());
+        cpe.set_supported_versions(&[v(1, 0), v(1, 1), v(1, 2)]);
+        let inform = over_the_wire(&cpe.start().unwrap());
+        assert_eq!(inform.supported_cwmp_versions(), vec![v(1, 0), v(1, 1), v(1, 2)]);
+        let mut acs = AcsSession::new();
+        let reply = match acs.handle(Some(&inform)) {
+            Ok(SessionAction::Send(e)) => over_the_wire(&e),
+            r => panic!("expected an InformResponse, got {r:?}"),
+        };
+        assert_eq!(reply.use_cwmp_version(), Some(v(1, 2)));
+        assert_eq!(reply.cwmp_version, Some(v(1, 2)));
+        assert_eq!(acs.cwmp_version(), Some(&v(1, 2)));
+        match cpe.handle(Some(&reply)) {
+            Ok(SessionAction::Send(e)) => assert_eq!(e.cwmp_version, Some(v(1, 2))),
+            r => panic!("expected the empty POST, got {r:?}"),
+        }
+    }
+
+    #[test]
+    fn version_check() {
+        let v = CwmpVersion::new;
+        let gaqt = BodyElement::GetAllQueuedTransfers(GetAllQueuedTransfers {});
+        assert_eq!(gaqt.min_version(), v(1, 1));
+        assert_eq!(BodyElement::ChangeDUStateResponse(ChangeDUStateResponse).min_version(), v(1, 2));
+        assert_eq!(BodyElement::Reboot(Reboot::new("")).min_version(), v(1, 0));
+        let heartbeat = Inform::new(
+            DeviceId::new("The Company", "AA1234", "IAD_001", "S99998888"),
+            vec![EventStruct::new("14 HEARTBEAT", "")],
+            1,
+            gen_utc_date(2014, 11, 28, 12, 0, 9),
+            0,
+            vec![],
+        );
+        assert_eq!(BodyElement::Inform(heartbeat).min_version(), v(1, 4));
+        // so can the fault codes an element reports
+        let transfer = |code| BodyElement::TransferComplete(TransferComplete::new("k", FaultStruct::new(code, ""), None, None));
+        assert_eq!(transfer(0).min_version(), v(1, 0));
+        assert_eq!(transfer(9010).min_version(), v(1, 0));
+        assert_eq!(transfer(9018).min_version(), v(1, 1));
+        assert_eq!(FaultCode::InvalidUuidFormat.min_version(), v(1, 2));
+        assert_eq!(BodyElement::Fault(Fault::from_code(FaultCode::DeploymentUnitMismatch)).min_version(), v(1, 2));
+
+        // unchecked by default
+        let e = Envelope::new(Some(v(1, 0)), vec![HeaderElement::ID(ID::new(true, "1"))], vec![gaqt]);
+        let xml = generate(&e).unwrap();
+        assert_eq!(parse(&xml).unwrap(), e);
+
+        let mut out = vec![];
+        match e.write_to(&mut out, &GenerateOptions::new().check_version(true)) {
+            Err(GenerateError::Incompatible { element, required, version }) => {
+                assert_eq!((element.as_str(), required, version), ("GetAllQueuedTransfers", v(1, 1), v(1, 0)));
+            }
+            r => panic!("expected an Incompatible error, got {r:?}"),
+        }
+        assert!(out.is_empty());
+        match parse_with(xml.as_bytes(), &ParseOptions::new().check_version(true)) {
+            Err(error::Cwmp::StructuralError(e)) => {
+                assert_eq!(e.path, "/Envelope/Body/GetAllQueuedTransfers");
+                assert_eq!(e.kind, StructuralErrorKind::Incompatible { required: v(1, 1), version: v(1, 0) });
+            }
+            r => panic!("expected a StructuralError, got {r:?}"),
+        }
+
+        let e = Envelope { cwmp_version: Some(v(1, 2)), ..e };
+        assert!(e.write_to(&mut vec![], &GenerateOptions::new().check_version(true)).is_ok());
+        assert!(parse_with(generate(&e).unwrap().as_bytes(), &ParseOptions::new().check_version(true)).is_ok());
+    }
+
+    #[test]
+    fn rpc_metadata() {
+        use std::collections::HashMap;
+
+        let gpv = BodyElement::GetParameterValues(GetParameterValues::new(&["Device."]));
+        assert_eq!(gpv.rpc_name(), "GetParameterValues");
+        assert!(gpv.is_request() && !gpv.is_response());
+        assert_eq!(gpv.direction(), Direction::AcsToCpe);
+        assert_eq!(gpv.expected_response_kind(), Some(BodyElementKind::GetParameterValuesResponse));
+
+        let gpvr = BodyElement::GetParameterValuesResponse(GetParameterValuesResponse::default());
+        assert!(gpvr.is_response());
+        assert_eq!(gpvr.direction(), Direction::CpeToAcs);
+        assert_eq!(gpvr.expected_response_kind(), None);
+        assert!(gpvr.matches_request(&gpv));
+        assert!(!gpvr.matches_request(&BodyElement::Reboot(Reboot::new("k"))));
+        assert!(BodyElement::Fault(Fault::default()).matches_request(&gpv));
+        assert_eq!(BodyElement::Fault(Fault::default()).direction(), Direction::Both);
+
+        let inform = parse(&read_to_string("./tests/samples/inform_1.xml").unwrap()).unwrap();
+        assert_eq!(inform.body[0].kind(), BodyElementKind::Inform);
+        assert_eq!(inform.body[0].direction(), Direction::CpeToAcs);
+        assert_eq!(BodyElementKind::InformResponse.direction(), Direction::AcsToCpe);
+
+        for kind in BodyElementKind::ALL {
+            assert_eq!(BodyElementKind::from_name(kind.name()), Some(kind));
+            if let Some(response) = kind.expected_response() {
+                assert!(response.is_response());
+                if kind.direction() != Direction::Both {
+                    assert_ne!(response.direction(), kind.direction());
+                }
+            }
+        }
+        assert_eq!(BodyElementKind::from_name("Bogus"), None);
+
+        let mut counts: HashMap<BodyElementKind, usize> = HashMap::new();
+        *counts.entry(gpv.kind()).or_default() += 1;
+        assert_eq!(counts[&BodyElementKind::GetParameterValues], 1);
+    }
+
+    #[test]
+    fn builders() {
+        use crate::error::Validation;
+
+        let d = Download::builder()
+            .command_key("cmdkey")
+            .file_type(FileType::FirmwareUpgradeImage)
+            .url("http://example.com/fw.bin")
+            .file_size(1024)
+            .build()
+            .unwrap();
+        assert_eq!(d.command_key(), "cmdkey");
+        assert_eq!(d.url(), "http://example.com/fw.bin");
+        assert_eq!(d.file_size(), 1024);
+        assert_eq!(d, Download::new("cmdkey", "1 Firmware Upgrade Image", "http://example.com/fw.bin", "", "", 1024, "", 0, "", ""));
+        let e = parse(&generate(&Envelope::new(None, vec![], vec![BodyElement::Download(d.clone())])).unwrap()).unwrap();
+        assert_eq!(e.body, vec![BodyElement::Download(d)]);
+
+        assert_eq!(Download::builder().url("http://x").build(), Err(Validation::Missing("FileType".to_string())));
+        assert_eq!(
+            Download::builder().command_key(&"k".repeat(33)).file_type(FileType::FirmwareUpgradeImage).url("http://x").build(),
+            Err(Validation::TooLong { field: "CommandKey".to_string(), length: 33, max: 32 })
+        );
+        assert!(Upload::builder().file_type(FileType::VendorLogFile).url(&format!("http://{}", "x".repeat(250))).build().is_err());
+        assert_eq!(Upload::builder().file_type(FileType::VendorLogFile).url("http://x").delay_seconds(5).build().unwrap().delay_seconds, 5);
+
+        let tw = TimeWindow::new(0, 3600, "1 At Any Time", "", -1);
+        assert_eq!(
+            ScheduleDownload::builder().file_type(FileType::FirmwareUpgradeImage).url("http://x").build(),
+            Err(Validation::Count { field: "TimeWindowList".to_string(), count: 0, min: 1, max: 2 })
+        );
+        assert!(ScheduleDownload::builder().file_type(FileType::FirmwareUpgradeImage).url("http://x").time_window(tw).build().is_ok());
+
+        assert_eq!(ChangeDUState::builder().command_key("k").build(), Err(Validation::Missing("Operations".to_string())));
+        let c = ChangeDUState::builder().install(InstallOp::new("http://x/du", "", "", "", "")).build().unwrap();
+        assert_eq!(c.install_operations.len(), 1);
+        assert!(ChangeDUState::builder().uninstall(UninstallOp::new("", "", "")).build().is_err());
+    }
+
+    #[test]
+    fn file_types() {
+        use crate::error::Validation;
+
+        for ft in [
+            FileType::FirmwareUpgradeImage,
+            FileType::StoredFirmwareImage,
+            FileType::UploadVendorConfigurationFile,
+            FileType::VendorLogFile,
+            FileType::VendorConfigurationFileInstance(2),
+            FileType::VendorLogFileInstance(1),
+            FileType::Vendor { oui: "00D09E".to_string(), name: "Backup".to_string() },
+            FileType::Other("7 Something New".to_string()),
+        ] {
+            assert_eq!(FileType::from(ft.to_string().as_str()), ft);
+        }
+        assert_eq!(FileType::from("3 Vendor Configuration File"), FileType::VendorConfigurationFile);
+        assert_eq!(FileType::from("1 Vendor Configuration File"), FileType::UploadVendorConfigurationFile);
+        assert_eq!(FileType::from("4 Vendor Log File 1"), FileType::VendorLogFileInstance(1));
+        assert_eq!(FileType::from("4 Vendor Log File 01"), FileType::Other("4 Vendor Log File 01".to_string()));
+        assert!(FileType::WebContent.is_download() && !FileType::WebContent.is_upload());
+        assert!(FileType::VendorLogFile.is_upload() && !FileType::VendorLogFile.is_download());
+        assert!(!FileType::Other("7 Something New".to_string()).is_download());
+
+        assert_eq!(
+            Download::builder().file_type(FileType::VendorLogFile).url("http://x").build(),
+            Err(Validation::WrongFileType { file_type: "2 Vendor Log File".to_string(), rpc: "Download".to_string() })
+        );
+        assert!(Upload::builder().file_type(FileType::FirmwareUpgradeImage).url("http://x").build().is_err());
+
+        let rd = BodyElement::RequestDownload(RequestDownload::new("2 Web Content", vec![]));
+        let e = parse(&generate(&Envelope::new(None, vec![], vec![rd])).unwrap()).unwrap();
+        match e.body.first() {
+            Some(BodyElement::RequestDownload(rd)) => assert_eq!(rd.file_type(), FileType::WebContent),
+            other => panic!("expected a RequestDownload, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn fault_helpers() {
+        let f = Fault::invalid_parameter_name("Device.Bogus.");
+        assert_eq!(f, Fault::new("Client", "CWMP fault", 9005, "Invalid parameter name: Device.Bogus."));
+        assert_eq!(f.fault_code(), FaultCode::InvalidParameterName);
+        assert_eq!(Fault::method_not_supported(), Fault::new("Server", "CWMP fault", 9000, "Method not supported"));
+        assert_eq!(Fault::request_denied().faultcode.0, "Server");
+        assert_eq!(Fault::invalid_arguments().faultcode.0, "Client");
+        assert_eq!(Fault::from_code(FaultCode::AcsInvalidArguments).faultcode.0, "Client");
+        assert_eq!(Fault::from_code(FaultCode::AcsRetryRequest).faultcode.0, "Server");
+        assert_eq!(Fault::non_writable_parameter("Device.DeviceInfo.SerialNumber").detail.code, 9008);
+
+        let validation = Download::builder().url("http://x").build().unwrap_err();
+        let f = Fault::from(validation);
+        assert_eq!(f.fault_code(), FaultCode::InvalidArguments);
+        assert_eq!(f.detail.string.0, "the mandatory FileType is missing");
+        let f: Fault = error::Value::Invalid { xsd_type: "boolean".to_string(), value: "yes".to_string() }.into();
+        assert_eq!(f, Fault::new("Client", "CWMP fault", 9007, "\"yes\" is not a valid boolean"));
+    }
+
+    #[test]
+    fn unknown_elements() {
+        let xml = r#"<SOAP-ENV:Envelope xmlns:SOAP-ENV="http://schemas.xmlsoap.org/soap/envelope/" xmlns:cwmp="urn:dslforum-org:cwmp-1-0">
+            <SOAP-ENV:Header>
+              <cwmp:ID SOAP-ENV:mustUnderstand="1">1</cwmp:ID>
+              <v:Trace xmlns:v="urn:vendor" SOAP-ENV:mustUnderstand="1" v:level="2">on</v:Trace>
+            </SOAP-ENV:Header>
+            <SOAP-ENV:Body>
+              <cwmp:Reboot>
+                <CommandKey>k</CommandKey>
+                <X_ABC_Delay xmlns="urn:abc"><Seconds>5</Seconds><Reason>maintenance &amp; update</Reason></X_ABC_Delay>
+              </cwmp:Reboot>
+              <abc:X_ABC_Ping xmlns:abc="urn:abc"><Host>example.com</Host></abc:X_ABC_Ping>
+            </SOAP-ENV:Body>
+          </SOAP-ENV:Envelope>"#;
+        let e = parse(xml).unwrap();
+        let HeaderElement::Unknown(trace) = &e.header[1] else { panic!("{:?}", e.header[1]) };
+        assert!(trace.must_understand);
+        assert_eq!(trace.element.attribute("level"), Some("2"));
+        assert_eq!(trace.element.text(), "on");
+        let Some(BodyElement::Reboot(reboot)) = e.body.first() else { panic!("{:?}", e.body) };
+        assert_eq!(reboot.command_key.0, "k");
+        assert_eq!(reboot.extra[0].name.namespace.as_deref(), Some("urn:abc"));
+        assert_eq!(reboot.extra[0].child("Reason").map(XmlElement::text).as_deref(), Some("maintenance & update"));
+        let ping = &e.body[1];
+        assert_eq!((ping.rpc_name(), ping.is_request()), ("X_ABC_Ping", true));
+        assert_eq!(ping.direction(), Direction::Both);
+        let mut pong = XmlElement::new(XmlName::new("X_ABC_PingResponse"));
+        pong.children.push(XmlNode::Text("ok".to_string()));
+        assert!(BodyElement::Unknown(pong).matches_request(ping));
+
+        // generating keeps all of it
+        let again = parse(&generate(&e).unwrap()).unwrap();
+        assert_eq!(again, e);
+        assert_eq!(parse(&generate(&again).unwrap()).unwrap(), e);
+
+        // unknown elements in the structs of the arguments are kept there
+        let xml = r#"<SOAP-ENV:Envelope xmlns:SOAP-ENV="http://schemas.xmlsoap.org/soap/envelope/" xmlns:cwmp="urn:dslforum-org:cwmp-1-0">
+            <SOAP-ENV:Body>
+              <cwmp:Inform>
+                <DeviceId><Manufacturer>M</Manufacturer><OUI>O</OUI><ProductClass>P</ProductClass><SerialNumber>S</SerialNumber><X_ABC_Model>m</X_ABC_Model></DeviceId>
+                <Event><EventStruct><EventCode>1 BOOT</EventCode><CommandKey></CommandKey><X_ABC_Reason>power</X_ABC_Reason></EventStruct></Event>
+                <MaxEnvelopes>1</MaxEnvelopes><CurrentTime>2014-11-28T12:00:09Z</CurrentTime><RetryCount>0</RetryCount>
+                <ParameterList>
+                  <ParameterValueStruct><Name>Device.A</Name><Value>1</Value><X_ABC_Flag>1</X_ABC_Flag></ParameterValueStruct>
+                  <ParameterValueStruct><Name>Device.B</Name><Value>2</Value></ParameterValueStruct>
+                </ParameterList>
+              </cwmp:Inform>
+              <cwmp:TransferComplete><CommandKey>k</CommandKey><FaultStruct><FaultCode>0</FaultCode><FaultString></FaultString><X_ABC_Detail>none</X_ABC_Detail></FaultStruct></cwmp:TransferComplete>
+            </SOAP-ENV:Body>
+          </SOAP-ENV:Envelope>"#;
+        let e = parse(xml).unwrap();
+        let Some(BodyElement::Inform(inform)) = e.body.first() else { panic!("{:?}", e.body) };
+        assert_eq!(inform.device_id.serial_number.0, "S");
+        assert_eq!(inform.device_id.extra[0].text(), "m");
+        assert_eq!(inform.event[0].extra[0].name.local_name, "X_ABC_Reason");
+        assert_eq!(inform.parameter_list[0].extra[0].text(), "1");
+        assert!(inform.parameter_list[1].extra.is_empty() && inform.extra.is_empty());
+        let Some(BodyElement::TransferComplete(tc)) = e.body.get(1) else { panic!("{:?}", e.body) };
+        assert_eq!(tc.fault.extra[0].text(), "none");
+        assert_eq!(parse(&generate(&e).unwrap()).unwrap(), e);
+
+        // a vendor child in each of the other structs, and in body elements
+        // without arguments, which are then kept as unknown ones
+        let v = "<X_ABC_Vendor>v</X_ABC_Vendor>";
+        let cases = [
+            (true, format!("<cwmp:GetParameterNamesResponse><ParameterList><ParameterInfoStruct><Name>A</Name><Writable>1</Writable>{v}</ParameterInfoStruct></ParameterList></cwmp:GetParameterNamesResponse>")),
+            (true, format!("<cwmp:GetParameterAttributesResponse><ParameterList><ParameterAttributeStruct><Name>A</Name><Notification>0</Notification><AccessList></AccessList>{v}</ParameterAttributeStruct></ParameterList></cwmp:GetParameterAttributesResponse>")),
+            (true, format!("<cwmp:SetParameterAttributes><ParameterList><SetParameterAttributesStruct><Name>A</Name><NotificationChange>1</NotificationChange><Notification>2</Notification><AccessListChange>0</AccessListChange><AccessList></AccessList>{v}</SetParameterAttributesStruct></ParameterList></cwmp:SetParameterAttributes>")),
+            (true, format!("<cwmp:ChangeDUState><CommandKey>k</CommandKey><Operations><InstallOpStruct><URL>u</URL><UUID>1</UUID><Username></Username><Password></Password><ExecutionEnvRef>e</ExecutionEnvRef>{v}</InstallOpStruct><UninstallOpStruct><URL>u</URL><UUID>2</UUID><ExecutionEnvRef>e</ExecutionEnvRef>{v}</UninstallOpStruct><UpdateOpStruct><URL>u</URL><UUID>3</UUID><Username></Username><Password></Password><Version>2</Version>{v}</UpdateOpStruct></Operations></cwmp:ChangeDUState>")),
+            (true, format!("<cwmp:DUStateChangeComplete><CommandKey>k</CommandKey><Results><OpResultStruct><UUID>1</UUID><DeploymentUnitRef>d</DeploymentUnitRef><Version>1</Version><CurrentState>Installed</CurrentState><Resolved>1</Resolved><ExecutionUnitRefList></ExecutionUnitRefList><Fault><FaultStruct><FaultCode>0</FaultCode><FaultString></FaultString></FaultStruct></Fault>{v}</OpResultStruct></Results></cwmp:DUStateChangeComplete>")),
+            (true, format!("<cwmp:AutonomousDUStateChangeComplete><Results><AutonOpResultStruct><UUID>1</UUID><DeploymentUnitRef>d</DeploymentUnitRef><Version>1</Version><CurrentState>Installed</CurrentState><Resolved>1</Resolved><ExecutionUnitRefList></ExecutionUnitRefList><Fault><FaultStruct><FaultCode>0</FaultCode><FaultString></FaultString></FaultStruct></Fault><OperationPerformed>Install</OperationPerformed>{v}</AutonOpResultStruct></Results></cwmp:AutonomousDUStateChangeComplete>")),
+            (true, format!("<cwmp:GetQueuedTransfersResponse><TransferList><QueuedTransferStruct><CommandKey>k</CommandKey><State>1</State>{v}</QueuedTransferStruct></TransferList></cwmp:GetQueuedTransfersResponse>")),
+            (true, format!("<cwmp:GetAllQueuedTransfersResponse><TransferList><AllQueuedTransferStruct><CommandKey>k</CommandKey><State>1</State><IsDownload>1</IsDownload><FileType>1 Firmware Upgrade Image</FileType><FileSize>0</FileSize><TargetFileName></TargetFileName>{v}</AllQueuedTransferStruct></TransferList></cwmp:GetAllQueuedTransfersResponse>")),
+            (true, format!("<cwmp:RequestDownload><FileType>2 Web Content</FileType><FileTypeArg><ArgStruct><Name>Version</Name><Value>1</Value>{v}</ArgStruct></FileTypeArg></cwmp:RequestDownload>")),
+            (true, format!("<cwmp:ScheduleDownload><CommandKey>k</CommandKey><FileType>1 Firmware Upgrade Image</FileType><URL>u</URL><Username></Username><Password></Password><FileSize>0</FileSize><TargetFileName></TargetFileName><TimeWindowList><TimeWindowStruct><WindowStart>0</WindowStart><WindowEnd>60</WindowEnd><WindowMode>1 At Any Time</WindowMode><UserMessage></UserMessage><MaxRetries>-1</MaxRetries>{v}</TimeWindowStruct></TimeWindowList></cwmp:ScheduleDownload>")),
+            (true, format!("<cwmp:GetOptionsResponse><OptionList><OptionStruct><OptionName>o</OptionName><VoucherSN>1</VoucherSN><State>1</State><Mode>1</Mode><IsTransferable>0</IsTransferable>{v}</OptionStruct></OptionList></cwmp:GetOptionsResponse>")),
+            (true, format!("<SOAP-ENV:Fault><faultcode>Client</faultcode><faultstring>CWMP fault</faultstring><detail><cwmp:Fault><FaultCode>9003</FaultCode><FaultString>Invalid arguments</FaultString><SetParameterValuesFault><ParameterName>A</ParameterName><FaultCode>9007</FaultCode><FaultString>Invalid value</FaultString>{v}</SetParameterValuesFault>{v}</cwmp:Fault></detail>{v}</SOAP-ENV:Fault>")),
+            (false, format!("<cwmp:GetRPCMethods>{v}</cwmp:GetRPCMethods>")),
+            (false, format!("<cwmp:FactoryReset xmlns:x=\"urn:x\" x:id=\"1\">{v}<x:X_ABC_Other/></cwmp:FactoryReset>")),
+            (false, format!("<cwmp:RebootResponse>\n  {v}\n</cwmp:RebootResponse>")),
+        ];
+        for (typed, body) in cases {
+            let xml = format!(r#"<SOAP-ENV:Envelope xmlns:SOAP-ENV="http://schemas.xmlsoap.org/soap/envelope/" xmlns:cwmp="urn:dslforum-org:cwmp-1-0"><SOAP-ENV:Body>{body}</SOAP-ENV:Body></SOAP-ENV:Envelope>"#);
+            let mut e = parse(&xml).unwrap();
+            assert_eq!(!matches!(e.body[0], BodyElement::Unknown(_)), typed, "{body}");
+            let generated = generate(&e).unwrap();
+            assert_eq!(generated.matches(v).count(), body.matches(v).count(), "{generated}");
+            assert_eq!(parse(&generated).unwrap(), e, "{generated}");
+            if typed {
+                let slots = e.body[0].extra_slots();
+                assert_eq!(slots.iter().map(|s| s.len()).sum::<usize>(), body.matches(v).count(), "{body}");
+            }
+        }
+    }
+
+    #[test]
+    fn vendor_rpcs() {
+        #[derive(Debug, Default, Clone, PartialEq)]
+        struct Diagnostics {
+            target: String,
+            count: u32,
+        }
+        impl VendorRpc for Diagnostics {
+            fn name(&self) -> &str { "X_00D09E_Diagnostics" }
+            fn namespace(&self) -> Option<&str> { None }
+            fn start_handler(&mut self, _path: &[&str], _name: &xml::name::OwnedName, _attributes: &[xml::attribute::OwnedAttribute]) {}
+            fn characters(&mut self, path: &[&str], characters: &str) {
+                match path {
+                    ["X_00D09E_Diagnostics", "Target"] => self.target = characters.to_string(),
+                    ["X_00D09E_Diagnostics", "Count"] => self.count = characters.parse().unwrap_or(0),
+                    _ => {}
+                }
+            }
+            fn to_element(&self, has_cwmp: bool, options: &GenerateOptions) -> XmlElement {
+                let mut element = XmlElement::new(self.element_name(has_cwmp, options));
+                for (name, value) in [("Target", self.target.clone()), ("Count", self.count.to_string())] {
+                    let mut child = XmlElement::new(XmlName::new(name));
+                    child.children.push(XmlNode::Text(value));
+                    element.children.push(XmlNode::Element(child));
+                }
+                element
+            }
+        }
+
+        let xml = r#"<SOAP-ENV:Envelope xmlns:SOAP-ENV="http://schemas.xmlsoap.org/soap/envelope/" xmlns:cwmp="urn:dslforum-org:cwmp-1-2">
+            <SOAP-ENV:Header><cwmp:ID SOAP-ENV:mustUnderstand="1">1</cwmp:ID></SOAP-ENV:Header>
+            <SOAP-ENV:Body><cwmp:X_00D09E_Diagnostics><Target>192.0.2.1</Target><Count>3</Count></cwmp:X_00D09E_Diagnostics></SOAP-ENV:Body>
+          </SOAP-ENV:Envelope>"#;
+        // without registration it stays unknown
+        assert!(matches!(parse(xml).unwrap().body[0], BodyElement::Unknown(_)));
+
+        let options = ParseOptions::new().strict(true).vendor_rpcs(VendorRegistry::new().register::<Diagnostics>());
+        let e = parse_with(xml.as_bytes(), &options).unwrap();
+        let BodyElement::Vendor(rpc) = &e.body[0] else { panic!("{:?}", e.body) };
+        assert_eq!(rpc.downcast_ref::<Diagnostics>(), Some(&Diagnostics { target: "192.0.2.1".to_string(), count: 3 }));
+        assert_eq!((e.body[0].rpc_name(), e.body[0].is_request()), ("X_00D09E_Diagnostics", true));
+        assert_eq!(e.body[0].clone(), e.body[0]);
+        assert_eq!(parse_with(generate(&e).unwrap().as_bytes(), &options).unwrap(), e);
+        let mut out = vec![];
+        let mut writer = xml::EmitterConfig::new()
+            .write_document_declaration(false)
+            .create_writer(&mut out as &mut dyn std::io::Write);
+        rpc.generate(&mut writer, false, &GenerateOptions::default()).unwrap();
+        drop(writer);
+        assert_eq!(String::from_utf8(out).unwrap(), "<X_00D09E_Diagnostics><Target>192.0.2.1</Target><Count>3</Count></X_00D09E_Diagnostics>");
+        // serialized as the element it generates, and deserialized as an unknown body element
+        #[cfg(feature = "serde")]
+        {
+            let back = serde_json::from_str::<Envelope>(&serde_json::to_string(&e).unwrap()).unwrap();
+            let BodyElement::Unknown(element) = &back.body[0] else { panic!("{:?}", back.body) };
+            assert_eq!(element, &rpc.to_element(true, &GenerateOptions::default()));
+            assert_eq!(generate(&back).unwrap(), generate(&e).unwrap());
+        }
+        // a vendor namespace must match
+        let other = xml.replace("cwmp:X_00D09E_Diagnostics", "v:X_00D09E_Diagnostics").replace("<SOAP-ENV:Body>", r#"<SOAP-ENV:Body xmlns:v="urn:v">"#);
+        assert!(matches!(parse_with(other.as_bytes(), &ParseOptions::new().vendor_rpcs(options.vendor_rpcs.clone())).unwrap().body[0], BodyElement::Unknown(_)));
+        // and is not taken for the registered RPC by strict parsing either
+        assert!(parse_with(other.as_bytes(), &options).is_err());
+    }
+
+    #[cfg(feature = "borrowed")]
+    #[test]
+    fn inform_ref() {
+        use std::borrow::Cow;
+
+        let sample = read_to_string("./tests/samples/inform_1.xml").unwrap();
+        let inform = parse_inform_ref(&sample).unwrap().unwrap();
+        let Some(BodyElement::Inform(owned)) = parse(&sample).unwrap().body.first().cloned() else { panic!() };
+        assert_eq!(inform.to_inform(), owned);
+        assert!(matches!(inform.device_id.serial_number, Cow::Borrowed(_)));
+        assert_eq!(inform.event_codes(), owned.event_codes());
+
+        let xml = r#"<SOAP-ENV:Envelope xmlns:SOAP-ENV="http://schemas.xmlsoap.org/soap/envelope/" xmlns:cwmp="urn:dslforum-org:cwmp-1-2">
+            <SOAP-ENV:Header><cwmp:ID SOAP-ENV:mustUnderstand="1">42</cwmp:ID></SOAP-ENV:Header>
+            <SOAP-ENV:Body><cwmp:Inform>
+              <DeviceId><Manufacturer>A &amp; B</Manufacturer><OUI/></DeviceId>
+              <ParameterList><ParameterValueStruct><Name>Device.X</Name><Value xsi:type="xsd:string">&#65;<![CDATA[<b>]]></Value></ParameterValueStruct></ParameterList>
+            </cwmp:Inform></SOAP-ENV:Body>
+          </SOAP-ENV:Envelope>"#;
+        let inform = parse_inform_ref(xml).unwrap().unwrap();
+        assert_eq!((inform.id.as_deref(), inform.cwmp_version.clone()), (Some("42"), Some(CwmpVersion::new(1, 2))));
+        assert_eq!(inform.device_id.manufacturer, "A & B");
+        assert_eq!(inform.parameter_value("Device.X"), Some("A<b>"));
+        assert_eq!(inform.parameter_list[0].r#type, "xsd:string");
+
+        let reboot = generate(&Envelope::new(None, vec![], vec![BodyElement::Reboot(Reboot::new("k"))])).unwrap();
+        assert_eq!(parse_inform_ref(&reboot).unwrap(), None);
+        assert!(matches!(parse_inform_ref("<Envelope><Body>"), Err(error::Cwmp::XmlError(_))));
+        assert!(parse_inform_ref("<Envelope>&bogus;</Envelope>").is_err());
+    }
+
+    #[test]
+    fn incremental_parsing() {
+        let sample = read_to_string("./tests/samples/inform_1.xml").unwrap();
+        let expected = parse(&sample).unwrap();
+        for size in [1, 7, 64, 4096] {
+            let mut parser = EnvelopeParser::new();
+            for chunk in sample.as_bytes().chunks(size) {
+                parser.feed(chunk).unwrap();
+            }
+            assert_eq!(parser.received(), sample.len());
+            assert_eq!(parser.finish().unwrap(), expected, "chunks of {size}");
+        }
+        // a '>' in an attribute value, a comment and CDATA are no place to stop
+        let xml = r#"<SOAP-ENV:Envelope xmlns:SOAP-ENV="http://schemas.xmlsoap.org/soap/envelope/" xmlns:cwmp="urn:dslforum-org:cwmp-1-0"><!-- a > b --><SOAP-ENV:Body><cwmp:Reboot x="a>b"><CommandKey><![CDATA[k>]]></CommandKey></cwmp:Reboot></SOAP-ENV:Body></SOAP-ENV:Envelope>"#;
+        let mut parser = EnvelopeParser::default();
+        for chunk in xml.as_bytes().chunks(3) {
+            parser.feed(chunk).unwrap();
+        }
+        assert_eq!(parser.finish().unwrap(), parse(xml).unwrap());
+        // malformed input fails before the end of the envelope
+        let mut parser = EnvelopeParser::new();
+        assert!(matches!(parser.feed(b"<a><b></a>"), Err(error::Cwmp::ParseError(_))));
+        let mut parser = EnvelopeParser::new();
+        parser.feed(&sample.as_bytes()[..100]).unwrap();
+        assert!(matches!(parser.finish(), Err(error::Cwmp::ParseError(_))));
+        let mut parser = EnvelopeParser::new().max_size(100);
+        assert!(matches!(parser.feed(sample.as_bytes()), Err(error::Cwmp::LimitExceeded(error::Limit::Bytes(100)))));
+
+        #[cfg(feature = "tokio")]
+        {
+            let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+            let envelope = runtime.block_on(EnvelopeParser::new().read_from(sample.as_bytes())).unwrap();
+            assert_eq!(envelope, expected);
+        }
+    }
+
     #[test]
     fn test_parse_fails() {
         let sample = read_to_string("./tests/samples/bogus_inform_1.xml").unwrap();
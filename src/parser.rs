@@ -0,0 +1,317 @@
+use std::collections::VecDeque;
+use std::io::{self, Read};
+
+use xml::common::Position;
+use xml::reader::EventReader;
+
+use crate::error::{self, Limit};
+use crate::protocol::{Envelope, ParseLimits, ParseOptions, State};
+use crate::{dispatch, parser_config};
+
+// the bytes handed to the reader and not read yet
+#[derive(Default)]
+struct Source {
+    data: VecDeque<u8>,
+    // set when the reader asked for more than it was handed
+    starved: bool,
+}
+
+impl Read for Source {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.data.read(buf)?;
+        if n == 0 && !buf.is_empty() {
+            self.starved = true;
+        }
+        Ok(n)
+    }
+}
+
+// where the received bytes end in the markup. The reader can only be
+// stopped right after a tag, anywhere else running out of input is an error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scan {
+    Text,
+    TagOpen,
+    Tag { quote: Option<u8>, end: bool },
+    // after "<!", counting the dashes of a comment
+    Bang(u8),
+    Comment(u8),
+    CData(u8),
+    Pi(bool),
+    Declaration,
+}
+
+impl Scan {
+    // the state after `b`, and whether a start or end tag closed with it
+    fn next(self, b: u8) -> (Scan, Option<bool>) {
+        let state = match (self, b) {
+            (Scan::Text, b'<') => Scan::TagOpen,
+            (Scan::Text, _) => Scan::Text,
+            (Scan::TagOpen, b'!') => Scan::Bang(0),
+            (Scan::TagOpen, b'?') => Scan::Pi(false),
+            (Scan::TagOpen, b'/') => Scan::Tag {
+                quote: None,
+                end: true,
+            },
+            (Scan::TagOpen | Scan::Declaration, b'>') => Scan::Text,
+            (Scan::TagOpen, _) => Scan::Tag {
+                quote: None,
+                end: false,
+            },
+            (Scan::Tag { quote: None, end }, b'>') => return (Scan::Text, Some(end)),
+            (Scan::Tag { quote: None, end }, b'"' | b'\'') => Scan::Tag {
+                quote: Some(b),
+                end,
+            },
+            (
+                Scan::Tag {
+                    quote: Some(q),
+                    end,
+                },
+                _,
+            ) if q == b => Scan::Tag { quote: None, end },
+            (Scan::Tag { .. }, _) => self,
+            (Scan::Bang(0), b'-') => Scan::Bang(1),
+            (Scan::Bang(0), b'[') => Scan::CData(0),
+            (Scan::Bang(1), b'-') => Scan::Comment(0),
+            (Scan::Bang(_) | Scan::Declaration, _) => Scan::Declaration,
+            (Scan::Comment(2), b'>') | (Scan::CData(2), b'>') | (Scan::Pi(true), b'>') => {
+                Scan::Text
+            }
+            (Scan::Comment(n), b'-') => Scan::Comment((n + 1).min(2)),
+            (Scan::Comment(_), _) => Scan::Comment(0),
+            (Scan::CData(n), b']') => Scan::CData((n + 1).min(2)),
+            (Scan::CData(_), _) => Scan::CData(0),
+            (Scan::Pi(_), _) => Scan::Pi(b == b'?'),
+        };
+        (state, None)
+    }
+}
+
+// the message of the reader when it stops at one of its own limits, which
+// it does not tell apart
+const READER_LIMIT: &str =
+    "This document is larger/more complex than allowed by the parser's configuration";
+
+// a source that follows where the reader is in the markup, to tell which of
+// the `ParseLimits` it stopped at. The reader takes a byte at a time.
+pub(crate) struct Scanned<R> {
+    pub(crate) inner: R,
+    scan: Scan,
+    // the attribute values of the tag read so far
+    values: usize,
+}
+
+impl<R> Scanned<R> {
+    pub(crate) fn new(inner: R) -> Self {
+        Scanned {
+            inner,
+            scan: Scan::Text,
+            values: 0,
+        }
+    }
+
+    // the error to return for an error of the reader
+    pub(crate) fn error(&self, e: xml::reader::Error, limits: &ParseLimits) -> error::Cwmp {
+        if e.msg() != READER_LIMIT {
+            return error::Cwmp::ParseError(e);
+        }
+        let limit = match self.scan {
+            Scan::Tag { .. } if self.values > limits.max_attributes => {
+                Limit::Attributes(limits.max_attributes)
+            }
+            Scan::Tag { .. } => Limit::AttributeLength(limits.max_attribute_length),
+            _ => Limit::TextLength(limits.max_text_length),
+        };
+        limit.into()
+    }
+}
+
+impl<R: Read> Read for Scanned<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        for &b in &buf[..n] {
+            let (scan, _) = self.scan.next(b);
+            match (self.scan, scan) {
+                (_, Scan::TagOpen) => self.values = 0,
+                (Scan::Tag { quote: Some(_), .. }, Scan::Tag { quote: None, .. }) => {
+                    self.values += 1;
+                }
+                _ => {}
+            }
+            self.scan = scan;
+        }
+        Ok(n)
+    }
+}
+
+/// Parses an envelope handed over chunk by chunk, as it is received, like
+/// the body of an HTTP request
+///
+/// Every complete element is parsed as soon as it arrives, so `feed` fails
+/// as soon as the envelope is malformed, or goes beyond its `ParseLimits`. The
+/// envelope is expected in UTF-8, as TR-069 requires.
+///
+/// ```
+/// let xml = std::fs::read("tests/samples/inform_1.xml").unwrap();
+/// let mut parser = cwmp::EnvelopeParser::new().max_size(64 * 1024);
+/// for chunk in xml.chunks(100) {
+///     parser.feed(chunk).unwrap();
+/// }
+/// let envelope = parser.finish().unwrap();
+/// assert_eq!(envelope.body.len(), 1);
+/// ```
+pub struct EnvelopeParser {
+    reader: EventReader<Scanned<Source>>,
+    state: State,
+    // received, but not handed to the reader as it does not end with a tag
+    pending: Vec<u8>,
+    scan: Scan,
+    root_started: bool,
+    received: usize,
+    done: bool,
+}
+
+impl EnvelopeParser {
+    #[must_use]
+    pub fn new() -> Self {
+        EnvelopeParser::with_options(ParseOptions::default())
+    }
+
+    #[must_use]
+    pub fn with_options(options: ParseOptions) -> Self {
+        EnvelopeParser {
+            reader: parser_config(&options.limits)
+                .ignore_end_of_stream(true)
+                .create_reader(Scanned::new(Source::default())),
+            state: State::with_options(options),
+            pending: vec![],
+            scan: Scan::Text,
+            root_started: false,
+            received: 0,
+            done: false,
+        }
+    }
+
+    /// Refuse envelopes of more than `max_size` bytes, the `max_bytes` of
+    /// the `ParseLimits`
+    #[must_use]
+    pub fn max_size(mut self, max_size: usize) -> Self {
+        self.state.options.limits.max_bytes = max_size;
+        self
+    }
+
+    /// The number of bytes fed so far
+    #[must_use]
+    pub fn received(&self) -> usize {
+        self.received
+    }
+
+    /// Parse the next chunk of the envelope, as far as it goes
+    ///
+    /// # Errors
+    ///
+    /// Returns `error::Cwmp::LimitExceeded` once more than `max_size` bytes
+    /// are fed, and the errors of `parse_with` as soon as the envelope received
+    /// so far shows them. The parser is of no use after an error.
+    pub fn feed(&mut self, chunk: &[u8]) -> Result<(), error::Cwmp> {
+        self.received += chunk.len();
+        let max = self.state.options.limits.max_bytes;
+        if self.received > max {
+            return Err(error::Limit::Bytes(max).into());
+        }
+        if self.done {
+            return Ok(());
+        }
+        // the reader gets everything up to the last tag, once the root element started
+        let mut cut = None;
+        for (i, &b) in chunk.iter().enumerate() {
+            let (scan, tag_end) = self.scan.next(b);
+            self.scan = scan;
+            if let Some(end) = tag_end {
+                self.root_started |= !end;
+                if self.root_started {
+                    cut = Some(self.pending.len() + i + 1);
+                }
+            }
+        }
+        self.pending.extend_from_slice(chunk);
+        if let Some(cut) = cut {
+            self.reader
+                .source_mut()
+                .inner
+                .data
+                .extend(self.pending.drain(..cut));
+            self.pull(true)?;
+        }
+        Ok(())
+    }
+
+    /// Parse the rest of the envelope, now that all of it has been fed
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of `parse_with`, like a `ParseError` if the
+    /// envelope is incomplete
+    pub fn finish(mut self) -> Result<Envelope, error::Cwmp> {
+        if !self.done {
+            let rest = std::mem::take(&mut self.pending);
+            self.reader.source_mut().inner.data.extend(rest);
+            self.pull(false)?;
+        }
+        Ok(self.state.envelope)
+    }
+
+    // hand the events of the reader to the state until the end of the
+    // document, or until the input runs out if `more` is to come
+    fn pull(&mut self, more: bool) -> Result<(), error::Cwmp> {
+        loop {
+            let e = self.reader.next();
+            self.state.position = self.reader.position();
+            match e {
+                Ok(event) => {
+                    if dispatch(&mut self.state, &event)? {
+                        self.done = true;
+                        return Ok(());
+                    }
+                }
+                // running out of input right after a tag is not an error yet
+                Err(_) if more && self.reader.source().inner.starved => {
+                    self.reader.source_mut().inner.starved = false;
+                    return Ok(());
+                }
+                Err(e) => return Err(self.reader.source().error(e, &self.state.options.limits)),
+            }
+        }
+    }
+}
+
+impl Default for EnvelopeParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl EnvelopeParser {
+    /// Parse an envelope read from `reader` until its end
+    ///
+    /// # Errors
+    ///
+    /// Returns `error::Cwmp::Io` if reading fails, and the errors of `feed`
+    /// and `finish`
+    pub async fn read_from<R: tokio::io::AsyncRead + Unpin>(
+        mut self,
+        mut reader: R,
+    ) -> Result<Envelope, error::Cwmp> {
+        use tokio::io::AsyncReadExt;
+
+        let mut buf = vec![0; 8192];
+        loop {
+            match reader.read(&mut buf).await? {
+                0 => return self.finish(),
+                n => self.feed(&buf[..n])?,
+            }
+        }
+    }
+}
@@ -4,12 +4,14 @@ use std::io::Write;
 use quickcheck::{Arbitrary, Gen};
 use xml::writer::XmlEvent;
 
-use super::{cwmp_prefix, write_simple, GenerateError, XmlSafeString};
+use super::{cwmp_prefix, write_simple, GenerateError, GenerateOptions, XmlElement, XmlSafeString};
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AddObject {
     pub object_name: XmlSafeString,
     pub parameter_key: XmlSafeString,
+    pub extra: Vec<XmlElement>,
 }
 
 impl AddObject {
@@ -18,6 +20,7 @@ impl AddObject {
         AddObject {
             object_name: object_name.into(),
             parameter_key: parameter_key.into(),
+            extra: vec![],
         }
     }
 
@@ -30,12 +33,16 @@ impl AddObject {
         &self,
         writer: &mut xml::EventWriter<W>,
         has_cwmp: bool,
+        options: &GenerateOptions,
     ) -> Result<(), GenerateError> {
         writer.write(XmlEvent::start_element(
-            &cwmp_prefix(has_cwmp, "AddObject")[..],
+            &cwmp_prefix(options, has_cwmp, "AddObject")[..],
         ))?;
         write_simple(writer, "ObjectName", self.object_name.0.as_ref())?;
         write_simple(writer, "ParameterKey", self.parameter_key.0.as_ref())?;
+        for e in &self.extra {
+            e.generate(writer)?;
+        }
         writer.write(XmlEvent::end_element())?;
 
         Ok(())
@@ -68,6 +75,7 @@ impl Arbitrary for AddObject {
                 .map(|(o, p)| AddObject {
                     object_name: o,
                     parameter_key: p,
+                    extra: vec![],
                 }),
         )
     }
@@ -4,12 +4,14 @@ use std::io::Write;
 use quickcheck::{Arbitrary, Gen};
 use xml::writer::XmlEvent;
 
-use super::{cwmp_prefix, GenerateError, XmlSafeString};
+use super::{cwmp_prefix, GenerateError, GenerateOptions, XmlElement, XmlSafeString};
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AddObjectResponse {
     pub instance_number: u32,
     pub status: XmlSafeString,
+    pub extra: Vec<XmlElement>,
 }
 
 impl AddObjectResponse {
@@ -18,6 +20,7 @@ impl AddObjectResponse {
         AddObjectResponse {
             instance_number,
             status: status.into(),
+            extra: vec![],
         }
     }
 
@@ -30,9 +33,10 @@ impl AddObjectResponse {
         &self,
         writer: &mut xml::EventWriter<W>,
         has_cwmp: bool,
+        options: &GenerateOptions,
     ) -> Result<(), GenerateError> {
         writer.write(XmlEvent::start_element(
-            &cwmp_prefix(has_cwmp, "AddObjectResponse")[..],
+            &cwmp_prefix(options, has_cwmp, "AddObjectResponse")[..],
         ))?;
 
         writer.write(XmlEvent::start_element("InstanceNumber"))?;
@@ -42,6 +46,9 @@ impl AddObjectResponse {
         writer.write(self.status.0.as_ref())?;
         writer.write(XmlEvent::end_element())?;
 
+        for e in &self.extra {
+            e.generate(writer)?;
+        }
         writer.write(XmlEvent::end_element())?;
 
         Ok(())
@@ -67,6 +74,7 @@ impl Arbitrary for AddObjectResponse {
         Self {
             instance_number: u32::arbitrary(g),
             status: XmlSafeString::arbitrary(g),
+            extra: vec![],
         }
     }
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
@@ -76,6 +84,7 @@ impl Arbitrary for AddObjectResponse {
                 .map(|(i, s)| AddObjectResponse {
                     instance_number: i,
                     status: s,
+                    extra: vec![],
                 }),
         )
     }
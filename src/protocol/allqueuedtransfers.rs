@@ -1,9 +1,10 @@
 #[cfg(test)]
 use quickcheck::{Arbitrary, Gen};
 
-use super::XmlSafeString;
+use super::{FileType, XmlElement, XmlSafeString};
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AllQueuedTransfers {
     pub command_key: XmlSafeString,
     pub state: XmlSafeString,
@@ -11,6 +12,11 @@ pub struct AllQueuedTransfers {
     pub file_type: XmlSafeString,
     pub file_size: u32,
     pub target_filename: XmlSafeString,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub extra: Vec<XmlElement>,
 }
 
 impl AllQueuedTransfers {
@@ -30,8 +36,13 @@ impl AllQueuedTransfers {
             file_type: file_type.into(),
             file_size,
             target_filename: target_filename.into(),
+            extra: vec![],
         }
     }
+    #[must_use]
+    pub fn file_type(&self) -> FileType {
+        FileType::from(self.file_type.0.as_ref())
+    }
 }
 
 #[cfg(test)]
@@ -44,6 +55,7 @@ impl Arbitrary for AllQueuedTransfers {
             file_type: XmlSafeString::arbitrary(g),
             file_size: u32::arbitrary(g),
             target_filename: XmlSafeString::arbitrary(g),
+            extra: vec![],
         }
     }
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
@@ -64,6 +76,7 @@ impl Arbitrary for AllQueuedTransfers {
                     file_type: ft,
                     file_size: fs,
                     target_filename: tf,
+                    extra: vec![],
                 }),
         )
     }
@@ -1,12 +1,18 @@
 #[cfg(test)]
 use quickcheck::{Arbitrary, Gen};
 
-use super::XmlSafeString;
+use super::{XmlElement, XmlSafeString};
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ArgStruct {
     pub name: XmlSafeString,
     pub value: XmlSafeString,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub extra: Vec<XmlElement>,
 }
 
 impl ArgStruct {
@@ -15,6 +21,7 @@ impl ArgStruct {
         ArgStruct {
             name: name.into(),
             value: value.into(),
+            extra: vec![],
         }
     }
 }
@@ -25,13 +32,18 @@ impl Arbitrary for ArgStruct {
         Self {
             name: XmlSafeString::arbitrary(g),
             value: XmlSafeString::arbitrary(g),
+            extra: vec![],
         }
     }
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
         Box::new(
             (self.name.clone(), self.value.clone())
                 .shrink()
-                .map(|(n, v)| ArgStruct { name: n, value: v }),
+                .map(|(n, v)| ArgStruct {
+                    name: n,
+                    value: v,
+                    extra: vec![],
+                }),
         )
     }
 }
@@ -5,17 +5,25 @@ use chrono::{DateTime, Utc};
 use quickcheck::{Arbitrary, Gen};
 use xml::writer::XmlEvent;
 
-use super::{write_fault, write_simple, AutonOpResult, GenerateError};
+use super::{
+    cwmp_array_type, cwmp_prefix, write_array_start, write_fault, write_simple, AutonOpResult,
+    GenerateError, GenerateOptions, XmlElement,
+};
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AutonomousDUStateChangeComplete {
     pub results: Vec<AutonOpResult>,
+    pub extra: Vec<XmlElement>,
 }
 
 impl AutonomousDUStateChangeComplete {
     #[must_use]
     pub fn new(results: Vec<AutonOpResult>) -> Self {
-        AutonomousDUStateChangeComplete { results }
+        AutonomousDUStateChangeComplete {
+            results,
+            extra: vec![],
+        }
     }
     /// Generate XML for `AutonomousDUStateChangeComplete`
     ///     
@@ -26,19 +34,14 @@ impl AutonomousDUStateChangeComplete {
         &self,
         writer: &mut xml::EventWriter<W>,
         has_cwmp: bool,
+        options: &GenerateOptions,
     ) -> Result<(), GenerateError> {
-        let ss = if has_cwmp {
-            writer.write(XmlEvent::start_element(
-                "cwmp:AutonomousDUStateChangeComplete",
-            ))?;
-            format!("cwmp:AutonOpResultStruct[{}]", self.results.len())
-        } else {
-            writer.write(XmlEvent::start_element("AutonomousDUStateChangeComplete"))?;
-
-            format!("AutonOpResultStruct[{}]", self.results.len())
-        };
+        writer.write(XmlEvent::start_element(
+            &cwmp_prefix(options, has_cwmp, "AutonomousDUStateChangeComplete")[..],
+        ))?;
+        let ss = cwmp_array_type(options, has_cwmp, "AutonOpResultStruct", self.results.len());
 
-        writer.write(XmlEvent::start_element("Results").attr("SOAP-ENC:arrayType", &ss[..]))?;
+        write_array_start(writer, options, "Results", &ss)?;
 
         for r in &self.results {
             writer.write(XmlEvent::start_element("AutonOpResultStruct"))?;
@@ -71,6 +74,9 @@ impl AutonomousDUStateChangeComplete {
                 "OperationPerformed",
                 r.operation_performed.0.as_ref(),
             )?;
+            for e in &r.extra {
+                e.generate(writer)?;
+            }
 
             // AutonOpResultStruct
             writer.write(XmlEvent::end_element())?;
@@ -79,6 +85,9 @@ impl AutonomousDUStateChangeComplete {
         writer.write(XmlEvent::end_element())?;
 
         // cwmp:AutonomousDUStateChangeComplete
+        for e in &self.extra {
+            e.generate(writer)?;
+        }
         writer.write(XmlEvent::end_element())?;
 
         Ok(())
@@ -111,12 +120,12 @@ impl AutonomousDUStateChangeComplete {
                             e.execution_unit_ref_list = characters.into();
                         }
                         "StartTime" => {
-                            if let Ok(dt) = characters.parse::<DateTime<Utc>>() {
+                            if let Ok(dt) = characters.trim().parse::<DateTime<Utc>>() {
                                 e.start_time = Some(dt);
                             }
                         }
                         "CompleteTime" => {
-                            if let Ok(dt) = characters.parse::<DateTime<Utc>>() {
+                            if let Ok(dt) = characters.trim().parse::<DateTime<Utc>>() {
                                 e.complete_time = Some(dt);
                             }
                         }
@@ -131,7 +140,7 @@ impl AutonomousDUStateChangeComplete {
                 if let Some(e) = last {
                     match key {
                         "FaultCode" => {
-                            if let Ok(parsed) = characters.parse::<u32>() {
+                            if let Ok(parsed) = characters.trim().parse::<u32>() {
                                 e.fault.set_code(parsed);
                             }
                         }
@@ -154,7 +163,10 @@ impl Arbitrary for AutonomousDUStateChangeComplete {
         Box::new(
             (self.results.clone())
                 .shrink()
-                .map(|r| AutonomousDUStateChangeComplete { results: r }),
+                .map(|r| AutonomousDUStateChangeComplete {
+                    results: r,
+                    extra: vec![],
+                }),
         )
     }
 }
@@ -8,11 +8,12 @@ use xml::writer::XmlEvent;
 #[cfg(test)]
 use super::gen_utc_date;
 use super::{
-    cwmp_prefix, parse_to_int, write_fault_struct, write_simple, FaultStruct, GenerateError,
-    XmlSafeString,
+    cwmp_prefix, parse_to_int, write_fault_struct, write_simple, FaultStruct, FileType,
+    GenerateError, GenerateOptions, XmlElement, XmlSafeString,
 };
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AutonomousTransferComplete {
     announce_url: XmlSafeString,
     transfer_url: XmlSafeString,
@@ -23,6 +24,7 @@ pub struct AutonomousTransferComplete {
     fault: FaultStruct,
     start_time: Option<DateTime<Utc>>,
     complete_time: Option<DateTime<Utc>>,
+    pub extra: Vec<XmlElement>,
 }
 
 impl AutonomousTransferComplete {
@@ -49,8 +51,24 @@ impl AutonomousTransferComplete {
             fault,
             start_time: Some(start_time),
             complete_time: Some(complete_time),
+            extra: vec![],
         }
     }
+    #[must_use]
+    pub fn file_type(&self) -> FileType {
+        FileType::from(self.file_type.0.as_ref())
+    }
+    #[must_use]
+    pub fn fault(&self) -> &FaultStruct {
+        &self.fault
+    }
+    pub(super) fn fault_mut(&mut self) -> &mut FaultStruct {
+        &mut self.fault
+    }
+    #[cfg(test)]
+    pub(super) fn extra_slots(&mut self) -> [&mut Vec<XmlElement>; 2] {
+        [&mut self.fault.extra, &mut self.extra]
+    }
 
     /// Generate XML for `AutonomousTransferComplete`
     ///     
@@ -61,9 +79,10 @@ impl AutonomousTransferComplete {
         &self,
         writer: &mut xml::EventWriter<W>,
         has_cwmp: bool,
+        options: &GenerateOptions,
     ) -> Result<(), GenerateError> {
         writer.write(XmlEvent::start_element(
-            &cwmp_prefix(has_cwmp, "AutonomousTransferComplete")[..],
+            &cwmp_prefix(options, has_cwmp, "AutonomousTransferComplete")[..],
         ))?;
         write_simple(writer, "AnnounceURL", self.announce_url.0.as_ref())?;
         write_simple(writer, "TransferURL", self.transfer_url.0.as_ref())?;
@@ -80,6 +99,9 @@ impl AutonomousTransferComplete {
             None => {}
             Some(dt) => write_simple(writer, "CompleteTime", &dt.to_rfc3339())?,
         }
+        for e in &self.extra {
+            e.generate(writer)?;
+        }
         writer.write(XmlEvent::end_element())?;
 
         Ok(())
@@ -104,12 +126,12 @@ impl AutonomousTransferComplete {
                 self.file_size = parse_to_int(characters, 0);
             }
             ["AutonomousTransferComplete", "StartTime"] => {
-                if let Ok(dt) = characters.parse::<DateTime<Utc>>() {
+                if let Ok(dt) = characters.trim().parse::<DateTime<Utc>>() {
                     self.start_time = Some(dt);
                 }
             }
             ["AutonomousTransferComplete", "CompleteTime"] => {
-                if let Ok(dt) = characters.parse::<DateTime<Utc>>() {
+                if let Ok(dt) = characters.trim().parse::<DateTime<Utc>>() {
                     self.complete_time = Some(dt);
                 }
             }
@@ -137,6 +159,7 @@ impl Arbitrary for AutonomousTransferComplete {
             fault: FaultStruct::arbitrary(g),
             start_time: Some(gen_utc_date(2014, 11, 28, 12, 0, 9)),
             complete_time: Some(gen_utc_date(2014, 11, 29, 12, 0, 9)),
+            extra: vec![],
         }
         // times are not arbitrary due to qc
         // tuple (used in shrink) limitations
@@ -163,6 +186,7 @@ impl Arbitrary for AutonomousTransferComplete {
                     fault: f,
                     start_time: Some(gen_utc_date(2014, 11, 28, 12, 0, 9)),
                     complete_time: Some(gen_utc_date(2014, 11, 29, 12, 0, 9)),
+                    extra: vec![],
                 }),
         )
     }
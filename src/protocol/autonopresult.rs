@@ -2,12 +2,13 @@ use chrono::{DateTime, Utc};
 #[cfg(test)]
 use quickcheck::{Arbitrary, Gen};
 
-use super::{FaultStruct, XmlSafeString};
+use super::{FaultStruct, XmlElement, XmlSafeString};
 
 #[cfg(test)]
 use super::gen_utc_date;
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AutonOpResult {
     pub uuid: XmlSafeString,
     pub deployment_unit_ref: XmlSafeString,
@@ -19,6 +20,11 @@ pub struct AutonOpResult {
     pub complete_time: Option<DateTime<Utc>>,
     pub fault: FaultStruct,
     pub operation_performed: XmlSafeString,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub extra: Vec<XmlElement>,
 }
 
 impl AutonOpResult {
@@ -48,6 +54,7 @@ impl AutonOpResult {
             complete_time: Some(complete_time),
             fault: FaultStruct::new(fault_code, fault_string),
             operation_performed: operation_performed.into(),
+            extra: vec![],
         }
     }
 }
@@ -69,6 +76,7 @@ impl Arbitrary for AutonOpResult {
             complete_time: Some(bogus_ct),
             fault: FaultStruct::arbitrary(g),
             operation_performed: XmlSafeString::arbitrary(g),
+            extra: vec![],
         }
     }
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
@@ -101,6 +109,7 @@ impl Arbitrary for AutonOpResult {
                     complete_time: Some(gen_utc_date(2014, 11, 29, 12, 0, 9)),
                     fault: f,
                     operation_performed: op,
+                    extra: vec![],
                 }),
         )
     }
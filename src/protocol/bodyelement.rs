@@ -1,18 +1,19 @@
 use super::{
     AddObject, AddObjectResponse, AutonomousDUStateChangeComplete,
     AutonomousDUStateChangeCompleteResponse, AutonomousTransferComplete,
-    AutonomousTransferCompleteResponse, CancelTransfer, CancelTransferResponse, ChangeDUState,
-    ChangeDUStateResponse, DUStateChangeComplete, DUStateChangeCompleteResponse, DeleteObject,
-    DeleteObjectResponse, Download, DownloadResponse, FactoryReset, FactoryResetResponse, Fault,
-    GetAllQueuedTransfers, GetAllQueuedTransfersResponse, GetOptions, GetOptionsResponse,
-    GetParameterAttributes, GetParameterAttributesResponse, GetParameterNames,
-    GetParameterNamesResponse, GetParameterValues, GetParameterValuesResponse, GetQueuedTransfers,
-    GetQueuedTransfersResponse, GetRPCMethods, GetRPCMethodsResponse, Inform, InformResponse,
-    Kicked, KickedResponse, Reboot, RebootResponse, RequestDownload, RequestDownloadResponse,
-    ScheduleDownload, ScheduleDownloadResponse, ScheduleInform, ScheduleInformResponse,
-    SetParameterAttributes, SetParameterAttributesResponse, SetParameterValues,
-    SetParameterValuesResponse, SetVouchers, SetVouchersResponse, TransferComplete,
-    TransferCompleteResponse, Upload, UploadResponse,
+    AutonomousTransferCompleteResponse, BodyElementKind, CancelTransfer, CancelTransferResponse,
+    ChangeDUState, ChangeDUStateResponse, CwmpVersion, DUStateChangeComplete,
+    DUStateChangeCompleteResponse, DeleteObject, DeleteObjectResponse, Direction, Download,
+    DownloadResponse, FactoryReset, FactoryResetResponse, Fault, FaultCode, GetAllQueuedTransfers,
+    GetAllQueuedTransfersResponse, GetOptions, GetOptionsResponse, GetParameterAttributes,
+    GetParameterAttributesResponse, GetParameterNames, GetParameterNamesResponse,
+    GetParameterValues, GetParameterValuesResponse, GetQueuedTransfers, GetQueuedTransfersResponse,
+    GetRPCMethods, GetRPCMethodsResponse, Inform, InformResponse, Kicked, KickedResponse, Reboot,
+    RebootResponse, RequestDownload, RequestDownloadResponse, ScheduleDownload,
+    ScheduleDownloadResponse, ScheduleInform, ScheduleInformResponse, SetParameterAttributes,
+    SetParameterAttributesResponse, SetParameterValues, SetParameterValuesResponse, SetVouchers,
+    SetVouchersResponse, TransferComplete, TransferCompleteResponse, Upload, UploadResponse,
+    VendorRpc, XmlElement,
 };
 #[cfg(test)]
 use quickcheck::{Arbitrary, Gen};
@@ -20,6 +21,7 @@ use quickcheck::{Arbitrary, Gen};
 use rand::Rng;
 
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BodyElement {
     AddObjectResponse(AddObjectResponse),
     AddObject(AddObject),
@@ -76,6 +78,425 @@ pub enum BodyElement {
     TransferComplete(TransferComplete),
     UploadResponse(UploadResponse),
     Upload(Upload),
+    /// A body element this crate does not model, like a vendor RPC, kept as it was received
+    Unknown(XmlElement),
+    /// A vendor RPC registered in the `VendorRegistry` of the `ParseOptions`.
+    /// Serialized as the unknown body element it generates, which is what it
+    /// is deserialized as.
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename(serialize = "Unknown"),
+            serialize_with = "serialize_vendor",
+            skip_deserializing
+        )
+    )]
+    Vendor(Box<dyn VendorRpc>),
+}
+
+// the element of a vendor RPC, as generated in an envelope with a cwmp namespace
+#[cfg(feature = "serde")]
+#[allow(clippy::borrowed_box)]
+fn serialize_vendor<S: serde::Serializer>(
+    rpc: &Box<dyn VendorRpc>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serde::Serialize::serialize(
+        &rpc.to_element(true, &super::GenerateOptions::default()),
+        serializer,
+    )
+}
+
+impl BodyElement {
+    /// The oldest CWMP version this body element can be sent with: the
+    /// version that introduced the RPC, or a later one if its content needs
+    /// it, like an Inform carrying a "14 HEARTBEAT" event or a
+    /// `TransferComplete` reporting a "9018 file corrupted" fault
+    ///
+    /// Only event codes and fault codes are taken into account, other
+    /// values added by later versions, like file types, are not.
+    #[must_use]
+    pub fn min_version(&self) -> CwmpVersion {
+        let rpc = CwmpVersion::introducing(self.rpc_name()).unwrap_or_default();
+        let fault_codes = match self {
+            BodyElement::Fault(f) => vec![f.detail.code],
+            BodyElement::TransferComplete(t) => vec![t.fault.code],
+            BodyElement::AutonomousTransferComplete(t) => vec![t.fault().code],
+            BodyElement::DUStateChangeComplete(d) => {
+                d.results.iter().map(|r| r.fault.code).collect()
+            }
+            BodyElement::AutonomousDUStateChangeComplete(d) => {
+                d.results.iter().map(|r| r.fault.code).collect()
+            }
+            _ => vec![],
+        };
+        let events = match self {
+            BodyElement::Inform(i) => i.event.iter().map(|e| e.code().min_version()).collect(),
+            _ => vec![],
+        };
+        fault_codes
+            .into_iter()
+            .map(|code| FaultCode::from(code).min_version())
+            .chain(events)
+            .fold(rpc, std::cmp::max)
+    }
+
+    /// The kind of this body element
+    #[must_use]
+    #[allow(clippy::too_many_lines)]
+    pub fn kind(&self) -> BodyElementKind {
+        match self {
+            BodyElement::AddObjectResponse(_) => BodyElementKind::AddObjectResponse,
+            BodyElement::AddObject(_) => BodyElementKind::AddObject,
+            BodyElement::AutonomousDUStateChangeCompleteResponse(_) => {
+                BodyElementKind::AutonomousDUStateChangeCompleteResponse
+            }
+            BodyElement::AutonomousDUStateChangeComplete(_) => {
+                BodyElementKind::AutonomousDUStateChangeComplete
+            }
+            BodyElement::AutonomousTransferCompleteResponse(_) => {
+                BodyElementKind::AutonomousTransferCompleteResponse
+            }
+            BodyElement::AutonomousTransferComplete(_) => {
+                BodyElementKind::AutonomousTransferComplete
+            }
+            BodyElement::CancelTransferResponse(_) => BodyElementKind::CancelTransferResponse,
+            BodyElement::CancelTransfer(_) => BodyElementKind::CancelTransfer,
+            BodyElement::ChangeDUStateResponse(_) => BodyElementKind::ChangeDUStateResponse,
+            BodyElement::ChangeDUState(_) => BodyElementKind::ChangeDUState,
+            BodyElement::DeleteObjectResponse(_) => BodyElementKind::DeleteObjectResponse,
+            BodyElement::DeleteObject(_) => BodyElementKind::DeleteObject,
+            BodyElement::DownloadResponse(_) => BodyElementKind::DownloadResponse,
+            BodyElement::Download(_) => BodyElementKind::Download,
+            BodyElement::DUStateChangeCompleteResponse(_) => {
+                BodyElementKind::DUStateChangeCompleteResponse
+            }
+            BodyElement::DUStateChangeComplete(_) => BodyElementKind::DUStateChangeComplete,
+            BodyElement::FactoryResetResponse(_) => BodyElementKind::FactoryResetResponse,
+            BodyElement::FactoryReset(_) => BodyElementKind::FactoryReset,
+            BodyElement::Fault(_) => BodyElementKind::Fault,
+            BodyElement::GetAllQueuedTransfersResponse(_) => {
+                BodyElementKind::GetAllQueuedTransfersResponse
+            }
+            BodyElement::GetAllQueuedTransfers(_) => BodyElementKind::GetAllQueuedTransfers,
+            BodyElement::GetOptionsResponse(_) => BodyElementKind::GetOptionsResponse,
+            BodyElement::GetOptions(_) => BodyElementKind::GetOptions,
+            BodyElement::GetParameterAttributes(_) => BodyElementKind::GetParameterAttributes,
+            BodyElement::GetParameterAttributesResponse(_) => {
+                BodyElementKind::GetParameterAttributesResponse
+            }
+            BodyElement::GetParameterNamesResponse(_) => BodyElementKind::GetParameterNamesResponse,
+            BodyElement::GetParameterNames(_) => BodyElementKind::GetParameterNames,
+            BodyElement::GetParameterValues(_) => BodyElementKind::GetParameterValues,
+            BodyElement::GetParameterValuesResponse(_) => {
+                BodyElementKind::GetParameterValuesResponse
+            }
+            BodyElement::GetQueuedTransfersResponse(_) => {
+                BodyElementKind::GetQueuedTransfersResponse
+            }
+            BodyElement::GetQueuedTransfers(_) => BodyElementKind::GetQueuedTransfers,
+            BodyElement::GetRPCMethodsResponse(_) => BodyElementKind::GetRPCMethodsResponse,
+            BodyElement::GetRPCMethods(_) => BodyElementKind::GetRPCMethods,
+            BodyElement::InformResponse(_) => BodyElementKind::InformResponse,
+            BodyElement::Inform(_) => BodyElementKind::Inform,
+            BodyElement::KickedResponse(_) => BodyElementKind::KickedResponse,
+            BodyElement::Kicked(_) => BodyElementKind::Kicked,
+            BodyElement::RebootResponse(_) => BodyElementKind::RebootResponse,
+            BodyElement::Reboot(_) => BodyElementKind::Reboot,
+            BodyElement::RequestDownloadResponse(_) => BodyElementKind::RequestDownloadResponse,
+            BodyElement::RequestDownload(_) => BodyElementKind::RequestDownload,
+            BodyElement::ScheduleDownloadResponse(_) => BodyElementKind::ScheduleDownloadResponse,
+            BodyElement::ScheduleDownload(_) => BodyElementKind::ScheduleDownload,
+            BodyElement::ScheduleInformResponse(_) => BodyElementKind::ScheduleInformResponse,
+            BodyElement::ScheduleInform(_) => BodyElementKind::ScheduleInform,
+            BodyElement::SetParameterAttributesResponse(_) => {
+                BodyElementKind::SetParameterAttributesResponse
+            }
+            BodyElement::SetParameterAttributes(_) => BodyElementKind::SetParameterAttributes,
+            BodyElement::SetParameterValuesResponse(_) => {
+                BodyElementKind::SetParameterValuesResponse
+            }
+            BodyElement::SetParameterValues(_) => BodyElementKind::SetParameterValues,
+            BodyElement::SetVouchersResponse(_) => BodyElementKind::SetVouchersResponse,
+            BodyElement::SetVouchers(_) => BodyElementKind::SetVouchers,
+            BodyElement::TransferCompleteResponse(_) => BodyElementKind::TransferCompleteResponse,
+            BodyElement::TransferComplete(_) => BodyElementKind::TransferComplete,
+            BodyElement::UploadResponse(_) => BodyElementKind::UploadResponse,
+            BodyElement::Upload(_) => BodyElementKind::Upload,
+            BodyElement::Unknown(_) => BodyElementKind::Unknown,
+            BodyElement::Vendor(_) => BodyElementKind::Vendor,
+        }
+    }
+
+    /// The element name used on the wire, like "GetParameterValuesResponse"
+    #[must_use]
+    pub fn rpc_name(&self) -> &str {
+        match self {
+            BodyElement::Unknown(e) => &e.name.local_name,
+            BodyElement::Vendor(v) => v.name(),
+            _ => self.kind().name(),
+        }
+    }
+
+    /// True for the RPCs that expect a response
+    #[must_use]
+    pub fn is_request(&self) -> bool {
+        !self.is_response()
+    }
+
+    /// True for responses and faults, which never start an exchange
+    #[must_use]
+    pub fn is_response(&self) -> bool {
+        match self {
+            BodyElement::Unknown(_) | BodyElement::Vendor(_) => {
+                self.rpc_name().ends_with("Response")
+            }
+            _ => self.kind().is_response(),
+        }
+    }
+
+    /// Which end of a session sends this body element
+    #[must_use]
+    pub fn direction(&self) -> Direction {
+        self.kind().direction()
+    }
+
+    /// The kind of the response this request expects, `None` for responses
+    /// and faults. A `Fault` may always be received instead.
+    #[must_use]
+    pub fn expected_response_kind(&self) -> Option<BodyElementKind> {
+        self.kind().expected_response()
+    }
+
+    /// True if this body element answers `request`: it is the matching
+    /// response, or a Fault. Unknown and vendor body elements are matched by name.
+    #[must_use]
+    pub fn matches_request(&self, request: &BodyElement) -> bool {
+        matches!(self, BodyElement::Fault(_))
+            || (request.is_request()
+                && self.rpc_name().strip_suffix("Response") == Some(request.rpc_name()))
+    }
+
+    /// The unknown children of the body element, like vendor arguments,
+    /// kept as they were received and generated after the known ones.
+    /// `None` for the body elements without arguments, and unknown ones.
+    #[must_use]
+    #[allow(clippy::too_many_lines)]
+    pub fn extra_mut(&mut self) -> Option<&mut Vec<XmlElement>> {
+        match self {
+            BodyElement::AddObjectResponse(e) => Some(&mut e.extra),
+            BodyElement::AddObject(e) => Some(&mut e.extra),
+            BodyElement::AutonomousDUStateChangeComplete(e) => Some(&mut e.extra),
+            BodyElement::AutonomousTransferComplete(e) => Some(&mut e.extra),
+            BodyElement::CancelTransfer(e) => Some(&mut e.extra),
+            BodyElement::ChangeDUState(e) => Some(&mut e.extra),
+            BodyElement::DeleteObjectResponse(e) => Some(&mut e.extra),
+            BodyElement::DeleteObject(e) => Some(&mut e.extra),
+            BodyElement::DownloadResponse(e) => Some(&mut e.extra),
+            BodyElement::Download(e) => Some(&mut e.extra),
+            BodyElement::DUStateChangeComplete(e) => Some(&mut e.extra),
+            BodyElement::Fault(e) => Some(&mut e.extra),
+            BodyElement::GetAllQueuedTransfersResponse(e) => Some(&mut e.extra),
+            BodyElement::GetOptionsResponse(e) => Some(&mut e.extra),
+            BodyElement::GetOptions(e) => Some(&mut e.extra),
+            BodyElement::GetParameterAttributes(e) => Some(&mut e.extra),
+            BodyElement::GetParameterAttributesResponse(e) => Some(&mut e.extra),
+            BodyElement::GetParameterNamesResponse(e) => Some(&mut e.extra),
+            BodyElement::GetParameterNames(e) => Some(&mut e.extra),
+            BodyElement::GetParameterValues(e) => Some(&mut e.extra),
+            BodyElement::GetParameterValuesResponse(e) => Some(&mut e.extra),
+            BodyElement::GetQueuedTransfersResponse(e) => Some(&mut e.extra),
+            BodyElement::GetRPCMethodsResponse(e) => Some(&mut e.extra),
+            BodyElement::InformResponse(e) => Some(&mut e.extra),
+            BodyElement::Inform(e) => Some(&mut e.extra),
+            BodyElement::KickedResponse(e) => Some(&mut e.extra),
+            BodyElement::Kicked(e) => Some(&mut e.extra),
+            BodyElement::Reboot(e) => Some(&mut e.extra),
+            BodyElement::RequestDownload(e) => Some(&mut e.extra),
+            BodyElement::ScheduleDownload(e) => Some(&mut e.extra),
+            BodyElement::ScheduleInform(e) => Some(&mut e.extra),
+            BodyElement::SetParameterAttributes(e) => Some(&mut e.extra),
+            BodyElement::SetParameterValuesResponse(e) => Some(&mut e.extra),
+            BodyElement::SetParameterValues(e) => Some(&mut e.extra),
+            BodyElement::SetVouchers(e) => Some(&mut e.extra),
+            BodyElement::TransferComplete(e) => Some(&mut e.extra),
+            BodyElement::UploadResponse(e) => Some(&mut e.extra),
+            BodyElement::Upload(e) => Some(&mut e.extra),
+            _ => None,
+        }
+    }
+
+    // the unknown children of the element at `path`, starting with the body
+    // element: its own `extra`, or the one of the struct of an argument
+    // being parsed, like the last `ParameterValueStruct` of an Inform.
+    // `None` if the element has no place for them.
+    pub(super) fn extra_at(&mut self, path: &[&str]) -> Option<&mut Vec<XmlElement>> {
+        match (self, path) {
+            (be, [_]) => be.extra_mut(),
+            (BodyElement::Inform(i), [_, "DeviceId"]) => Some(&mut i.device_id.extra),
+            (BodyElement::Inform(i), [_, "Event", "EventStruct"]) => {
+                i.event.last_mut().map(|e| &mut e.extra)
+            }
+            (BodyElement::Inform(i), [_, "ParameterList", "ParameterValueStruct"]) => {
+                i.parameter_list.last_mut().map(|p| &mut p.extra)
+            }
+            (
+                BodyElement::GetParameterValuesResponse(r),
+                [_, "ParameterList", "ParameterValueStruct"],
+            ) => r.parameters.last_mut().map(|p| &mut p.extra),
+            (BodyElement::SetParameterValues(s), [_, "ParameterList", "ParameterValueStruct"]) => {
+                s.parameter_list.last_mut().map(|p| &mut p.extra)
+            }
+            (
+                BodyElement::GetParameterNamesResponse(r),
+                [_, "ParameterList", "ParameterInfoStruct"],
+            ) => r.parameter_list.last_mut().map(|p| &mut p.extra),
+            (
+                BodyElement::GetParameterAttributesResponse(r),
+                [_, "ParameterList", "ParameterAttributeStruct"],
+            ) => r.parameters.last_mut().map(|p| &mut p.extra),
+            (
+                BodyElement::SetParameterAttributes(s),
+                [_, "ParameterList", "SetParameterAttributesStruct"],
+            ) => s.parameter_list.last_mut().map(|p| &mut p.extra),
+            (BodyElement::RequestDownload(r), [_, "FileTypeArg", "ArgStruct"]) => {
+                r.file_type_arg.last_mut().map(|a| &mut a.extra)
+            }
+            (BodyElement::ScheduleDownload(s), [_, "TimeWindowList", "TimeWindowStruct"]) => {
+                s.timewindow_list.last_mut().map(|t| &mut t.extra)
+            }
+            (
+                BodyElement::GetQueuedTransfersResponse(r),
+                [_, "TransferList", "QueuedTransferStruct"],
+            ) => r.transfer_list.last_mut().map(|t| &mut t.extra),
+            (
+                BodyElement::GetAllQueuedTransfersResponse(r),
+                [_, "TransferList", "AllQueuedTransferStruct"],
+            ) => r.transfer_list.last_mut().map(|t| &mut t.extra),
+            (BodyElement::GetOptionsResponse(r), [_, "OptionList", "OptionStruct"]) => {
+                r.option_list.last_mut().map(|o| &mut o.extra)
+            }
+            (BodyElement::ChangeDUState(c), [_, "Operations", "InstallOpStruct"]) => {
+                c.install_operations.last_mut().map(|o| &mut o.extra)
+            }
+            (BodyElement::ChangeDUState(c), [_, "Operations", "UninstallOpStruct"]) => {
+                c.uninstall_operations.last_mut().map(|o| &mut o.extra)
+            }
+            (BodyElement::ChangeDUState(c), [_, "Operations", "UpdateOpStruct"]) => {
+                c.update_operations.last_mut().map(|o| &mut o.extra)
+            }
+            (BodyElement::DUStateChangeComplete(d), [_, "Results", "OpResultStruct"]) => {
+                d.results.last_mut().map(|r| &mut r.extra)
+            }
+            (
+                BodyElement::AutonomousDUStateChangeComplete(d),
+                [_, "Results", "AutonOpResultStruct"],
+            ) => d.results.last_mut().map(|r| &mut r.extra),
+            (BodyElement::TransferComplete(t), [_, "FaultStruct"]) => Some(&mut t.fault.extra),
+            (BodyElement::AutonomousTransferComplete(t), [_, "FaultStruct"]) => {
+                Some(&mut t.fault_mut().extra)
+            }
+            (BodyElement::DUStateChangeComplete(d), [_, "Results", _, "Fault", "FaultStruct"]) => {
+                d.results.last_mut().map(|r| &mut r.fault.extra)
+            }
+            (
+                BodyElement::AutonomousDUStateChangeComplete(d),
+                [_, "Results", _, "Fault", "FaultStruct"],
+            ) => d.results.last_mut().map(|r| &mut r.fault.extra),
+            (BodyElement::Fault(f), [_, "detail", "Fault"]) => Some(&mut f.detail.extra),
+            (BodyElement::Fault(f), [_, "detail", "Fault", "SetParameterValuesFault"]) => f
+                .detail
+                .set_parameter_values_fault
+                .last_mut()
+                .map(|s| &mut s.extra),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+impl BodyElement {
+    // every `extra` of the body element and the structs of its arguments
+    pub(crate) fn extra_slots(&mut self) -> Vec<&mut Vec<XmlElement>> {
+        fn slots<'a, T: 'a>(
+            entries: &'a mut [T],
+            extra: impl FnMut(&'a mut T) -> &'a mut Vec<XmlElement>,
+            own: &'a mut Vec<XmlElement>,
+        ) -> Vec<&'a mut Vec<XmlElement>> {
+            entries
+                .iter_mut()
+                .map(extra)
+                .chain(std::iter::once(own))
+                .collect()
+        }
+        match self {
+            BodyElement::Inform(i) => std::iter::once(&mut i.device_id.extra)
+                .chain(i.event.iter_mut().map(|e| &mut e.extra))
+                .chain(i.parameter_list.iter_mut().map(|p| &mut p.extra))
+                .chain(std::iter::once(&mut i.extra))
+                .collect(),
+            BodyElement::GetParameterValuesResponse(r) => {
+                slots(&mut r.parameters, |p| &mut p.extra, &mut r.extra)
+            }
+            BodyElement::SetParameterValues(s) => {
+                slots(&mut s.parameter_list, |p| &mut p.extra, &mut s.extra)
+            }
+            BodyElement::GetParameterNamesResponse(r) => {
+                slots(&mut r.parameter_list, |p| &mut p.extra, &mut r.extra)
+            }
+            BodyElement::GetParameterAttributesResponse(r) => {
+                slots(&mut r.parameters, |p| &mut p.extra, &mut r.extra)
+            }
+            BodyElement::SetParameterAttributes(s) => {
+                slots(&mut s.parameter_list, |p| &mut p.extra, &mut s.extra)
+            }
+            BodyElement::RequestDownload(r) => {
+                slots(&mut r.file_type_arg, |a| &mut a.extra, &mut r.extra)
+            }
+            BodyElement::ScheduleDownload(s) => {
+                slots(&mut s.timewindow_list, |t| &mut t.extra, &mut s.extra)
+            }
+            BodyElement::GetQueuedTransfersResponse(r) => {
+                slots(&mut r.transfer_list, |t| &mut t.extra, &mut r.extra)
+            }
+            BodyElement::GetAllQueuedTransfersResponse(r) => {
+                slots(&mut r.transfer_list, |t| &mut t.extra, &mut r.extra)
+            }
+            BodyElement::GetOptionsResponse(r) => {
+                slots(&mut r.option_list, |o| &mut o.extra, &mut r.extra)
+            }
+            BodyElement::ChangeDUState(c) => c
+                .install_operations
+                .iter_mut()
+                .map(|o| &mut o.extra)
+                .chain(c.uninstall_operations.iter_mut().map(|o| &mut o.extra))
+                .chain(c.update_operations.iter_mut().map(|o| &mut o.extra))
+                .chain(std::iter::once(&mut c.extra))
+                .collect(),
+            BodyElement::TransferComplete(t) => vec![&mut t.fault.extra, &mut t.extra],
+            BodyElement::AutonomousTransferComplete(t) => t.extra_slots().into(),
+            BodyElement::DUStateChangeComplete(d) => d
+                .results
+                .iter_mut()
+                .flat_map(|r| [&mut r.fault.extra, &mut r.extra])
+                .chain(std::iter::once(&mut d.extra))
+                .collect(),
+            BodyElement::AutonomousDUStateChangeComplete(d) => d
+                .results
+                .iter_mut()
+                .flat_map(|r| [&mut r.fault.extra, &mut r.extra])
+                .chain(std::iter::once(&mut d.extra))
+                .collect(),
+            BodyElement::Fault(f) => f
+                .detail
+                .set_parameter_values_fault
+                .iter_mut()
+                .map(|s| &mut s.extra)
+                .chain([&mut f.detail.extra, &mut f.extra])
+                .collect(),
+            be => be.extra_mut().into_iter().collect(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -258,6 +679,7 @@ impl Arbitrary for BodyElement {
             }
             BodyElement::UploadResponse(x) => Box::new(x.shrink().map(BodyElement::UploadResponse)),
             BodyElement::Upload(x) => Box::new(x.shrink().map(BodyElement::Upload)),
+            BodyElement::Unknown(_) | BodyElement::Vendor(_) => quickcheck::empty_shrinker(),
         }
     }
 }
@@ -0,0 +1,267 @@
+use std::fmt;
+
+use super::Direction;
+
+/// The kind of a `BodyElement`, without its content, for use as a map key
+/// or in tables of RPCs
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BodyElementKind {
+    AddObjectResponse,
+    AddObject,
+    AutonomousDUStateChangeCompleteResponse,
+    AutonomousDUStateChangeComplete,
+    AutonomousTransferCompleteResponse,
+    AutonomousTransferComplete,
+    CancelTransferResponse,
+    CancelTransfer,
+    ChangeDUStateResponse,
+    ChangeDUState,
+    DeleteObjectResponse,
+    DeleteObject,
+    DownloadResponse,
+    Download,
+    DUStateChangeCompleteResponse,
+    DUStateChangeComplete,
+    FactoryResetResponse,
+    FactoryReset,
+    Fault,
+    GetAllQueuedTransfersResponse,
+    GetAllQueuedTransfers,
+    GetOptionsResponse,
+    GetOptions,
+    GetParameterAttributes,
+    GetParameterAttributesResponse,
+    GetParameterNamesResponse,
+    GetParameterNames,
+    GetParameterValues,
+    GetParameterValuesResponse,
+    GetQueuedTransfersResponse,
+    GetQueuedTransfers,
+    GetRPCMethodsResponse,
+    GetRPCMethods,
+    InformResponse,
+    Inform,
+    KickedResponse,
+    Kicked,
+    RebootResponse,
+    Reboot,
+    RequestDownloadResponse,
+    RequestDownload,
+    ScheduleDownloadResponse,
+    ScheduleDownload,
+    ScheduleInformResponse,
+    ScheduleInform,
+    SetParameterAttributesResponse,
+    SetParameterAttributes,
+    SetParameterValuesResponse,
+    SetParameterValues,
+    SetVouchersResponse,
+    SetVouchers,
+    TransferCompleteResponse,
+    TransferComplete,
+    UploadResponse,
+    Upload,
+    /// A body element this crate does not model
+    Unknown,
+    /// A vendor RPC registered in a `VendorRegistry`
+    Vendor,
+}
+
+impl BodyElementKind {
+    /// Every known kind, in the order of the `BodyElement` variants
+    pub const ALL: [BodyElementKind; 55] = [
+        BodyElementKind::AddObjectResponse,
+        BodyElementKind::AddObject,
+        BodyElementKind::AutonomousDUStateChangeCompleteResponse,
+        BodyElementKind::AutonomousDUStateChangeComplete,
+        BodyElementKind::AutonomousTransferCompleteResponse,
+        BodyElementKind::AutonomousTransferComplete,
+        BodyElementKind::CancelTransferResponse,
+        BodyElementKind::CancelTransfer,
+        BodyElementKind::ChangeDUStateResponse,
+        BodyElementKind::ChangeDUState,
+        BodyElementKind::DeleteObjectResponse,
+        BodyElementKind::DeleteObject,
+        BodyElementKind::DownloadResponse,
+        BodyElementKind::Download,
+        BodyElementKind::DUStateChangeCompleteResponse,
+        BodyElementKind::DUStateChangeComplete,
+        BodyElementKind::FactoryResetResponse,
+        BodyElementKind::FactoryReset,
+        BodyElementKind::Fault,
+        BodyElementKind::GetAllQueuedTransfersResponse,
+        BodyElementKind::GetAllQueuedTransfers,
+        BodyElementKind::GetOptionsResponse,
+        BodyElementKind::GetOptions,
+        BodyElementKind::GetParameterAttributes,
+        BodyElementKind::GetParameterAttributesResponse,
+        BodyElementKind::GetParameterNamesResponse,
+        BodyElementKind::GetParameterNames,
+        BodyElementKind::GetParameterValues,
+        BodyElementKind::GetParameterValuesResponse,
+        BodyElementKind::GetQueuedTransfersResponse,
+        BodyElementKind::GetQueuedTransfers,
+        BodyElementKind::GetRPCMethodsResponse,
+        BodyElementKind::GetRPCMethods,
+        BodyElementKind::InformResponse,
+        BodyElementKind::Inform,
+        BodyElementKind::KickedResponse,
+        BodyElementKind::Kicked,
+        BodyElementKind::RebootResponse,
+        BodyElementKind::Reboot,
+        BodyElementKind::RequestDownloadResponse,
+        BodyElementKind::RequestDownload,
+        BodyElementKind::ScheduleDownloadResponse,
+        BodyElementKind::ScheduleDownload,
+        BodyElementKind::ScheduleInformResponse,
+        BodyElementKind::ScheduleInform,
+        BodyElementKind::SetParameterAttributesResponse,
+        BodyElementKind::SetParameterAttributes,
+        BodyElementKind::SetParameterValuesResponse,
+        BodyElementKind::SetParameterValues,
+        BodyElementKind::SetVouchersResponse,
+        BodyElementKind::SetVouchers,
+        BodyElementKind::TransferCompleteResponse,
+        BodyElementKind::TransferComplete,
+        BodyElementKind::UploadResponse,
+        BodyElementKind::Upload,
+    ];
+
+    /// The element name used on the wire
+    #[must_use]
+    #[allow(clippy::too_many_lines)]
+    pub fn name(self) -> &'static str {
+        match self {
+            BodyElementKind::AddObjectResponse => "AddObjectResponse",
+            BodyElementKind::AddObject => "AddObject",
+            BodyElementKind::AutonomousDUStateChangeCompleteResponse => {
+                "AutonomousDUStateChangeCompleteResponse"
+            }
+            BodyElementKind::AutonomousDUStateChangeComplete => "AutonomousDUStateChangeComplete",
+            BodyElementKind::AutonomousTransferCompleteResponse => {
+                "AutonomousTransferCompleteResponse"
+            }
+            BodyElementKind::AutonomousTransferComplete => "AutonomousTransferComplete",
+            BodyElementKind::CancelTransferResponse => "CancelTransferResponse",
+            BodyElementKind::CancelTransfer => "CancelTransfer",
+            BodyElementKind::ChangeDUStateResponse => "ChangeDUStateResponse",
+            BodyElementKind::ChangeDUState => "ChangeDUState",
+            BodyElementKind::DeleteObjectResponse => "DeleteObjectResponse",
+            BodyElementKind::DeleteObject => "DeleteObject",
+            BodyElementKind::DownloadResponse => "DownloadResponse",
+            BodyElementKind::Download => "Download",
+            BodyElementKind::DUStateChangeCompleteResponse => "DUStateChangeCompleteResponse",
+            BodyElementKind::DUStateChangeComplete => "DUStateChangeComplete",
+            BodyElementKind::FactoryResetResponse => "FactoryResetResponse",
+            BodyElementKind::FactoryReset => "FactoryReset",
+            BodyElementKind::Fault => "Fault",
+            BodyElementKind::GetAllQueuedTransfersResponse => "GetAllQueuedTransfersResponse",
+            BodyElementKind::GetAllQueuedTransfers => "GetAllQueuedTransfers",
+            BodyElementKind::GetOptionsResponse => "GetOptionsResponse",
+            BodyElementKind::GetOptions => "GetOptions",
+            BodyElementKind::GetParameterAttributes => "GetParameterAttributes",
+            BodyElementKind::GetParameterAttributesResponse => "GetParameterAttributesResponse",
+            BodyElementKind::GetParameterNamesResponse => "GetParameterNamesResponse",
+            BodyElementKind::GetParameterNames => "GetParameterNames",
+            BodyElementKind::GetParameterValues => "GetParameterValues",
+            BodyElementKind::GetParameterValuesResponse => "GetParameterValuesResponse",
+            BodyElementKind::GetQueuedTransfersResponse => "GetQueuedTransfersResponse",
+            BodyElementKind::GetQueuedTransfers => "GetQueuedTransfers",
+            BodyElementKind::GetRPCMethodsResponse => "GetRPCMethodsResponse",
+            BodyElementKind::GetRPCMethods => "GetRPCMethods",
+            BodyElementKind::InformResponse => "InformResponse",
+            BodyElementKind::Inform => "Inform",
+            BodyElementKind::KickedResponse => "KickedResponse",
+            BodyElementKind::Kicked => "Kicked",
+            BodyElementKind::RebootResponse => "RebootResponse",
+            BodyElementKind::Reboot => "Reboot",
+            BodyElementKind::RequestDownloadResponse => "RequestDownloadResponse",
+            BodyElementKind::RequestDownload => "RequestDownload",
+            BodyElementKind::ScheduleDownloadResponse => "ScheduleDownloadResponse",
+            BodyElementKind::ScheduleDownload => "ScheduleDownload",
+            BodyElementKind::ScheduleInformResponse => "ScheduleInformResponse",
+            BodyElementKind::ScheduleInform => "ScheduleInform",
+            BodyElementKind::SetParameterAttributesResponse => "SetParameterAttributesResponse",
+            BodyElementKind::SetParameterAttributes => "SetParameterAttributes",
+            BodyElementKind::SetParameterValuesResponse => "SetParameterValuesResponse",
+            BodyElementKind::SetParameterValues => "SetParameterValues",
+            BodyElementKind::SetVouchersResponse => "SetVouchersResponse",
+            BodyElementKind::SetVouchers => "SetVouchers",
+            BodyElementKind::TransferCompleteResponse => "TransferCompleteResponse",
+            BodyElementKind::TransferComplete => "TransferComplete",
+            BodyElementKind::UploadResponse => "UploadResponse",
+            BodyElementKind::Upload => "Upload",
+            BodyElementKind::Unknown => "Unknown",
+            BodyElementKind::Vendor => "Vendor",
+        }
+    }
+
+    /// The kind of a body element named `name`, `None` if there is no such RPC
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        BodyElementKind::ALL.into_iter().find(|k| k.name() == name)
+    }
+
+    /// True for responses and faults
+    #[must_use]
+    pub fn is_response(self) -> bool {
+        self == BodyElementKind::Fault || self.name().ends_with("Response")
+    }
+
+    /// True for the RPCs that expect a response
+    #[must_use]
+    pub fn is_request(self) -> bool {
+        !self.is_response()
+    }
+
+    /// Which end of a session sends this kind of body element
+    #[must_use]
+    pub fn direction(self) -> Direction {
+        let request = self.name().strip_suffix("Response").unwrap_or(self.name());
+        match (self, request) {
+            (BodyElementKind::Fault | BodyElementKind::Unknown | BodyElementKind::Vendor, _)
+            | (_, "GetRPCMethods") => Direction::Both,
+            (
+                _,
+                "AutonomousDUStateChangeComplete"
+                | "AutonomousTransferComplete"
+                | "DUStateChangeComplete"
+                | "Inform"
+                | "Kicked"
+                | "RequestDownload"
+                | "TransferComplete",
+            ) => {
+                if self.is_response() {
+                    Direction::AcsToCpe
+                } else {
+                    Direction::CpeToAcs
+                }
+            }
+            _ => {
+                if self.is_response() {
+                    Direction::CpeToAcs
+                } else {
+                    Direction::AcsToCpe
+                }
+            }
+        }
+    }
+
+    /// The kind of the response to a request, `None` for responses and faults.
+    /// A `Fault` may always be received instead.
+    #[must_use]
+    pub fn expected_response(self) -> Option<Self> {
+        if self.is_response() {
+            return None;
+        }
+        BodyElementKind::from_name(&format!("{}Response", self.name()))
+    }
+}
+
+impl fmt::Display for BodyElementKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
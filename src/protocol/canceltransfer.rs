@@ -4,11 +4,13 @@ use std::io::Write;
 use quickcheck::{Arbitrary, Gen};
 use xml::writer::XmlEvent;
 
-use super::{cwmp_prefix, write_simple, GenerateError, XmlSafeString};
+use super::{cwmp_prefix, write_simple, GenerateError, GenerateOptions, XmlElement, XmlSafeString};
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CancelTransfer {
     pub command_key: XmlSafeString,
+    pub extra: Vec<XmlElement>,
 }
 
 impl CancelTransfer {
@@ -16,6 +18,7 @@ impl CancelTransfer {
     pub fn new(command_key: &str) -> Self {
         CancelTransfer {
             command_key: command_key.into(),
+            extra: vec![],
         }
     }
 
@@ -28,11 +31,15 @@ impl CancelTransfer {
         &self,
         writer: &mut xml::EventWriter<W>,
         has_cwmp: bool,
+        options: &GenerateOptions,
     ) -> Result<(), GenerateError> {
         writer.write(XmlEvent::start_element(
-            &cwmp_prefix(has_cwmp, "CancelTransfer")[..],
+            &cwmp_prefix(options, has_cwmp, "CancelTransfer")[..],
         ))?;
         write_simple(writer, "CommandKey", self.command_key.0.as_ref())?;
+        for e in &self.extra {
+            e.generate(writer)?;
+        }
         writer.write(XmlEvent::end_element())?;
 
         Ok(())
@@ -50,14 +57,13 @@ impl Arbitrary for CancelTransfer {
     fn arbitrary(g: &mut Gen) -> Self {
         Self {
             command_key: XmlSafeString::arbitrary(g),
+            extra: vec![],
         }
     }
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
-        Box::new(
-            self.command_key
-                .clone()
-                .shrink()
-                .map(|c| CancelTransfer { command_key: c }),
-        )
+        Box::new(self.command_key.clone().shrink().map(|c| CancelTransfer {
+            command_key: c,
+            extra: vec![],
+        }))
     }
 }
@@ -0,0 +1,205 @@
+use log::warn;
+use xml::namespace::Namespace;
+
+use super::{
+    schema, strict::is_header_element, BodyElement, BodyElementKind, HeaderElement, State,
+    UnknownHeader, XmlAttribute, XmlElement, XmlName, XmlNode,
+};
+
+// where a captured element goes once it is complete
+#[derive(Debug, Clone, Copy)]
+pub(super) enum Target {
+    Header,
+    Body,
+    Extra,
+}
+
+// the bindings of `inner` that `outer` does not have, leaving out the ones
+// every document has
+fn declared(inner: &Namespace, outer: Option<&Namespace>) -> Vec<(String, String)> {
+    inner
+        .iter()
+        .filter(|(prefix, uri)| {
+            !(matches!(*prefix, "xml" | "xmlns") || prefix.is_empty() && uri.is_empty())
+                && outer.and_then(|o| o.get(*prefix)) != Some(*uri)
+        })
+        .map(|(prefix, uri)| (prefix.to_string(), uri.to_string()))
+        .collect()
+}
+
+// the element as it starts, without its children
+fn element(
+    name: &xml::name::OwnedName,
+    attributes: &[xml::attribute::OwnedAttribute],
+    namespace: &Namespace,
+    outer: Option<&Namespace>,
+) -> XmlElement {
+    let mut element = XmlElement::new(XmlName::from(name));
+    element.attributes = attributes
+        .iter()
+        .map(|a| XmlAttribute {
+            name: XmlName::from(&a.name),
+            value: a.value.clone(),
+        })
+        .collect();
+    // generating the element binds the prefixes of its name and attributes
+    // again, so they are left out to keep round trips stable
+    let bound: Vec<&XmlName> = std::iter::once(&element.name)
+        .chain(element.attributes.iter().map(|a| &a.name))
+        .collect();
+    element.namespaces = declared(namespace, outer)
+        .into_iter()
+        .filter(|(prefix, uri)| {
+            !bound.iter().any(|n| {
+                n.prefix.as_deref().unwrap_or_default() == prefix
+                    && n.namespace.as_deref() == Some(uri)
+            })
+        })
+        .collect();
+    element
+}
+
+impl State {
+    // called after the element name has been pushed onto the path, true if
+    // the element is captured rather than handed to the envelope
+    pub(super) fn capture_start(
+        &mut self,
+        name: &xml::name::OwnedName,
+        attributes: &[xml::attribute::OwnedAttribute],
+        namespace: &Namespace,
+    ) -> bool {
+        if self.capture.is_empty() {
+            let target = {
+                let path_pattern: Vec<&str> = self.path.iter().map(AsRef::as_ref).collect();
+                match &path_pattern[..] {
+                    ["Envelope", "Header", header_element]
+                        if !is_header_element(header_element) =>
+                    {
+                        Some(Target::Header)
+                    }
+                    ["Envelope", "Body", body_element]
+                        if BodyElementKind::from_name(body_element).is_none()
+                            && self.options.vendor_rpcs.create(name).is_none() =>
+                    {
+                        Some(Target::Body)
+                    }
+                    ["Envelope", "Body", body_element] => {
+                        self.empty_body_element = schema::known_children(&[body_element])
+                            .is_some_and(<[_]>::is_empty)
+                            .then(|| element(name, attributes, namespace, self.scopes.last()));
+                        None
+                    }
+                    // an element unknown to a known parent, at any depth
+                    ["Envelope", "Body", parent @ .., child]
+                        if !parent.is_empty()
+                            && schema::known_children(parent)
+                                .is_some_and(|known| known.iter().all(|c| c.name() != *child)) =>
+                    {
+                        if self
+                            .envelope
+                            .body
+                            .last_mut()
+                            .is_some_and(|be| be.extra_at(parent).is_some())
+                        {
+                            Some(Target::Extra)
+                        } else if let Some(body_element) =
+                            self.empty_body_element.take_if(|_| parent.len() == 1)
+                        {
+                            // nothing was parsed into it, so it goes as it was received
+                            self.envelope.body.pop();
+                            let scope = self.scopes.pop().unwrap_or_else(Namespace::empty);
+                            self.capture.push((body_element, scope));
+                            Some(Target::Body)
+                        } else {
+                            warn!(
+                                "Dropping {}, it has no place in {}",
+                                child,
+                                parent.join("/")
+                            );
+                            None
+                        }
+                    }
+                    _ => None,
+                }
+            };
+            let Some(target) = target else {
+                // the namespaces in scope down to the body elements, the few
+                // declared further down are at worst repeated on a capture
+                if self.path.len() <= 3 {
+                    self.scopes.push(namespace.clone());
+                }
+                return false;
+            };
+            self.capture_target = target;
+        }
+
+        let outer = match self.capture.last() {
+            Some((_, scope)) => Some(scope),
+            None => self.scopes.last(),
+        };
+        let element = element(name, attributes, namespace, outer);
+        self.capture.push((element, namespace.clone()));
+        true
+    }
+
+    // called before the element name is popped from the path, true if the
+    // element was captured
+    pub(super) fn capture_end(&mut self) -> bool {
+        let Some((mut element, _)) = self.capture.pop() else {
+            if self.path.len() <= 3 {
+                self.scopes.pop();
+            }
+            return false;
+        };
+        // indentation between child elements carries no information
+        if element
+            .children
+            .iter()
+            .any(|c| matches!(c, XmlNode::Element(_)))
+        {
+            element
+                .children
+                .retain(|c| !matches!(c, XmlNode::Text(t) if t.trim().is_empty()));
+        }
+        match self.capture.last_mut() {
+            Some((parent, _)) => parent.children.push(XmlNode::Element(element)),
+            None => match self.capture_target {
+                Target::Header => self
+                    .envelope
+                    .header
+                    .push(HeaderElement::Unknown(UnknownHeader::from_element(element))),
+                Target::Body => self.envelope.body.push(BodyElement::Unknown(element)),
+                Target::Extra => {
+                    // the path still ends with the element, below its parent
+                    let parent: Vec<&str> = self.path[2..self.path.len() - 1]
+                        .iter()
+                        .map(AsRef::as_ref)
+                        .collect();
+                    if let Some(extra) = self
+                        .envelope
+                        .body
+                        .last_mut()
+                        .and_then(|be| be.extra_at(&parent))
+                    {
+                        extra.push(element);
+                    }
+                }
+            },
+        }
+        true
+    }
+
+    // true if the characters belong to a captured element
+    pub(super) fn capture_characters(&mut self, characters: &str) -> bool {
+        let Some((element, _)) = self.capture.last_mut() else {
+            return false;
+        };
+        // CDATA and escaped text can come in more than one event
+        if let Some(XmlNode::Text(text)) = element.children.last_mut() {
+            text.push_str(characters);
+        } else {
+            element.children.push(XmlNode::Text(characters.to_string()));
+        }
+        true
+    }
+}
@@ -5,15 +5,19 @@ use quickcheck::{Arbitrary, Gen};
 use xml::writer::XmlEvent;
 
 use super::{
-    cwmp_prefix, write_simple, GenerateError, InstallOp, UninstallOp, UpdateOp, XmlSafeString,
+    check_length, check_mandatory, cwmp_prefix, write_simple, GenerateError, GenerateOptions,
+    InstallOp, UninstallOp, UpdateOp, XmlElement, XmlSafeString,
 };
+use crate::error::Validation;
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ChangeDUState {
     pub command_key: XmlSafeString,
     pub install_operations: Vec<InstallOp>,
     pub uninstall_operations: Vec<UninstallOp>,
     pub update_operations: Vec<UpdateOp>,
+    pub extra: Vec<XmlElement>,
 }
 
 impl ChangeDUState {
@@ -29,9 +33,16 @@ impl ChangeDUState {
             install_operations,
             uninstall_operations,
             update_operations,
+            extra: vec![],
         }
     }
 
+    /// A builder checking the fields against their TR-069 limits
+    #[must_use]
+    pub fn builder() -> ChangeDUStateBuilder {
+        ChangeDUStateBuilder::new()
+    }
+
     /// Generate XML for `ChangeDUState`
     ///     
     /// # Errors
@@ -41,9 +52,10 @@ impl ChangeDUState {
         &self,
         writer: &mut xml::EventWriter<W>,
         has_cwmp: bool,
+        options: &GenerateOptions,
     ) -> Result<(), GenerateError> {
         writer.write(XmlEvent::start_element(
-            &cwmp_prefix(has_cwmp, "ChangeDUState")[..],
+            &cwmp_prefix(options, has_cwmp, "ChangeDUState")[..],
         ))?;
         write_simple(writer, "CommandKey", self.command_key.0.as_ref())?;
         writer.write(XmlEvent::start_element("Operations"))?;
@@ -55,6 +67,9 @@ impl ChangeDUState {
             write_simple(writer, "Username", io.username.0.as_ref())?;
             write_simple(writer, "Password", io.password.0.as_ref())?;
             write_simple(writer, "ExecutionEnvRef", io.execution_env_ref.0.as_ref())?;
+            for e in &io.extra {
+                e.generate(writer)?;
+            }
             writer.write(XmlEvent::end_element())?;
         }
         for uio in &self.uninstall_operations {
@@ -62,6 +77,9 @@ impl ChangeDUState {
             write_simple(writer, "URL", uio.url.0.as_ref())?;
             write_simple(writer, "UUID", uio.uuid.0.as_ref())?;
             write_simple(writer, "ExecutionEnvRef", uio.execution_env_ref.0.as_ref())?;
+            for e in &uio.extra {
+                e.generate(writer)?;
+            }
             writer.write(XmlEvent::end_element())?;
         }
         for uo in &self.update_operations {
@@ -71,10 +89,16 @@ impl ChangeDUState {
             write_simple(writer, "Username", uo.username.0.as_ref())?;
             write_simple(writer, "Password", uo.password.0.as_ref())?;
             write_simple(writer, "Version", uo.version.0.as_ref())?;
+            for e in &uo.extra {
+                e.generate(writer)?;
+            }
             writer.write(XmlEvent::end_element())?;
         }
 
         writer.write(XmlEvent::end_element())?;
+        for e in &self.extra {
+            e.generate(writer)?;
+        }
         writer.write(XmlEvent::end_element())?;
 
         Ok(())
@@ -148,6 +172,83 @@ impl ChangeDUState {
     }
 }
 
+/// Builds a `ChangeDUState`, checking its operations against TR-069 Table 50
+///
+/// At least one operation is mandatory. An install needs a URL and an
+/// uninstall a UUID.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, PartialEq, Eq, Default, Clone)]
+pub struct ChangeDUStateBuilder {
+    change_du_state: ChangeDUState,
+}
+
+impl ChangeDUStateBuilder {
+    #[must_use]
+    pub fn new() -> Self {
+        ChangeDUStateBuilder::default()
+    }
+    #[must_use]
+    pub fn command_key(mut self, command_key: &str) -> Self {
+        self.change_du_state.command_key = command_key.into();
+        self
+    }
+    #[must_use]
+    pub fn install(mut self, operation: InstallOp) -> Self {
+        self.change_du_state.install_operations.push(operation);
+        self
+    }
+    #[must_use]
+    pub fn uninstall(mut self, operation: UninstallOp) -> Self {
+        self.change_du_state.uninstall_operations.push(operation);
+        self
+    }
+    #[must_use]
+    pub fn update(mut self, operation: UpdateOp) -> Self {
+        self.change_du_state.update_operations.push(operation);
+        self
+    }
+
+    /// The `ChangeDUState`, once its operations are checked
+    ///
+    /// # Errors
+    ///
+    /// Returns `error::Validation::Missing` without operations, or for an
+    /// operation without its mandatory field, and `error::Validation::TooLong`
+    /// for a field over its size limit
+    pub fn build(self) -> Result<ChangeDUState, Validation> {
+        let c = self.change_du_state;
+        if c.install_operations.is_empty()
+            && c.uninstall_operations.is_empty()
+            && c.update_operations.is_empty()
+        {
+            return Err(Validation::Missing("Operations".to_string()));
+        }
+        check_length("CommandKey", &c.command_key.0, 32)?;
+        for op in &c.install_operations {
+            check_mandatory("URL", &op.url.0)?;
+            check_length("URL", &op.url.0, 1024)?;
+            check_length("UUID", &op.uuid.0, 36)?;
+            check_length("Username", &op.username.0, 256)?;
+            check_length("Password", &op.password.0, 256)?;
+            check_length("ExecutionEnvRef", &op.execution_env_ref.0, 256)?;
+        }
+        for op in &c.uninstall_operations {
+            check_mandatory("UUID", &op.uuid.0)?;
+            check_length("URL", &op.url.0, 1024)?;
+            check_length("UUID", &op.uuid.0, 36)?;
+            check_length("ExecutionEnvRef", &op.execution_env_ref.0, 256)?;
+        }
+        for op in &c.update_operations {
+            check_length("URL", &op.url.0, 1024)?;
+            check_length("UUID", &op.uuid.0, 36)?;
+            check_length("Username", &op.username.0, 256)?;
+            check_length("Password", &op.password.0, 256)?;
+            check_length("Version", &op.version.0, 32)?;
+        }
+        Ok(c)
+    }
+}
+
 #[cfg(test)]
 impl Arbitrary for ChangeDUState {
     fn arbitrary(g: &mut Gen) -> Self {
@@ -156,6 +257,7 @@ impl Arbitrary for ChangeDUState {
             install_operations: Vec::<InstallOp>::arbitrary(g),
             uninstall_operations: Vec::<UninstallOp>::arbitrary(g),
             update_operations: Vec::<UpdateOp>::arbitrary(g),
+            extra: vec![],
         }
     }
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
@@ -172,6 +274,7 @@ impl Arbitrary for ChangeDUState {
                     install_operations: i,
                     uninstall_operations: un,
                     update_operations: up,
+                    extra: vec![],
                 }),
         )
     }
@@ -1,17 +1,130 @@
+use std::fmt;
+
 #[cfg(test)]
 use quickcheck::{Arbitrary, Gen};
 
-#[derive(Debug, PartialEq, Default, Clone)]
+/// A CWMP version, ordered by major then minor number
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CwmpVersion {
     pub major: u8,
     pub minor: u8,
 }
 
+// the RPCs and headers added after 1.0, with the minor version adding them
+const INTRODUCED: [(&str, u8); 10] = [
+    ("AutonomousTransferComplete", 1),
+    ("CancelTransfer", 1),
+    ("GetAllQueuedTransfers", 1),
+    ("ScheduleDownload", 1),
+    ("AutonomousDUStateChangeComplete", 2),
+    ("ChangeDUState", 2),
+    ("DUStateChangeComplete", 2),
+    ("SessionTimeout", 2),
+    ("SupportedCWMPVersions", 4),
+    ("UseCWMPVersion", 4),
+];
+
+// the RPCs and headers of CWMP 1.0
+const BASE: [&str; 24] = [
+    "AddObject",
+    "DeleteObject",
+    "Download",
+    "FactoryReset",
+    "Fault",
+    "GetOptions",
+    "GetParameterAttributes",
+    "GetParameterNames",
+    "GetParameterValues",
+    "GetQueuedTransfers",
+    "GetRPCMethods",
+    "Inform",
+    "Kicked",
+    "Reboot",
+    "RequestDownload",
+    "ScheduleInform",
+    "SetParameterAttributes",
+    "SetParameterValues",
+    "SetVouchers",
+    "TransferComplete",
+    "Upload",
+    "ID",
+    "HoldRequests",
+    "NoMoreRequests",
+];
+
 impl CwmpVersion {
+    /// The versions defined by TR-069 so far, oldest first
+    pub const KNOWN: [CwmpVersion; 5] = [
+        CwmpVersion::new(1, 0),
+        CwmpVersion::new(1, 1),
+        CwmpVersion::new(1, 2),
+        CwmpVersion::new(1, 3),
+        CwmpVersion::new(1, 4),
+    ];
+
     #[must_use]
-    pub fn new(major: u8, minor: u8) -> Self {
+    pub const fn new(major: u8, minor: u8) -> Self {
         CwmpVersion { major, minor }
     }
+
+    /// Parse a version as written in the `SupportedCWMPVersions` and
+    /// `UseCWMPVersion` headers, like "1.2"
+    #[must_use]
+    pub fn parse(version: &str) -> Option<Self> {
+        let (major, minor) = version.trim().split_once('.')?;
+        Some(CwmpVersion::new(major.parse().ok()?, minor.parse().ok()?))
+    }
+
+    /// Parse a comma separated list of versions, like "1.0,1.2,1.4",
+    /// skipping the ones that cannot be parsed
+    #[must_use]
+    pub fn parse_list(versions: &str) -> Vec<Self> {
+        versions.split(',').filter_map(CwmpVersion::parse).collect()
+    }
+
+    /// The namespace URN of this version, like "urn:dslforum-org:cwmp-1-2"
+    #[must_use]
+    pub fn urn(&self) -> String {
+        format!("urn:dslforum-org:cwmp-{}-{}", self.major, self.minor)
+    }
+
+    /// The version that introduced an RPC or header element, by name.
+    /// `None` for names TR-069 does not define.
+    #[must_use]
+    pub fn introducing(name: &str) -> Option<Self> {
+        let name = name.strip_suffix("Response").unwrap_or(name);
+        if let Some((_, minor)) = INTRODUCED.iter().find(|(n, _)| *n == name) {
+            return Some(CwmpVersion::new(1, *minor));
+        }
+        BASE.contains(&name).then_some(CwmpVersion::new(1, 0))
+    }
+
+    /// True if the RPC or header element `name` exists in this version
+    #[must_use]
+    pub fn supports(&self, name: &str) -> bool {
+        CwmpVersion::introducing(name).is_some_and(|v| v <= *self)
+    }
+}
+
+impl fmt::Display for CwmpVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+/// The version an ACS and a CPE should use: the highest one both support.
+/// `None` if they have none in common.
+#[must_use]
+pub fn negotiate(
+    acs_supported: &[CwmpVersion],
+    cpe_supported: &[CwmpVersion],
+) -> Option<CwmpVersion> {
+    acs_supported
+        .iter()
+        .filter(|v| cpe_supported.contains(v))
+        .max()
+        .cloned()
 }
 
 #[cfg(test)]
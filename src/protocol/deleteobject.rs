@@ -4,12 +4,14 @@ use std::io::Write;
 use quickcheck::{Arbitrary, Gen};
 use xml::writer::XmlEvent;
 
-use super::{cwmp_prefix, write_simple, GenerateError, XmlSafeString};
+use super::{cwmp_prefix, write_simple, GenerateError, GenerateOptions, XmlElement, XmlSafeString};
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DeleteObject {
     pub object_name: XmlSafeString,
     pub parameter_key: XmlSafeString,
+    pub extra: Vec<XmlElement>,
 }
 
 impl DeleteObject {
@@ -18,6 +20,7 @@ impl DeleteObject {
         Self {
             object_name: object_name.into(),
             parameter_key: parameter_key.into(),
+            extra: vec![],
         }
     }
     /// Generate XML for `DeleteObject`
@@ -29,12 +32,16 @@ impl DeleteObject {
         &self,
         writer: &mut xml::EventWriter<W>,
         has_cwmp: bool,
+        options: &GenerateOptions,
     ) -> Result<(), GenerateError> {
         writer.write(XmlEvent::start_element(
-            &cwmp_prefix(has_cwmp, "DeleteObject")[..],
+            &cwmp_prefix(options, has_cwmp, "DeleteObject")[..],
         ))?;
         write_simple(writer, "ObjectName", self.object_name.0.as_ref())?;
         write_simple(writer, "ParameterKey", self.parameter_key.0.as_ref())?;
+        for e in &self.extra {
+            e.generate(writer)?;
+        }
         writer.write(XmlEvent::end_element())?;
 
         Ok(())
@@ -58,6 +65,7 @@ impl Arbitrary for DeleteObject {
         Self {
             object_name: XmlSafeString::arbitrary(g),
             parameter_key: XmlSafeString::arbitrary(g),
+            extra: vec![],
         }
     }
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
@@ -67,6 +75,7 @@ impl Arbitrary for DeleteObject {
                 .map(|(o, p)| DeleteObject {
                     object_name: o,
                     parameter_key: p,
+                    extra: vec![],
                 }),
         )
     }
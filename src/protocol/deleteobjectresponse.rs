@@ -4,11 +4,13 @@ use std::io::Write;
 use quickcheck::{Arbitrary, Gen};
 use xml::writer::XmlEvent;
 
-use super::{cwmp_prefix, write_simple, GenerateError, XmlSafeString};
+use super::{cwmp_prefix, write_simple, GenerateError, GenerateOptions, XmlElement, XmlSafeString};
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DeleteObjectResponse {
     pub status: XmlSafeString,
+    pub extra: Vec<XmlElement>,
 }
 
 impl DeleteObjectResponse {
@@ -16,6 +18,7 @@ impl DeleteObjectResponse {
     pub fn new(status: &str) -> Self {
         DeleteObjectResponse {
             status: status.into(),
+            extra: vec![],
         }
     }
     /// Generate XML for `DeleteObjectResponse`
@@ -27,11 +30,15 @@ impl DeleteObjectResponse {
         &self,
         writer: &mut xml::EventWriter<W>,
         has_cwmp: bool,
+        options: &GenerateOptions,
     ) -> Result<(), GenerateError> {
         writer.write(XmlEvent::start_element(
-            &cwmp_prefix(has_cwmp, "DeleteObjectResponse")[..],
+            &cwmp_prefix(options, has_cwmp, "DeleteObjectResponse")[..],
         ))?;
         write_simple(writer, "Status", self.status.0.as_ref())?;
+        for e in &self.extra {
+            e.generate(writer)?;
+        }
         writer.write(XmlEvent::end_element())?;
 
         Ok(())
@@ -48,14 +55,13 @@ impl Arbitrary for DeleteObjectResponse {
     fn arbitrary(g: &mut Gen) -> Self {
         Self {
             status: XmlSafeString::arbitrary(g),
+            extra: vec![],
         }
     }
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
-        Box::new(
-            self.status
-                .clone()
-                .shrink()
-                .map(|s| DeleteObjectResponse { status: s }),
-        )
+        Box::new(self.status.clone().shrink().map(|s| DeleteObjectResponse {
+            status: s,
+            extra: vec![],
+        }))
     }
 }
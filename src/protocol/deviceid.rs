@@ -1,14 +1,20 @@
 #[cfg(test)]
 use quickcheck::{Arbitrary, Gen};
 
-use super::XmlSafeString;
+use super::{XmlElement, XmlSafeString};
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DeviceId {
     pub manufacturer: XmlSafeString,
     pub oui: XmlSafeString,
     pub product_class: XmlSafeString,
     pub serial_number: XmlSafeString,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub extra: Vec<XmlElement>,
 }
 impl DeviceId {
     #[must_use]
@@ -18,6 +24,7 @@ impl DeviceId {
             oui: oui.into(),
             product_class: product_class.into(),
             serial_number: serial_number.into(),
+            extra: vec![],
         }
     }
 }
@@ -30,6 +37,7 @@ impl Arbitrary for DeviceId {
             oui: XmlSafeString::arbitrary(g),
             product_class: XmlSafeString::arbitrary(g),
             serial_number: XmlSafeString::arbitrary(g),
+            extra: vec![],
         }
     }
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
@@ -46,6 +54,7 @@ impl Arbitrary for DeviceId {
                     oui: o,
                     product_class: p,
                     serial_number: s,
+                    extra: vec![],
                 }),
         )
     }
@@ -0,0 +1,11 @@
+/// Which end of a CWMP session sends a body element
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Direction {
+    /// Requests of the ACS and responses of the CPE
+    AcsToCpe,
+    /// Requests of the CPE and responses of the ACS
+    CpeToAcs,
+    /// Sent by both, like GetRPCMethods and Fault
+    Both,
+}
@@ -4,9 +4,14 @@ use std::io::Write;
 use quickcheck::{Arbitrary, Gen};
 use xml::writer::XmlEvent;
 
-use super::{cwmp_prefix, parse_to_int, write_simple, GenerateError, XmlSafeString};
+use super::{
+    check_length, check_mandatory, cwmp_prefix, parse_to_int, write_simple, FileType,
+    GenerateError, GenerateOptions, XmlElement, XmlSafeString,
+};
+use crate::error::Validation;
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Download {
     command_key: XmlSafeString,
     file_type: XmlSafeString,
@@ -18,6 +23,7 @@ pub struct Download {
     delay_seconds: u32,
     success_url: XmlSafeString,
     failure_url: XmlSafeString,
+    pub extra: Vec<XmlElement>,
 }
 
 impl Download {
@@ -46,8 +52,55 @@ impl Download {
             delay_seconds,
             success_url: success_url.into(),
             failure_url: failure_url.into(),
+            extra: vec![],
         }
     }
+
+    /// A builder checking the fields against their TR-069 limits
+    #[must_use]
+    pub fn builder() -> DownloadBuilder {
+        DownloadBuilder::new()
+    }
+    #[must_use]
+    pub fn command_key(&self) -> &str {
+        &self.command_key.0
+    }
+    #[must_use]
+    pub fn file_type(&self) -> FileType {
+        FileType::from(self.file_type.0.as_ref())
+    }
+    #[must_use]
+    pub fn url(&self) -> &str {
+        &self.url.0
+    }
+    #[must_use]
+    pub fn username(&self) -> &str {
+        &self.username.0
+    }
+    #[must_use]
+    pub fn password(&self) -> &str {
+        &self.password.0
+    }
+    #[must_use]
+    pub fn file_size(&self) -> u32 {
+        self.file_size
+    }
+    #[must_use]
+    pub fn target_filename(&self) -> &str {
+        &self.target_filename.0
+    }
+    #[must_use]
+    pub fn delay_seconds(&self) -> u32 {
+        self.delay_seconds
+    }
+    #[must_use]
+    pub fn success_url(&self) -> &str {
+        &self.success_url.0
+    }
+    #[must_use]
+    pub fn failure_url(&self) -> &str {
+        &self.failure_url.0
+    }
     /// Generate XML for `Download`
     ///     
     /// # Errors
@@ -57,9 +110,10 @@ impl Download {
         &self,
         writer: &mut xml::EventWriter<W>,
         has_cwmp: bool,
+        options: &GenerateOptions,
     ) -> Result<(), GenerateError> {
         writer.write(XmlEvent::start_element(
-            &cwmp_prefix(has_cwmp, "Download")[..],
+            &cwmp_prefix(options, has_cwmp, "Download")[..],
         ))?;
         write_simple(writer, "CommandKey", self.command_key.0.as_ref())?;
         write_simple(writer, "FileType", self.file_type.0.as_ref())?;
@@ -71,6 +125,9 @@ impl Download {
         write_simple(writer, "DelaySeconds", &self.delay_seconds.to_string())?;
         write_simple(writer, "SuccessURL", self.success_url.0.as_ref())?;
         write_simple(writer, "FailureURL", self.failure_url.0.as_ref())?;
+        for e in &self.extra {
+            e.generate(writer)?;
+        }
         writer.write(XmlEvent::end_element())?;
 
         Ok(())
@@ -92,6 +149,100 @@ impl Download {
     }
 }
 
+/// Builds a `Download`, checking its fields against TR-069 Table 26
+///
+/// The file type and URL are mandatory, everything else defaults to empty
+/// or zero.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, PartialEq, Eq, Default, Clone)]
+pub struct DownloadBuilder {
+    download: Download,
+}
+
+impl DownloadBuilder {
+    #[must_use]
+    pub fn new() -> Self {
+        DownloadBuilder::default()
+    }
+    #[must_use]
+    pub fn command_key(mut self, command_key: &str) -> Self {
+        self.download.command_key = command_key.into();
+        self
+    }
+    #[must_use]
+    pub fn file_type(mut self, file_type: FileType) -> Self {
+        self.download.file_type = file_type.to_string().as_str().into();
+        self
+    }
+    #[must_use]
+    pub fn url(mut self, url: &str) -> Self {
+        self.download.url = url.into();
+        self
+    }
+    #[must_use]
+    pub fn username(mut self, username: &str) -> Self {
+        self.download.username = username.into();
+        self
+    }
+    #[must_use]
+    pub fn password(mut self, password: &str) -> Self {
+        self.download.password = password.into();
+        self
+    }
+    #[must_use]
+    pub fn file_size(mut self, file_size: u32) -> Self {
+        self.download.file_size = file_size;
+        self
+    }
+    #[must_use]
+    pub fn target_filename(mut self, target_filename: &str) -> Self {
+        self.download.target_filename = target_filename.into();
+        self
+    }
+    #[must_use]
+    pub fn delay_seconds(mut self, delay_seconds: u32) -> Self {
+        self.download.delay_seconds = delay_seconds;
+        self
+    }
+    #[must_use]
+    pub fn success_url(mut self, success_url: &str) -> Self {
+        self.download.success_url = success_url.into();
+        self
+    }
+    #[must_use]
+    pub fn failure_url(mut self, failure_url: &str) -> Self {
+        self.download.failure_url = failure_url.into();
+        self
+    }
+
+    /// The `Download`, once its fields are checked
+    ///
+    /// # Errors
+    ///
+    /// Returns `error::Validation::Missing` without a file type or URL, and
+    /// `error::Validation::TooLong` for a field over its size limit
+    pub fn build(self) -> Result<Download, Validation> {
+        let d = self.download;
+        check_mandatory("FileType", &d.file_type.0)?;
+        check_mandatory("URL", &d.url.0)?;
+        check_length("CommandKey", &d.command_key.0, 32)?;
+        check_length("FileType", &d.file_type.0, 64)?;
+        if !d.file_type().is_download() {
+            return Err(Validation::WrongFileType {
+                file_type: d.file_type.0,
+                rpc: "Download".to_string(),
+            });
+        }
+        check_length("URL", &d.url.0, 256)?;
+        check_length("Username", &d.username.0, 256)?;
+        check_length("Password", &d.password.0, 256)?;
+        check_length("TargetFileName", &d.target_filename.0, 256)?;
+        check_length("SuccessURL", &d.success_url.0, 256)?;
+        check_length("FailureURL", &d.failure_url.0, 256)?;
+        Ok(d)
+    }
+}
+
 #[cfg(test)]
 impl Arbitrary for Download {
     fn arbitrary(g: &mut Gen) -> Self {
@@ -106,6 +257,7 @@ impl Arbitrary for Download {
             delay_seconds: u32::arbitrary(g),
             success_url: XmlSafeString::arbitrary(g),
             failure_url: XmlSafeString::arbitrary(g),
+            extra: vec![],
         }
     }
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
@@ -132,6 +284,7 @@ impl Arbitrary for Download {
                     delay_seconds: ds,
                     success_url: XmlSafeString::new(),
                     failure_url: XmlSafeString::new(),
+                    extra: vec![],
                 }),
         )
     }
@@ -7,13 +7,15 @@ use xml::writer::XmlEvent;
 
 #[cfg(test)]
 use super::gen_utc_date;
-use super::{cwmp_prefix, write_simple, GenerateError, XmlSafeString};
+use super::{cwmp_prefix, write_simple, GenerateError, GenerateOptions, XmlElement, XmlSafeString};
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DownloadResponse {
     pub status: XmlSafeString,
     pub start_time: Option<DateTime<Utc>>,
     pub complete_time: Option<DateTime<Utc>>,
+    pub extra: Vec<XmlElement>,
 }
 
 impl DownloadResponse {
@@ -23,6 +25,7 @@ impl DownloadResponse {
             status: status.into(),
             start_time: Some(start_time),
             complete_time: Some(complete_time),
+            extra: vec![],
         }
     }
 
@@ -35,9 +38,10 @@ impl DownloadResponse {
         &self,
         writer: &mut xml::EventWriter<W>,
         has_cwmp: bool,
+        options: &GenerateOptions,
     ) -> Result<(), GenerateError> {
         writer.write(XmlEvent::start_element(
-            &cwmp_prefix(has_cwmp, "DownloadResponse")[..],
+            &cwmp_prefix(options, has_cwmp, "DownloadResponse")[..],
         ))?;
         write_simple(writer, "Status", self.status.0.as_ref())?;
         match self.start_time {
@@ -48,6 +52,9 @@ impl DownloadResponse {
             None => {}
             Some(dt) => write_simple(writer, "CompleteTime", &dt.to_rfc3339())?,
         }
+        for e in &self.extra {
+            e.generate(writer)?;
+        }
         writer.write(XmlEvent::end_element())?;
 
         Ok(())
@@ -58,12 +65,12 @@ impl DownloadResponse {
                 self.status = characters.into();
             }
             ["DownloadResponse", "StartTime"] => {
-                if let Ok(dt) = characters.parse::<DateTime<Utc>>() {
+                if let Ok(dt) = characters.trim().parse::<DateTime<Utc>>() {
                     self.start_time = Some(dt);
                 }
             }
             ["DownloadResponse", "CompleteTime"] => {
-                if let Ok(dt) = characters.parse::<DateTime<Utc>>() {
+                if let Ok(dt) = characters.trim().parse::<DateTime<Utc>>() {
                     self.complete_time = Some(dt);
                 }
             }
@@ -81,6 +88,7 @@ impl Arbitrary for DownloadResponse {
             status: XmlSafeString::arbitrary(g),
             start_time: Some(bogus_st),
             complete_time: Some(bogus_ct),
+            extra: vec![],
         }
     }
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
@@ -88,6 +96,7 @@ impl Arbitrary for DownloadResponse {
             status: s,
             start_time: Some(gen_utc_date(2014, 11, 28, 12, 0, 9)),
             complete_time: Some(gen_utc_date(2014, 11, 29, 12, 0, 9)),
+            extra: vec![],
         }))
     }
 }
@@ -6,13 +6,16 @@ use quickcheck::{Arbitrary, Gen};
 use xml::writer::XmlEvent;
 
 use super::{
-    cwmp_prefix, parse_to_int, write_fault, write_simple, GenerateError, OpResult, XmlSafeString,
+    cwmp_array_type, cwmp_prefix, parse_to_int, write_array_start, write_fault, write_simple,
+    GenerateError, GenerateOptions, OpResult, XmlElement, XmlSafeString,
 };
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DUStateChangeComplete {
     pub command_key: XmlSafeString,
     pub results: Vec<OpResult>,
+    pub extra: Vec<XmlElement>,
 }
 
 impl DUStateChangeComplete {
@@ -21,6 +24,7 @@ impl DUStateChangeComplete {
         DUStateChangeComplete {
             command_key: command_key.into(),
             results,
+            extra: vec![],
         }
     }
 
@@ -33,18 +37,15 @@ impl DUStateChangeComplete {
         &self,
         writer: &mut xml::EventWriter<W>,
         has_cwmp: bool,
+        options: &GenerateOptions,
     ) -> Result<(), GenerateError> {
         writer.write(XmlEvent::start_element(
-            &cwmp_prefix(has_cwmp, "DUStateChangeComplete")[..],
+            &cwmp_prefix(options, has_cwmp, "DUStateChangeComplete")[..],
         ))?;
         write_simple(writer, "CommandKey", self.command_key.0.as_ref())?;
-        let ss = if has_cwmp {
-            format!("cwmp:OpResultStruct[{}]", self.results.len())
-        } else {
-            format!("OpResultStruct[{}]", self.results.len())
-        };
+        let ss = cwmp_array_type(options, has_cwmp, "OpResultStruct", self.results.len());
 
-        writer.write(XmlEvent::start_element("Results").attr("SOAP-ENC:arrayType", &ss[..]))?;
+        write_array_start(writer, options, "Results", &ss)?;
 
         for r in &self.results {
             writer.write(XmlEvent::start_element("OpResultStruct"))?;
@@ -71,9 +72,15 @@ impl DUStateChangeComplete {
                 Some(dt) => write_simple(writer, "CompleteTime", &dt.to_rfc3339())?,
             }
             write_fault(writer, &r.fault)?;
+            for e in &r.extra {
+                e.generate(writer)?;
+            }
             writer.write(XmlEvent::end_element())?;
         }
         writer.write(XmlEvent::end_element())?;
+        for e in &self.extra {
+            e.generate(writer)?;
+        }
         writer.write(XmlEvent::end_element())?;
 
         Ok(())
@@ -106,12 +113,12 @@ impl DUStateChangeComplete {
                             e.execution_unit_ref_list = characters.into();
                         }
                         "StartTime" => {
-                            if let Ok(dt) = characters.parse::<DateTime<Utc>>() {
+                            if let Ok(dt) = characters.trim().parse::<DateTime<Utc>>() {
                                 e.start_time = Some(dt);
                             }
                         }
                         "CompleteTime" => {
-                            if let Ok(dt) = characters.parse::<DateTime<Utc>>() {
+                            if let Ok(dt) = characters.trim().parse::<DateTime<Utc>>() {
                                 e.complete_time = Some(dt);
                             }
                         }
@@ -140,6 +147,7 @@ impl Arbitrary for DUStateChangeComplete {
         Self {
             command_key: XmlSafeString::arbitrary(g),
             results: Vec::<OpResult>::arbitrary(g),
+            extra: vec![],
         }
     }
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
@@ -149,6 +157,7 @@ impl Arbitrary for DUStateChangeComplete {
                 .map(|(c, r)| DUStateChangeComplete {
                     command_key: c,
                     results: r,
+                    extra: vec![],
                 }),
         )
     }
@@ -1,28 +1,33 @@
+use std::io::Write;
+
 use log::warn;
 use xml::{writer::XmlEvent, EmitterConfig};
 
+use crate::error::Violation;
+
 use super::{
     cwmp_urn_to_version, parse_to_int, str2bool, AddObject, AddObjectResponse,
     AutonomousDUStateChangeComplete, AutonomousDUStateChangeCompleteResponse,
     AutonomousTransferComplete, AutonomousTransferCompleteResponse, BodyElement, CancelTransfer,
     CancelTransferResponse, ChangeDUState, ChangeDUStateResponse, CwmpVersion,
     DUStateChangeComplete, DUStateChangeCompleteResponse, DeleteObject, DeleteObjectResponse,
-    Download, DownloadResponse, FactoryReset, FactoryResetResponse, Fault, GenerateError,
-    GetAllQueuedTransfers, GetAllQueuedTransfersResponse, GetOptions, GetOptionsResponse,
-    GetParameterAttributes, GetParameterAttributesResponse, GetParameterNames,
-    GetParameterNamesResponse, GetParameterValues, GetParameterValuesResponse, GetQueuedTransfers,
-    GetQueuedTransfersResponse, GetRPCMethods, GetRPCMethodsResponse, HeaderElement, HoldRequests,
-    Inform, InformResponse, Kicked, KickedResponse, NoMoreRequests, Reboot, RebootResponse,
-    RequestDownload, RequestDownloadResponse, ScheduleDownload, ScheduleDownloadResponse,
-    ScheduleInform, ScheduleInformResponse, SessionTimeout, SetParameterAttributes,
-    SetParameterAttributesResponse, SetParameterValues, SetParameterValuesResponse, SetVouchers,
-    SetVouchersResponse, SupportedCWMPVersions, TransferComplete, TransferCompleteResponse, Upload,
-    UploadResponse, UseCWMPVersion, ID,
+    Direction, Download, DownloadResponse, FactoryReset, FactoryResetResponse, Fault,
+    GenerateError, GenerateOptions, GetAllQueuedTransfers, GetAllQueuedTransfersResponse,
+    GetOptions, GetOptionsResponse, GetParameterAttributes, GetParameterAttributesResponse,
+    GetParameterNames, GetParameterNamesResponse, GetParameterValues, GetParameterValuesResponse,
+    GetQueuedTransfers, GetQueuedTransfersResponse, GetRPCMethods, GetRPCMethodsResponse,
+    HeaderElement, HoldRequests, Inform, InformResponse, Kicked, KickedResponse, NoMoreRequests,
+    Reboot, RebootResponse, RequestDownload, RequestDownloadResponse, ScheduleDownload,
+    ScheduleDownloadResponse, ScheduleInform, ScheduleInformResponse, SessionTimeout,
+    SetParameterAttributes, SetParameterAttributesResponse, SetParameterValues,
+    SetParameterValuesResponse, SetVouchers, SetVouchersResponse, SupportedCWMPVersions,
+    TransferComplete, TransferCompleteResponse, Upload, UploadResponse, UseCWMPVersion, ID,
 };
 #[cfg(test)]
 use quickcheck::{Arbitrary, Gen};
 
 #[derive(Debug, PartialEq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Envelope {
     pub cwmp_version: Option<CwmpVersion>,
     pub header: Vec<HeaderElement>,
@@ -61,57 +66,199 @@ impl Envelope {
             .any(|v| matches!(v, BodyElement::Inform(_)))
     }
 
+    /// The versions listed in the `SupportedCWMPVersions` header, empty if there is none
+    #[must_use]
+    pub fn supported_cwmp_versions(&self) -> Vec<CwmpVersion> {
+        self.header
+            .iter()
+            .find_map(|h| match h {
+                HeaderElement::SupportedCWMPVersions(s) => Some(s.versions()),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    /// The version of the `UseCWMPVersion` header, if any
+    #[must_use]
+    pub fn use_cwmp_version(&self) -> Option<CwmpVersion> {
+        self.header.iter().find_map(|h| match h {
+            HeaderElement::UseCWMPVersion(u) => u.version(),
+            _ => None,
+        })
+    }
+
+    /// Check that every body element exists in `cwmp_version`, the version
+    /// the envelope is generated with, see `BodyElement::min_version`. An
+    /// envelope without cwmp namespace is not checked.
+    ///
+    /// # Errors
+    ///
+    /// Returns `GenerateError::Incompatible` for the first body element
+    /// needing a newer version
+    pub fn check_version(&self) -> Result<(), GenerateError> {
+        let Some(version) = self.cwmp_version.clone() else {
+            return Ok(());
+        };
+        for be in &self.body {
+            let required = be.min_version();
+            if required > version {
+                return Err(GenerateError::Incompatible {
+                    element: be.rpc_name().to_string(),
+                    required,
+                    version,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Check the envelope against the TR-069 rules that span its header and
+    /// body, returning every rule it breaks. An empty list means it is valid.
+    #[must_use]
+    pub fn validate(&self) -> Vec<Violation> {
+        let mut violations = vec![];
+        if self.body.len() != 1 {
+            violations.push(Violation::BodyElementCount(self.body.len()));
+        }
+        for he in &self.header {
+            match he {
+                HeaderElement::NoMoreRequests(_) => {
+                    if let Some(be) = self
+                        .body
+                        .iter()
+                        .find(|be| be.direction() == Direction::AcsToCpe)
+                    {
+                        violations
+                            .push(Violation::NoMoreRequestsFromAcs(be.rpc_name().to_string()));
+                    }
+                }
+                HeaderElement::HoldRequests(_) => {
+                    if let Some(be) = self
+                        .body
+                        .iter()
+                        .find(|be| be.direction() == Direction::CpeToAcs)
+                    {
+                        violations.push(Violation::HoldRequestsFromCpe(be.rpc_name().to_string()));
+                    }
+                }
+                HeaderElement::Unknown(h) if h.must_understand => {
+                    violations.push(Violation::MustUnderstand(h.name.clone()));
+                }
+                HeaderElement::UseCWMPVersion(u) => {
+                    if let Some(v) = &self.cwmp_version {
+                        if u.version().as_ref() != Some(v) {
+                            violations.push(Violation::VersionMismatch {
+                                used: u.value.clone(),
+                                envelope: v.to_string(),
+                            });
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        let has_id = self
+            .header
+            .iter()
+            .any(|h| matches!(h, HeaderElement::ID(_)));
+        if !has_id {
+            if let Some(be) = self
+                .body
+                .iter()
+                .find(|be| !be.is_response() && !matches!(be, BodyElement::Inform(_)))
+            {
+                violations.push(Violation::MissingId(be.rpc_name().to_string()));
+            }
+        }
+        violations
+    }
+
     /// Generate XML for `Envelope`
     ///     
     /// # Errors
     /// Any errors encountered while writing to `writer` will be returned.
-    #[allow(clippy::too_many_lines)]
     pub fn generate(&self) -> Result<String, GenerateError> {
+        let mut xml = Vec::new();
+        self.write_to(&mut xml, &GenerateOptions::default())?;
+        Ok(String::from_utf8(xml)?)
+    }
+
+    /// Write the XML for `Envelope` straight into `w`, without building
+    /// the whole document in memory first. Wrap sockets and files in a
+    /// `BufWriter`, the XML is written in many small pieces.
+    ///
+    /// # Errors
+    /// Any errors encountered while writing to `w` will be returned.
+    #[allow(clippy::too_many_lines)]
+    pub fn write_to<W: Write>(
+        &self,
+        w: W,
+        options: &GenerateOptions,
+    ) -> Result<(), GenerateError> {
+        if options.check_version {
+            self.check_version()?;
+        }
         let mut writer = EmitterConfig::new()
-            .perform_indent(true)
-            .create_writer(Vec::new());
+            .perform_indent(options.indent)
+            .write_document_declaration(options.declaration)
+            .create_writer(w);
 
-        let mut start_event = XmlEvent::start_element("Envelope")
-            .ns("SOAP-ENV", "http://schemas.xmlsoap.org/soap/envelope/")
-            .ns("SOAP-ENC", "http://schemas.xmlsoap.org/soap/encoding/")
+        let env = |name: &str| format!("{}:{name}", options.envelope_prefix);
+
+        let envelope = env("Envelope");
+        let encoding_style = env("encodingStyle");
+        let mut start_event = XmlEvent::start_element(&envelope[..])
+            .ns(
+                &options.envelope_prefix[..],
+                "http://schemas.xmlsoap.org/soap/envelope/",
+            )
+            .ns(
+                &options.encoding_prefix[..],
+                "http://schemas.xmlsoap.org/soap/encoding/",
+            )
             .ns("xsi", "http://www.w3.org/2001/XMLSchema-instance")
             .ns("xsd", "http://www.w3.org/2001/XMLSchema");
 
-        if let Some(cwmp) = &self.cwmp_version {
-            start_event = start_event.ns(
-                "cwmp",
-                format!("urn:dslforum-org:cwmp-{}-{}", cwmp.major, cwmp.minor),
+        // a UseCWMPVersion header disagreeing with it is left to `validate`
+        let urn = self.cwmp_version.as_ref().map(CwmpVersion::urn);
+        if let Some(urn) = &urn {
+            start_event = start_event.ns(&options.cwmp_prefix[..], &urn[..]);
+        }
+        if options.encoding_style {
+            start_event = start_event.attr(
+                &encoding_style[..],
+                "http://schemas.xmlsoap.org/soap/encoding/",
             );
         }
-        start_event = start_event.attr(
-            "SOAP-ENV:encodingStyle",
-            "http://schemas.xmlsoap.org/soap/encoding/",
-        );
 
         writer.write(start_event)?;
 
         // now generate the header elements
-        let start_header = XmlEvent::start_element("SOAP-ENV:Header");
+        let header = env("Header");
+        let start_header = XmlEvent::start_element(&header[..]);
         writer.write(start_header)?;
 
         for he in &self.header {
             match he {
-                HeaderElement::ID(e) => e.generate(&mut writer, self.cwmp_version.is_some())?,
+                HeaderElement::ID(e) => {
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?
+                }
                 HeaderElement::HoldRequests(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 HeaderElement::NoMoreRequests(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 HeaderElement::SessionTimeout(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 HeaderElement::SupportedCWMPVersions(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 HeaderElement::UseCWMPVersion(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
+                HeaderElement::Unknown(h) => h.element.generate(&mut writer)?,
             };
         }
 
@@ -119,164 +266,181 @@ impl Envelope {
         writer.write(end_header)?;
 
         // now generate the body elemenets
-        let body_start = XmlEvent::start_element("SOAP-ENV:Body");
+        let body = env("Body");
+        let body_start = XmlEvent::start_element(&body[..]);
         writer.write(body_start)?;
 
         for be in &self.body {
             match be {
                 BodyElement::AddObject(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 BodyElement::AddObjectResponse(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 BodyElement::AutonomousDUStateChangeComplete(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 BodyElement::AutonomousDUStateChangeCompleteResponse(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 BodyElement::AutonomousTransferComplete(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 BodyElement::AutonomousTransferCompleteResponse(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 BodyElement::CancelTransferResponse(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 BodyElement::CancelTransfer(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 BodyElement::ChangeDUStateResponse(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 BodyElement::ChangeDUState(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 BodyElement::DeleteObjectResponse(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 BodyElement::DeleteObject(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 BodyElement::DownloadResponse(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
+                }
+                BodyElement::Download(e) => {
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?
                 }
-                BodyElement::Download(e) => e.generate(&mut writer, self.cwmp_version.is_some())?,
                 BodyElement::DUStateChangeCompleteResponse(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 BodyElement::DUStateChangeComplete(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 BodyElement::FactoryResetResponse(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 BodyElement::FactoryReset(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
+                }
+                BodyElement::Fault(e) => {
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?
                 }
-                BodyElement::Fault(e) => e.generate(&mut writer, self.cwmp_version.is_some())?,
                 BodyElement::GetAllQueuedTransfersResponse(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 BodyElement::GetAllQueuedTransfers(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 BodyElement::GetOptionsResponse(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 BodyElement::GetOptions(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 BodyElement::GetParameterAttributes(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 BodyElement::GetParameterAttributesResponse(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 BodyElement::GetParameterNamesResponse(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 BodyElement::GetParameterNames(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 BodyElement::GetParameterValues(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 BodyElement::GetParameterValuesResponse(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 BodyElement::GetQueuedTransfersResponse(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 BodyElement::GetQueuedTransfers(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 BodyElement::GetRPCMethodsResponse(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 BodyElement::GetRPCMethods(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 BodyElement::InformResponse(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
+                }
+                BodyElement::Inform(e) => {
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?
                 }
-                BodyElement::Inform(e) => e.generate(&mut writer, self.cwmp_version.is_some())?,
                 BodyElement::KickedResponse(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
+                }
+                BodyElement::Kicked(e) => {
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?
                 }
-                BodyElement::Kicked(e) => e.generate(&mut writer, self.cwmp_version.is_some())?,
                 BodyElement::RebootResponse(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
+                }
+                BodyElement::Reboot(e) => {
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?
                 }
-                BodyElement::Reboot(e) => e.generate(&mut writer, self.cwmp_version.is_some())?,
                 BodyElement::RequestDownloadResponse(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 BodyElement::RequestDownload(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 BodyElement::ScheduleDownloadResponse(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 BodyElement::ScheduleDownload(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 BodyElement::ScheduleInformResponse(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 BodyElement::ScheduleInform(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 BodyElement::SetParameterAttributesResponse(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 BodyElement::SetParameterAttributes(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 BodyElement::SetParameterValuesResponse(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 BodyElement::SetParameterValues(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 BodyElement::SetVouchersResponse(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 BodyElement::SetVouchers(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 BodyElement::TransferCompleteResponse(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
                 BodyElement::TransferComplete(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
+                }
+                BodyElement::Upload(e) => {
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?
                 }
-                BodyElement::Upload(e) => e.generate(&mut writer, self.cwmp_version.is_some())?,
                 BodyElement::UploadResponse(e) => {
-                    e.generate(&mut writer, self.cwmp_version.is_some())?;
+                    e.generate(&mut writer, self.cwmp_version.is_some(), options)?;
                 }
+                BodyElement::Unknown(e) => e.generate(&mut writer)?,
+                BodyElement::Vendor(e) => e
+                    .to_element(self.cwmp_version.is_some(), options)
+                    .generate(&mut writer)?,
             }
         }
 
@@ -287,8 +451,7 @@ impl Envelope {
         let end_event: XmlEvent = XmlEvent::end_element().into();
         writer.write(end_event)?;
 
-        // Excape invalid XML characters and return the XML
-        Ok(String::from_utf8(writer.into_inner())?)
+        Ok(())
     }
 
     #[allow(clippy::too_many_lines)]
@@ -304,8 +467,15 @@ impl Envelope {
         let path_pattern: Vec<&str> = path.iter().map(AsRef::as_ref).collect();
         match &path_pattern[..] {
             ["Envelope"] => {
-                // search through the namespaces to find a cwmp value
-                match namespace.get("cwmp") {
+                // search through the namespaces to find a cwmp value, the
+                // prefix is usually "cwmp" but any prefix may be bound to the urn
+                let cwmp_ns = namespace.get("cwmp").or_else(|| {
+                    namespace
+                        .iter()
+                        .map(|(_, uri)| uri)
+                        .find(|uri| uri.starts_with("urn:dslforum-org:cwmp-"))
+                });
+                match cwmp_ns {
                     Some(ns) => self.cwmp_version = Some(cwmp_urn_to_version(ns)),
                     None => self.cwmp_version = None,
                 }
@@ -361,6 +531,7 @@ impl Envelope {
                                 String::new(),
                             )));
                     }
+                    // unknown headers are captured by the parser state
                     _ => {}
                 }
             }
@@ -571,6 +742,9 @@ impl Envelope {
                     Some(BodyElement::DUStateChangeComplete(e)) => {
                         e.start_handler(&path_pattern[2..], name, attributes);
                     }
+                    Some(BodyElement::Fault(e)) => {
+                        e.start_handler(&path_pattern[2..], name, attributes);
+                    }
                     Some(BodyElement::GetAllQueuedTransfersResponse(e)) => {
                         e.start_handler(&path_pattern[2..], name, attributes);
                     }
@@ -607,6 +781,9 @@ impl Envelope {
                     Some(BodyElement::SetVouchers(e)) => {
                         e.start_handler(&path_pattern[2..], name, attributes);
                     }
+                    Some(BodyElement::Vendor(e)) => {
+                        e.start_handler(&path_pattern[2..], name, attributes);
+                    }
                     Some(_unhandled) => { // the ones who dont need a start_handler, ie GetParameterValues aso
                     }
                     None => {
@@ -629,7 +806,7 @@ impl Envelope {
         {}
     }
 
-    #[allow(clippy::too_many_lines)]
+    #[allow(clippy::too_many_lines, clippy::collapsible_match)]
     pub fn characters(&mut self, path: &[String], characters: &str) {
         // println!("Path: {:?} Chars: {}", path, characters);
         let path_pattern: Vec<&str> = path.iter().map(AsRef::as_ref).collect();
@@ -773,6 +950,7 @@ impl Envelope {
                         e.characters(&path_pattern[2..], characters);
                     }
                     Some(BodyElement::Upload(e)) => e.characters(&path_pattern[2..], characters),
+                    Some(BodyElement::Vendor(e)) => e.characters(&path_pattern[2..], characters),
                     Some(unhandled) => {
                         println!("characters for {unhandled:?} is so far unhandled");
                     }
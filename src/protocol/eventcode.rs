@@ -0,0 +1,147 @@
+use std::fmt;
+
+use super::CwmpVersion;
+
+/// Inform event codes as defined in TR-069 Table 8 (section 3.7.1.5)
+///
+/// The numbered codes are single events, the "M" codes are multiple events
+/// that carry the command key of the method that caused them. Vendor events
+/// are written "X <OUI> <event>". Any other code is kept as `Other` so no
+/// information is lost.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EventCode {
+    Bootstrap,
+    Boot,
+    Periodic,
+    Scheduled,
+    ValueChange,
+    Kicked,
+    ConnectionRequest,
+    TransferComplete,
+    DiagnosticsComplete,
+    RequestDownload,
+    AutonomousTransferComplete,
+    DUStateChangeComplete,
+    AutonomousDUStateChangeComplete,
+    Wakeup,
+    Heartbeat,
+    MReboot,
+    MScheduleInform,
+    MDownload,
+    MScheduleDownload,
+    MUpload,
+    MChangeDUState,
+    /// "X <OUI> <event>", a vendor specific event
+    Vendor {
+        oui: String,
+        event: String,
+    },
+    /// Any code not covered by TR-069
+    Other(String),
+}
+
+impl EventCode {
+    /// True for the "M" codes, which carry the command key of the method that caused them
+    #[must_use]
+    pub fn is_multiple(&self) -> bool {
+        matches!(
+            self,
+            EventCode::MReboot
+                | EventCode::MScheduleInform
+                | EventCode::MDownload
+                | EventCode::MScheduleDownload
+                | EventCode::MUpload
+                | EventCode::MChangeDUState
+        )
+    }
+
+    #[must_use]
+    pub fn is_vendor(&self) -> bool {
+        matches!(self, EventCode::Vendor { .. })
+    }
+
+    /// The oldest CWMP version defining this event
+    #[must_use]
+    pub fn min_version(&self) -> CwmpVersion {
+        match self {
+            EventCode::AutonomousTransferComplete | EventCode::MScheduleDownload => {
+                CwmpVersion::new(1, 1)
+            }
+            EventCode::DUStateChangeComplete
+            | EventCode::AutonomousDUStateChangeComplete
+            | EventCode::MChangeDUState => CwmpVersion::new(1, 2),
+            EventCode::Wakeup => CwmpVersion::new(1, 3),
+            EventCode::Heartbeat => CwmpVersion::new(1, 4),
+            _ => CwmpVersion::new(1, 0),
+        }
+    }
+}
+
+impl From<&str> for EventCode {
+    fn from(code: &str) -> Self {
+        match code {
+            "0 BOOTSTRAP" => EventCode::Bootstrap,
+            "1 BOOT" => EventCode::Boot,
+            "2 PERIODIC" => EventCode::Periodic,
+            "3 SCHEDULED" => EventCode::Scheduled,
+            "4 VALUE CHANGE" => EventCode::ValueChange,
+            "5 KICKED" => EventCode::Kicked,
+            "6 CONNECTION REQUEST" => EventCode::ConnectionRequest,
+            "7 TRANSFER COMPLETE" => EventCode::TransferComplete,
+            "8 DIAGNOSTICS COMPLETE" => EventCode::DiagnosticsComplete,
+            "9 REQUEST DOWNLOAD" => EventCode::RequestDownload,
+            "10 AUTONOMOUS TRANSFER COMPLETE" => EventCode::AutonomousTransferComplete,
+            "11 DU STATE CHANGE COMPLETE" => EventCode::DUStateChangeComplete,
+            "12 AUTONOMOUS DU STATE CHANGE COMPLETE" => EventCode::AutonomousDUStateChangeComplete,
+            "13 WAKEUP" => EventCode::Wakeup,
+            "14 HEARTBEAT" => EventCode::Heartbeat,
+            "M Reboot" => EventCode::MReboot,
+            "M ScheduleInform" => EventCode::MScheduleInform,
+            "M Download" => EventCode::MDownload,
+            "M ScheduleDownload" => EventCode::MScheduleDownload,
+            "M Upload" => EventCode::MUpload,
+            "M ChangeDUState" => EventCode::MChangeDUState,
+            _ => match code.strip_prefix("X ").and_then(|v| v.split_once(' ')) {
+                Some((oui, event)) if !oui.is_empty() && !event.is_empty() => EventCode::Vendor {
+                    oui: oui.to_string(),
+                    event: event.to_string(),
+                },
+                _ => EventCode::Other(code.to_string()),
+            },
+        }
+    }
+}
+
+impl fmt::Display for EventCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EventCode::Bootstrap => f.write_str("0 BOOTSTRAP"),
+            EventCode::Boot => f.write_str("1 BOOT"),
+            EventCode::Periodic => f.write_str("2 PERIODIC"),
+            EventCode::Scheduled => f.write_str("3 SCHEDULED"),
+            EventCode::ValueChange => f.write_str("4 VALUE CHANGE"),
+            EventCode::Kicked => f.write_str("5 KICKED"),
+            EventCode::ConnectionRequest => f.write_str("6 CONNECTION REQUEST"),
+            EventCode::TransferComplete => f.write_str("7 TRANSFER COMPLETE"),
+            EventCode::DiagnosticsComplete => f.write_str("8 DIAGNOSTICS COMPLETE"),
+            EventCode::RequestDownload => f.write_str("9 REQUEST DOWNLOAD"),
+            EventCode::AutonomousTransferComplete => f.write_str("10 AUTONOMOUS TRANSFER COMPLETE"),
+            EventCode::DUStateChangeComplete => f.write_str("11 DU STATE CHANGE COMPLETE"),
+            EventCode::AutonomousDUStateChangeComplete => {
+                f.write_str("12 AUTONOMOUS DU STATE CHANGE COMPLETE")
+            }
+            EventCode::Wakeup => f.write_str("13 WAKEUP"),
+            EventCode::Heartbeat => f.write_str("14 HEARTBEAT"),
+            EventCode::MReboot => f.write_str("M Reboot"),
+            EventCode::MScheduleInform => f.write_str("M ScheduleInform"),
+            EventCode::MDownload => f.write_str("M Download"),
+            EventCode::MScheduleDownload => f.write_str("M ScheduleDownload"),
+            EventCode::MUpload => f.write_str("M Upload"),
+            EventCode::MChangeDUState => f.write_str("M ChangeDUState"),
+            EventCode::Vendor { oui, event } => write!(f, "X {oui} {event}"),
+            EventCode::Other(code) => f.write_str(code),
+        }
+    }
+}
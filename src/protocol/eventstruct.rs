@@ -1,12 +1,18 @@
 #[cfg(test)]
 use quickcheck::{Arbitrary, Gen};
 
-use super::XmlSafeString;
+use super::{EventCode, XmlElement, XmlSafeString};
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EventStruct {
     pub event_code: XmlSafeString,
     pub command_key: XmlSafeString,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub extra: Vec<XmlElement>,
 }
 
 impl EventStruct {
@@ -15,8 +21,13 @@ impl EventStruct {
         EventStruct {
             event_code: event_code.into(),
             command_key: command_key.into(),
+            extra: vec![],
         }
     }
+    #[must_use]
+    pub fn code(&self) -> EventCode {
+        EventCode::from(self.event_code.0.as_ref())
+    }
 }
 
 #[cfg(test)]
@@ -25,6 +36,7 @@ impl Arbitrary for EventStruct {
         Self {
             event_code: XmlSafeString::arbitrary(g),
             command_key: XmlSafeString::arbitrary(g),
+            extra: vec![],
         }
     }
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
@@ -34,6 +46,7 @@ impl Arbitrary for EventStruct {
                 .map(|(e, c)| EventStruct {
                     event_code: e,
                     command_key: c,
+                    extra: vec![],
                 }),
         )
     }
@@ -4,13 +4,23 @@ use std::io::Write;
 use quickcheck::{Arbitrary, Gen};
 use xml::writer::XmlEvent;
 
-use super::{cwmp_prefix, parse_to_int, write_simple, GenerateError, XmlSafeString};
+use super::{
+    cwmp_prefix, parse_to_int, write_simple, FaultCode, GenerateError, GenerateOptions,
+    SetParameterValuesFault, XmlElement, XmlSafeString,
+};
+use crate::error::{Validation, Value};
 
 #[allow(clippy::module_name_repetitions)]
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FaultStruct {
     pub code: u32,
     pub string: XmlSafeString,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub extra: Vec<XmlElement>,
 }
 
 impl FaultStruct {
@@ -19,6 +29,7 @@ impl FaultStruct {
         FaultStruct {
             code,
             string: string.into(),
+            extra: vec![],
         }
     }
     pub fn set_code(&mut self, code: u32) {
@@ -27,6 +38,10 @@ impl FaultStruct {
     pub fn set_string(&mut self, string: &str) {
         self.string = string.into();
     }
+    #[must_use]
+    pub fn fault_code(&self) -> FaultCode {
+        FaultCode::from(self.code)
+    }
 }
 
 #[cfg(test)]
@@ -35,22 +50,34 @@ impl Arbitrary for FaultStruct {
         FaultStruct {
             code: u32::arbitrary(g),
             string: XmlSafeString::arbitrary(g),
+            extra: vec![],
         }
     }
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
         Box::new(
             (self.code, self.string.clone())
                 .shrink()
-                .map(|(c, s)| FaultStruct { code: c, string: s }),
+                .map(|(c, s)| FaultStruct {
+                    code: c,
+                    string: s,
+                    extra: vec![],
+                }),
         )
     }
 }
 
 #[allow(clippy::module_name_repetitions)]
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FaultDetail {
     pub code: u32,
     pub string: XmlSafeString,
+    pub set_parameter_values_fault: Vec<SetParameterValuesFault>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub extra: Vec<XmlElement>,
 }
 
 impl FaultDetail {
@@ -59,8 +86,14 @@ impl FaultDetail {
         FaultDetail {
             code,
             string: string.into(),
+            set_parameter_values_fault: vec![],
+            extra: vec![],
         }
     }
+    #[must_use]
+    pub fn fault_code(&self) -> FaultCode {
+        FaultCode::from(self.code)
+    }
 }
 
 #[cfg(test)]
@@ -69,22 +102,39 @@ impl Arbitrary for FaultDetail {
         Self {
             code: u32::arbitrary(g),
             string: XmlSafeString::arbitrary(g),
+            set_parameter_values_fault: Vec::<SetParameterValuesFault>::arbitrary(g),
+            extra: vec![],
         }
     }
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
         Box::new(
-            (self.code, self.string.clone())
+            (
+                self.code,
+                self.string.clone(),
+                self.set_parameter_values_fault.clone(),
+            )
                 .shrink()
-                .map(|(c, s)| FaultDetail { code: c, string: s }),
+                .map(|(c, s, f)| FaultDetail {
+                    code: c,
+                    string: s,
+                    set_parameter_values_fault: f,
+                    extra: vec![],
+                }),
         )
     }
 }
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Fault {
     pub faultcode: XmlSafeString,
     pub faultstring: XmlSafeString,
     pub detail: FaultDetail,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub extra: Vec<XmlElement>,
 }
 
 impl Fault {
@@ -94,8 +144,70 @@ impl Fault {
             faultcode: faultcode.into(),
             faultstring: faultstring.into(),
             detail: FaultDetail::new(code, string),
+            extra: vec![],
         }
     }
+    /// A fault with the TR-069 faultcode, faultstring and FaultString of `code`
+    #[must_use]
+    pub fn from_code(code: FaultCode) -> Self {
+        Fault::with_message(code, code.description())
+    }
+    /// A fault for `code` with its own FaultString
+    #[must_use]
+    pub fn with_message(code: FaultCode, message: &str) -> Self {
+        Fault::new(code.soap_fault_code(), "CWMP fault", code.code(), message)
+    }
+    #[must_use]
+    pub fn method_not_supported() -> Self {
+        Fault::from_code(FaultCode::MethodNotSupported)
+    }
+    #[must_use]
+    pub fn request_denied() -> Self {
+        Fault::from_code(FaultCode::RequestDenied)
+    }
+    #[must_use]
+    pub fn internal_error() -> Self {
+        Fault::from_code(FaultCode::InternalError)
+    }
+    #[must_use]
+    pub fn invalid_arguments() -> Self {
+        Fault::from_code(FaultCode::InvalidArguments)
+    }
+    #[must_use]
+    pub fn resources_exceeded() -> Self {
+        Fault::from_code(FaultCode::ResourcesExceeded)
+    }
+    /// The fault for a parameter the CPE does not know, naming it in the FaultString
+    #[must_use]
+    pub fn invalid_parameter_name(name: &str) -> Self {
+        Fault::parameter_fault(FaultCode::InvalidParameterName, name)
+    }
+    /// The fault for a value of the wrong type, naming the parameter in the FaultString
+    #[must_use]
+    pub fn invalid_parameter_type(name: &str) -> Self {
+        Fault::parameter_fault(FaultCode::InvalidParameterType, name)
+    }
+    /// The fault for a value the parameter cannot take, naming it in the FaultString
+    #[must_use]
+    pub fn invalid_parameter_value(name: &str) -> Self {
+        Fault::parameter_fault(FaultCode::InvalidParameterValue, name)
+    }
+    /// The fault for setting a read-only parameter, naming it in the FaultString
+    #[must_use]
+    pub fn non_writable_parameter(name: &str) -> Self {
+        Fault::parameter_fault(FaultCode::NonWritableParameter, name)
+    }
+    fn parameter_fault(code: FaultCode, name: &str) -> Self {
+        Fault::with_message(code, &format!("{}: {name}", code.description()))
+    }
+    #[must_use]
+    pub fn fault_code(&self) -> FaultCode {
+        self.detail.fault_code()
+    }
+    /// Add a per parameter fault, as returned by a CPE when a `SetParameterValues` fails
+    pub fn add_set_parameter_values_fault(&mut self, fault: SetParameterValuesFault) {
+        self.detail.set_parameter_values_fault.push(fault);
+    }
     /// Generate XML for `Fault`
     ///     
     /// # Errors
@@ -105,19 +217,52 @@ impl Fault {
         &self,
         writer: &mut xml::EventWriter<W>,
         has_cwmp: bool,
+        options: &GenerateOptions,
     ) -> Result<(), GenerateError> {
-        writer.write(XmlEvent::start_element("SOAP-ENV:Fault"))?;
+        writer.write(XmlEvent::start_element(
+            &format!("{}:Fault", options.envelope_prefix)[..],
+        ))?;
         write_simple(writer, "faultcode", self.faultcode.0.as_ref())?;
         write_simple(writer, "faultstring", self.faultstring.0.as_ref())?;
         writer.write(XmlEvent::start_element("detail"))?;
-        writer.write(XmlEvent::start_element(&cwmp_prefix(has_cwmp, "Fault")[..]))?;
+        writer.write(XmlEvent::start_element(
+            &cwmp_prefix(options, has_cwmp, "Fault")[..],
+        ))?;
         write_simple(writer, "FaultCode", &self.detail.code.to_string())?;
         write_simple(writer, "FaultString", self.detail.string.0.as_ref())?;
+        for f in &self.detail.set_parameter_values_fault {
+            writer.write(XmlEvent::start_element("SetParameterValuesFault"))?;
+            write_simple(writer, "ParameterName", f.parameter_name.0.as_ref())?;
+            write_simple(writer, "FaultCode", &f.fault_code.to_string())?;
+            write_simple(writer, "FaultString", f.fault_string.0.as_ref())?;
+            for e in &f.extra {
+                e.generate(writer)?;
+            }
+            writer.write(XmlEvent::end_element())?;
+        }
+        for e in &self.detail.extra {
+            e.generate(writer)?;
+        }
         writer.write(XmlEvent::end_element())?;
         writer.write(XmlEvent::end_element())?;
+        for e in &self.extra {
+            e.generate(writer)?;
+        }
         writer.write(XmlEvent::end_element())?;
         Ok(())
     }
+    pub fn start_handler(
+        &mut self,
+        path: &[&str],
+        _name: &xml::name::OwnedName,
+        _attributes: &[xml::attribute::OwnedAttribute],
+    ) {
+        if let ["Fault", "detail", "Fault", "SetParameterValuesFault"] = *path {
+            self.detail
+                .set_parameter_values_fault
+                .push(SetParameterValuesFault::default());
+        }
+    }
     pub fn characters(&mut self, path: &[&str], characters: &str) {
         match *path {
             ["Fault", "faultcode"] => {
@@ -132,21 +277,41 @@ impl Fault {
             ["Fault", "detail", "Fault", "FaultString"] => {
                 self.detail.string = characters.into();
             }
+            ["Fault", "detail", "Fault", "SetParameterValuesFault", key] => {
+                if let Some(f) = self.detail.set_parameter_values_fault.last_mut() {
+                    match key {
+                        "ParameterName" => f.parameter_name = characters.into(),
+                        "FaultCode" => f.fault_code = parse_to_int(characters, 0),
+                        "FaultString" => f.fault_string = characters.into(),
+                        _ => {}
+                    }
+                }
+            }
             _ => {}
         }
     }
 }
 
+impl From<Validation> for Fault {
+    fn from(e: Validation) -> Self {
+        Fault::with_message(FaultCode::InvalidArguments, &e.to_string())
+    }
+}
+
+impl From<Value> for Fault {
+    fn from(e: Value) -> Self {
+        Fault::with_message(FaultCode::InvalidParameterValue, &e.to_string())
+    }
+}
+
 #[cfg(test)]
 impl Arbitrary for Fault {
     fn arbitrary(g: &mut Gen) -> Fault {
         Self {
             faultcode: XmlSafeString::arbitrary(g),
             faultstring: XmlSafeString::arbitrary(g),
-            detail: FaultDetail {
-                code: u32::arbitrary(g),
-                string: XmlSafeString::arbitrary(g),
-            },
+            detail: FaultDetail::arbitrary(g),
+            extra: vec![],
         }
     }
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
@@ -161,6 +326,7 @@ impl Arbitrary for Fault {
                     faultcode: c,
                     faultstring: s,
                     detail: d,
+                    extra: vec![],
                 }),
         )
     }
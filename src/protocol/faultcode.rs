@@ -0,0 +1,320 @@
+use std::fmt;
+
+use super::CwmpVersion;
+
+#[cfg(test)]
+use quickcheck::{Arbitrary, Gen};
+
+/// CWMP fault codes as defined in TR-069 Annex A.5.1
+///
+/// The 8000 range are faults returned by the ACS in response to
+/// CPE initiated methods, the 9000 range are faults returned by the CPE.
+/// 8800-8899 and 9800-9899 are reserved for vendor specific faults.
+/// Any other code is kept as `Other` so no information is lost.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FaultCode {
+    AcsMethodNotSupported,
+    AcsRequestDenied,
+    AcsInternalError,
+    AcsInvalidArguments,
+    AcsResourcesExceeded,
+    AcsRetryRequest,
+    AcsVersionIncompatible,
+    MethodNotSupported,
+    RequestDenied,
+    InternalError,
+    InvalidArguments,
+    ResourcesExceeded,
+    InvalidParameterName,
+    InvalidParameterType,
+    InvalidParameterValue,
+    NonWritableParameter,
+    NotificationRequestRejected,
+    DownloadFailure,
+    UploadFailure,
+    FileTransferServerAuthenticationFailure,
+    UnsupportedFileTransferProtocol,
+    MulticastGroupJoinFailure,
+    FileServerUnreachable,
+    FileAccessFailure,
+    DownloadIncomplete,
+    FileCorrupted,
+    FileAuthenticationFailure,
+    TimeWindowExceeded,
+    CancelTransferNotPermitted,
+    InvalidUuidFormat,
+    UnknownExecutionEnvironment,
+    DisabledExecutionEnvironment,
+    DeploymentUnitMismatch,
+    DuplicateDeploymentUnit,
+    SystemResourcesExceeded,
+    UnknownDeploymentUnit,
+    InvalidDeploymentUnitState,
+    DowngradeNotPermitted,
+    VersionNotSpecified,
+    VersionAlreadyExists,
+    /// Vendor specific fault from the ACS, 8800-8899
+    AcsVendor(u32),
+    /// Vendor specific fault from the CPE, 9800-9899
+    CpeVendor(u32),
+    /// Any code not covered by TR-069
+    Other(u32),
+}
+
+impl FaultCode {
+    /// The numeric value used on the wire
+    #[must_use]
+    pub fn code(self) -> u32 {
+        match self {
+            FaultCode::AcsMethodNotSupported => 8000,
+            FaultCode::AcsRequestDenied => 8001,
+            FaultCode::AcsInternalError => 8002,
+            FaultCode::AcsInvalidArguments => 8003,
+            FaultCode::AcsResourcesExceeded => 8004,
+            FaultCode::AcsRetryRequest => 8005,
+            FaultCode::AcsVersionIncompatible => 8006,
+            FaultCode::MethodNotSupported => 9000,
+            FaultCode::RequestDenied => 9001,
+            FaultCode::InternalError => 9002,
+            FaultCode::InvalidArguments => 9003,
+            FaultCode::ResourcesExceeded => 9004,
+            FaultCode::InvalidParameterName => 9005,
+            FaultCode::InvalidParameterType => 9006,
+            FaultCode::InvalidParameterValue => 9007,
+            FaultCode::NonWritableParameter => 9008,
+            FaultCode::NotificationRequestRejected => 9009,
+            FaultCode::DownloadFailure => 9010,
+            FaultCode::UploadFailure => 9011,
+            FaultCode::FileTransferServerAuthenticationFailure => 9012,
+            FaultCode::UnsupportedFileTransferProtocol => 9013,
+            FaultCode::MulticastGroupJoinFailure => 9014,
+            FaultCode::FileServerUnreachable => 9015,
+            FaultCode::FileAccessFailure => 9016,
+            FaultCode::DownloadIncomplete => 9017,
+            FaultCode::FileCorrupted => 9018,
+            FaultCode::FileAuthenticationFailure => 9019,
+            FaultCode::TimeWindowExceeded => 9020,
+            FaultCode::CancelTransferNotPermitted => 9021,
+            FaultCode::InvalidUuidFormat => 9022,
+            FaultCode::UnknownExecutionEnvironment => 9023,
+            FaultCode::DisabledExecutionEnvironment => 9024,
+            FaultCode::DeploymentUnitMismatch => 9025,
+            FaultCode::DuplicateDeploymentUnit => 9026,
+            FaultCode::SystemResourcesExceeded => 9027,
+            FaultCode::UnknownDeploymentUnit => 9028,
+            FaultCode::InvalidDeploymentUnitState => 9029,
+            FaultCode::DowngradeNotPermitted => 9030,
+            FaultCode::VersionNotSpecified => 9031,
+            FaultCode::VersionAlreadyExists => 9032,
+            FaultCode::AcsVendor(c) | FaultCode::CpeVendor(c) | FaultCode::Other(c) => c,
+        }
+    }
+
+    /// The oldest CWMP version defining this fault, the one introducing the
+    /// transfers and deployment unit operations it reports on
+    #[must_use]
+    pub fn min_version(self) -> CwmpVersion {
+        let introducing = |rpc| CwmpVersion::introducing(rpc).unwrap_or_default();
+        match self {
+            FaultCode::DownloadIncomplete
+            | FaultCode::FileCorrupted
+            | FaultCode::FileAuthenticationFailure => CwmpVersion::new(1, 1),
+            FaultCode::TimeWindowExceeded => introducing("ScheduleDownload"),
+            FaultCode::CancelTransferNotPermitted => introducing("CancelTransfer"),
+            FaultCode::InvalidUuidFormat
+            | FaultCode::UnknownExecutionEnvironment
+            | FaultCode::DisabledExecutionEnvironment
+            | FaultCode::DeploymentUnitMismatch
+            | FaultCode::DuplicateDeploymentUnit
+            | FaultCode::SystemResourcesExceeded
+            | FaultCode::UnknownDeploymentUnit
+            | FaultCode::InvalidDeploymentUnitState
+            | FaultCode::DowngradeNotPermitted
+            | FaultCode::VersionNotSpecified
+            | FaultCode::VersionAlreadyExists => introducing("ChangeDUState"),
+            _ => CwmpVersion::new(1, 0),
+        }
+    }
+
+    /// The fault string TR-069 uses for this code
+    #[must_use]
+    pub fn description(self) -> &'static str {
+        match self {
+            FaultCode::AcsMethodNotSupported | FaultCode::MethodNotSupported => {
+                "Method not supported"
+            }
+            FaultCode::AcsRequestDenied => "Request denied",
+            FaultCode::RequestDenied => "Request denied (no reason specified)",
+            FaultCode::AcsInternalError | FaultCode::InternalError => "Internal error",
+            FaultCode::AcsInvalidArguments | FaultCode::InvalidArguments => "Invalid arguments",
+            FaultCode::AcsResourcesExceeded | FaultCode::ResourcesExceeded => "Resources exceeded",
+            FaultCode::AcsRetryRequest => "Retry request",
+            FaultCode::AcsVersionIncompatible => "ACS version incompatible with CPE",
+            FaultCode::InvalidParameterName => "Invalid parameter name",
+            FaultCode::InvalidParameterType => "Invalid parameter type",
+            FaultCode::InvalidParameterValue => "Invalid parameter value",
+            FaultCode::NonWritableParameter => "Attempt to set a non-writable parameter",
+            FaultCode::NotificationRequestRejected => "Notification request rejected",
+            FaultCode::DownloadFailure => "File transfer failure",
+            FaultCode::UploadFailure => "Upload failure",
+            FaultCode::FileTransferServerAuthenticationFailure => {
+                "File transfer server authentication failure"
+            }
+            FaultCode::UnsupportedFileTransferProtocol => "Unsupported protocol for file transfer",
+            FaultCode::MulticastGroupJoinFailure => {
+                "File transfer failure: unable to join multicast group"
+            }
+            FaultCode::FileServerUnreachable => {
+                "File transfer failure: unable to contact file server"
+            }
+            FaultCode::FileAccessFailure => "File transfer failure: unable to access file",
+            FaultCode::DownloadIncomplete => "File transfer failure: unable to complete download",
+            FaultCode::FileCorrupted => "File transfer failure: file corrupted",
+            FaultCode::FileAuthenticationFailure => {
+                "File transfer failure: file authentication failure"
+            }
+            FaultCode::TimeWindowExceeded => {
+                "File transfer failure: unable to complete download within specified time windows"
+            }
+            FaultCode::CancelTransferNotPermitted => {
+                "Cancelation of file transfer not permitted in current transfer state"
+            }
+            FaultCode::InvalidUuidFormat => "Invalid UUID Format",
+            FaultCode::UnknownExecutionEnvironment => "Unknown Execution Environment",
+            FaultCode::DisabledExecutionEnvironment => "Disabled Execution Environment",
+            FaultCode::DeploymentUnitMismatch => {
+                "Deployment Unit to Execution Environment Mismatch"
+            }
+            FaultCode::DuplicateDeploymentUnit => "Duplicate Deployment Unit",
+            FaultCode::SystemResourcesExceeded => "System Resources Exceeded",
+            FaultCode::UnknownDeploymentUnit => "Unknown Deployment Unit",
+            FaultCode::InvalidDeploymentUnitState => "Invalid Deployment Unit State",
+            FaultCode::DowngradeNotPermitted => {
+                "Invalid Deployment Unit Update: Downgrade not permitted"
+            }
+            FaultCode::VersionNotSpecified => {
+                "Invalid Deployment Unit Update: Version not specified"
+            }
+            FaultCode::VersionAlreadyExists => {
+                "Invalid Deployment Unit Update: Version already exists"
+            }
+            FaultCode::AcsVendor(_) | FaultCode::CpeVendor(_) => "Vendor defined fault",
+            FaultCode::Other(_) => "Unknown fault",
+        }
+    }
+
+    /// True for faults the ACS sends to the CPE (8000 range)
+    #[must_use]
+    pub fn is_acs_fault(self) -> bool {
+        (8000..9000).contains(&self.code())
+    }
+
+    /// True for faults the CPE sends to the ACS (9000 range)
+    #[must_use]
+    pub fn is_cpe_fault(self) -> bool {
+        (9000..10000).contains(&self.code())
+    }
+
+    /// The SOAP faultcode TR-069 Table 55 gives this code: "Client" when the
+    /// request was at fault, "Server" otherwise
+    #[must_use]
+    pub fn soap_fault_code(self) -> &'static str {
+        match self {
+            FaultCode::AcsInvalidArguments
+            | FaultCode::InvalidArguments
+            | FaultCode::InvalidParameterName
+            | FaultCode::InvalidParameterType
+            | FaultCode::InvalidParameterValue
+            | FaultCode::NonWritableParameter
+            | FaultCode::CancelTransferNotPermitted
+            | FaultCode::InvalidUuidFormat
+            | FaultCode::UnknownExecutionEnvironment
+            | FaultCode::DisabledExecutionEnvironment
+            | FaultCode::DeploymentUnitMismatch
+            | FaultCode::DuplicateDeploymentUnit
+            | FaultCode::UnknownDeploymentUnit
+            | FaultCode::InvalidDeploymentUnitState
+            | FaultCode::DowngradeNotPermitted
+            | FaultCode::VersionNotSpecified
+            | FaultCode::VersionAlreadyExists => "Client",
+            _ => "Server",
+        }
+    }
+
+    /// True for the vendor specific ranges 8800-8899 and 9800-9899
+    #[must_use]
+    pub fn is_vendor(self) -> bool {
+        matches!(self, FaultCode::AcsVendor(_) | FaultCode::CpeVendor(_))
+    }
+}
+
+impl From<u32> for FaultCode {
+    fn from(code: u32) -> Self {
+        match code {
+            8000 => FaultCode::AcsMethodNotSupported,
+            8001 => FaultCode::AcsRequestDenied,
+            8002 => FaultCode::AcsInternalError,
+            8003 => FaultCode::AcsInvalidArguments,
+            8004 => FaultCode::AcsResourcesExceeded,
+            8005 => FaultCode::AcsRetryRequest,
+            8006 => FaultCode::AcsVersionIncompatible,
+            8800..=8899 => FaultCode::AcsVendor(code),
+            9000 => FaultCode::MethodNotSupported,
+            9001 => FaultCode::RequestDenied,
+            9002 => FaultCode::InternalError,
+            9003 => FaultCode::InvalidArguments,
+            9004 => FaultCode::ResourcesExceeded,
+            9005 => FaultCode::InvalidParameterName,
+            9006 => FaultCode::InvalidParameterType,
+            9007 => FaultCode::InvalidParameterValue,
+            9008 => FaultCode::NonWritableParameter,
+            9009 => FaultCode::NotificationRequestRejected,
+            9010 => FaultCode::DownloadFailure,
+            9011 => FaultCode::UploadFailure,
+            9012 => FaultCode::FileTransferServerAuthenticationFailure,
+            9013 => FaultCode::UnsupportedFileTransferProtocol,
+            9014 => FaultCode::MulticastGroupJoinFailure,
+            9015 => FaultCode::FileServerUnreachable,
+            9016 => FaultCode::FileAccessFailure,
+            9017 => FaultCode::DownloadIncomplete,
+            9018 => FaultCode::FileCorrupted,
+            9019 => FaultCode::FileAuthenticationFailure,
+            9020 => FaultCode::TimeWindowExceeded,
+            9021 => FaultCode::CancelTransferNotPermitted,
+            9022 => FaultCode::InvalidUuidFormat,
+            9023 => FaultCode::UnknownExecutionEnvironment,
+            9024 => FaultCode::DisabledExecutionEnvironment,
+            9025 => FaultCode::DeploymentUnitMismatch,
+            9026 => FaultCode::DuplicateDeploymentUnit,
+            9027 => FaultCode::SystemResourcesExceeded,
+            9028 => FaultCode::UnknownDeploymentUnit,
+            9029 => FaultCode::InvalidDeploymentUnitState,
+            9030 => FaultCode::DowngradeNotPermitted,
+            9031 => FaultCode::VersionNotSpecified,
+            9032 => FaultCode::VersionAlreadyExists,
+            9800..=9899 => FaultCode::CpeVendor(code),
+            _ => FaultCode::Other(code),
+        }
+    }
+}
+
+impl From<FaultCode> for u32 {
+    fn from(code: FaultCode) -> Self {
+        code.code()
+    }
+}
+
+impl fmt::Display for FaultCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.code(), self.description())
+    }
+}
+
+#[cfg(test)]
+impl Arbitrary for FaultCode {
+    fn arbitrary(g: &mut Gen) -> Self {
+        FaultCode::from(u32::arbitrary(g))
+    }
+}
@@ -0,0 +1,129 @@
+use std::fmt;
+
+/// File types of Download, Upload and the other transfer RPCs, as defined
+/// in TR-069 Tables 26 and 32
+///
+/// The same name can carry a different number for a download and for an
+/// upload, "3 Vendor Configuration File" is downloaded while
+/// "1 Vendor Configuration File" is uploaded. Vendor types are written
+/// "X <OUI> <name>". Any other type is kept as `Other` so no information is
+/// lost.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FileType {
+    FirmwareUpgradeImage,
+    WebContent,
+    VendorConfigurationFile,
+    ToneFile,
+    RingerFile,
+    StoredFirmwareImage,
+    /// "1 Vendor Configuration File", the upload of the whole configuration
+    UploadVendorConfigurationFile,
+    VendorLogFile,
+    /// "3 Vendor Configuration File <i>", the configuration of the
+    /// `Device.DeviceInfo.VendorConfigFile.{i}` instance
+    VendorConfigurationFileInstance(u32),
+    /// "4 Vendor Log File <i>", the log of the
+    /// `Device.DeviceInfo.VendorLogFile.{i}` instance
+    VendorLogFileInstance(u32),
+    /// "X <OUI> <name>", a vendor specific file type
+    Vendor {
+        oui: String,
+        name: String,
+    },
+    /// Any type not covered by TR-069
+    Other(String),
+}
+
+impl FileType {
+    /// True for the types an ACS can ask a CPE to download
+    #[must_use]
+    pub fn is_download(&self) -> bool {
+        matches!(
+            self,
+            FileType::FirmwareUpgradeImage
+                | FileType::WebContent
+                | FileType::VendorConfigurationFile
+                | FileType::ToneFile
+                | FileType::RingerFile
+                | FileType::StoredFirmwareImage
+                | FileType::Vendor { .. }
+        )
+    }
+
+    /// True for the types an ACS can ask a CPE to upload
+    #[must_use]
+    pub fn is_upload(&self) -> bool {
+        matches!(
+            self,
+            FileType::UploadVendorConfigurationFile
+                | FileType::VendorLogFile
+                | FileType::VendorConfigurationFileInstance(_)
+                | FileType::VendorLogFileInstance(_)
+                | FileType::Vendor { .. }
+        )
+    }
+
+    #[must_use]
+    pub fn is_vendor(&self) -> bool {
+        matches!(self, FileType::Vendor { .. })
+    }
+}
+
+// the instance number of "<prefix> <i>", only if it is written the way Display writes it back
+fn instance(file_type: &str, prefix: &str) -> Option<u32> {
+    let i = file_type.strip_prefix(prefix)?.strip_prefix(' ')?;
+    i.parse::<u32>().ok().filter(|n| n.to_string() == i)
+}
+
+impl From<&str> for FileType {
+    fn from(file_type: &str) -> Self {
+        match file_type {
+            "1 Firmware Upgrade Image" => FileType::FirmwareUpgradeImage,
+            "2 Web Content" => FileType::WebContent,
+            "3 Vendor Configuration File" => FileType::VendorConfigurationFile,
+            "4 Tone File" => FileType::ToneFile,
+            "5 Ringer File" => FileType::RingerFile,
+            "6 Stored Firmware Image" => FileType::StoredFirmwareImage,
+            "1 Vendor Configuration File" => FileType::UploadVendorConfigurationFile,
+            "2 Vendor Log File" => FileType::VendorLogFile,
+            _ => {
+                if let Some(i) = instance(file_type, "3 Vendor Configuration File") {
+                    return FileType::VendorConfigurationFileInstance(i);
+                }
+                if let Some(i) = instance(file_type, "4 Vendor Log File") {
+                    return FileType::VendorLogFileInstance(i);
+                }
+                match file_type.strip_prefix("X ").and_then(|v| v.split_once(' ')) {
+                    Some((oui, name)) if !oui.is_empty() && !name.is_empty() => FileType::Vendor {
+                        oui: oui.to_string(),
+                        name: name.to_string(),
+                    },
+                    _ => FileType::Other(file_type.to_string()),
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for FileType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FileType::FirmwareUpgradeImage => f.write_str("1 Firmware Upgrade Image"),
+            FileType::WebContent => f.write_str("2 Web Content"),
+            FileType::VendorConfigurationFile => f.write_str("3 Vendor Configuration File"),
+            FileType::ToneFile => f.write_str("4 Tone File"),
+            FileType::RingerFile => f.write_str("5 Ringer File"),
+            FileType::StoredFirmwareImage => f.write_str("6 Stored Firmware Image"),
+            FileType::UploadVendorConfigurationFile => f.write_str("1 Vendor Configuration File"),
+            FileType::VendorLogFile => f.write_str("2 Vendor Log File"),
+            FileType::VendorConfigurationFileInstance(i) => {
+                write!(f, "3 Vendor Configuration File {i}")
+            }
+            FileType::VendorLogFileInstance(i) => write!(f, "4 Vendor Log File {i}"),
+            FileType::Vendor { oui, name } => write!(f, "X {oui} {name}"),
+            FileType::Other(file_type) => f.write_str(file_type),
+        }
+    }
+}
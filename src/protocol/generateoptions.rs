@@ -0,0 +1,88 @@
+/// Options controlling how an envelope is turned into XML
+///
+/// The defaults give the output `Envelope::generate` always produced:
+/// indented, with an XML declaration, the `SOAP-ENV`, `SOAP-ENC` and `cwmp`
+/// prefixes and an `encodingStyle` attribute on the envelope.
+#[allow(clippy::module_name_repetitions, clippy::struct_excessive_bools)]
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GenerateOptions {
+    /// Indent the XML, one element per line
+    pub indent: bool,
+    /// Start the document with `<?xml version="1.0" encoding="utf-8"?>`
+    pub declaration: bool,
+    /// Put `encodingStyle` on the envelope
+    pub encoding_style: bool,
+    /// Put `xsi:type="SOAP-ENC:Array"` on arrays, next to their `arrayType`
+    pub array_xsi_type: bool,
+    /// Prefix of the SOAP envelope namespace, like `soap` or `soapenv`
+    pub envelope_prefix: String,
+    /// Prefix of the SOAP encoding namespace, like `soapenc`
+    pub encoding_prefix: String,
+    /// Prefix of the CWMP namespace
+    pub cwmp_prefix: String,
+    /// Refuse body elements that need a newer CWMP version than the envelope's
+    pub check_version: bool,
+}
+
+impl Default for GenerateOptions {
+    fn default() -> Self {
+        GenerateOptions {
+            indent: true,
+            declaration: true,
+            encoding_style: true,
+            array_xsi_type: false,
+            envelope_prefix: String::from("SOAP-ENV"),
+            encoding_prefix: String::from("SOAP-ENC"),
+            cwmp_prefix: String::from("cwmp"),
+            check_version: false,
+        }
+    }
+}
+
+impl GenerateOptions {
+    #[must_use]
+    pub fn new() -> Self {
+        GenerateOptions::default()
+    }
+    #[must_use]
+    pub fn indent(mut self, indent: bool) -> Self {
+        self.indent = indent;
+        self
+    }
+    #[must_use]
+    pub fn declaration(mut self, declaration: bool) -> Self {
+        self.declaration = declaration;
+        self
+    }
+    #[must_use]
+    pub fn encoding_style(mut self, encoding_style: bool) -> Self {
+        self.encoding_style = encoding_style;
+        self
+    }
+    #[must_use]
+    pub fn array_xsi_type(mut self, array_xsi_type: bool) -> Self {
+        self.array_xsi_type = array_xsi_type;
+        self
+    }
+    #[must_use]
+    pub fn envelope_prefix(mut self, prefix: &str) -> Self {
+        self.envelope_prefix = prefix.to_string();
+        self
+    }
+    #[must_use]
+    pub fn encoding_prefix(mut self, prefix: &str) -> Self {
+        self.encoding_prefix = prefix.to_string();
+        self
+    }
+    #[must_use]
+    pub fn cwmp_prefix(mut self, prefix: &str) -> Self {
+        self.cwmp_prefix = prefix.to_string();
+        self
+    }
+    #[must_use]
+    pub fn check_version(mut self, check_version: bool) -> Self {
+        self.check_version = check_version;
+        self
+    }
+}
@@ -4,17 +4,25 @@ use std::io::Write;
 use quickcheck::{Arbitrary, Gen};
 use xml::writer::XmlEvent;
 
-use super::{cwmp_prefix, parse_to_int, write_simple, AllQueuedTransfers, GenerateError};
+use super::{
+    cwmp_array_type, cwmp_prefix, parse_to_int, write_array_start, write_simple,
+    AllQueuedTransfers, GenerateError, GenerateOptions, XmlElement,
+};
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GetAllQueuedTransfersResponse {
     pub transfer_list: Vec<AllQueuedTransfers>,
+    pub extra: Vec<XmlElement>,
 }
 
 impl GetAllQueuedTransfersResponse {
     #[must_use]
     pub fn new(transfer_list: Vec<AllQueuedTransfers>) -> Self {
-        GetAllQueuedTransfersResponse { transfer_list }
+        GetAllQueuedTransfersResponse {
+            transfer_list,
+            extra: vec![],
+        }
     }
     /// Generate XML for `GetAllQueuedTransfersResponse`
     ///     
@@ -25,18 +33,20 @@ impl GetAllQueuedTransfersResponse {
         &self,
         writer: &mut xml::EventWriter<W>,
         has_cwmp: bool,
+        options: &GenerateOptions,
     ) -> Result<(), GenerateError> {
         writer.write(XmlEvent::start_element(
-            &cwmp_prefix(has_cwmp, "GetAllQueuedTransfersResponse")[..],
+            &cwmp_prefix(options, has_cwmp, "GetAllQueuedTransfersResponse")[..],
         ))?;
 
-        let ss = format!(
-            "cwmp::AllQueuedTransferStruct[{}]",
-            self.transfer_list.len()
+        let ss = cwmp_array_type(
+            options,
+            has_cwmp,
+            "AllQueuedTransferStruct",
+            self.transfer_list.len(),
         );
 
-        writer
-            .write(XmlEvent::start_element("TransferList").attr("SOAP-ENC:arrayType", &ss[..]))?;
+        write_array_start(writer, options, "TransferList", &ss)?;
 
         for t in &self.transfer_list {
             writer.write(XmlEvent::start_element("AllQueuedTransferStruct"))?;
@@ -46,10 +56,16 @@ impl GetAllQueuedTransfersResponse {
             write_simple(writer, "FileType", t.file_type.0.as_ref())?;
             write_simple(writer, "FileSize", &t.file_size.to_string())?;
             write_simple(writer, "TargetFileName", t.target_filename.0.as_ref())?;
+            for e in &t.extra {
+                e.generate(writer)?;
+            }
             writer.write(XmlEvent::end_element())?;
         }
 
         writer.write(XmlEvent::end_element())?;
+        for e in &self.extra {
+            e.generate(writer)?;
+        }
         writer.write(XmlEvent::end_element())?;
         Ok(())
     }
@@ -91,6 +107,7 @@ impl Arbitrary for GetAllQueuedTransfersResponse {
     fn arbitrary(g: &mut Gen) -> Self {
         Self {
             transfer_list: Vec::<AllQueuedTransfers>::arbitrary(g),
+            extra: vec![],
         }
     }
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
@@ -98,7 +115,10 @@ impl Arbitrary for GetAllQueuedTransfersResponse {
             self.transfer_list
                 .clone()
                 .shrink()
-                .map(|tl| GetAllQueuedTransfersResponse { transfer_list: tl }),
+                .map(|tl| GetAllQueuedTransfersResponse {
+                    transfer_list: tl,
+                    extra: vec![],
+                }),
         )
     }
 }
@@ -4,11 +4,13 @@ use std::io::Write;
 use quickcheck::{Arbitrary, Gen};
 use xml::writer::XmlEvent;
 
-use super::{cwmp_prefix, write_simple, GenerateError, XmlSafeString};
+use super::{cwmp_prefix, write_simple, GenerateError, GenerateOptions, XmlElement, XmlSafeString};
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GetOptions {
     pub option_name: XmlSafeString,
+    pub extra: Vec<XmlElement>,
 }
 
 impl GetOptions {
@@ -16,6 +18,7 @@ impl GetOptions {
     pub fn new(option_name: &str) -> Self {
         GetOptions {
             option_name: option_name.into(),
+            extra: vec![],
         }
     }
     /// Generate XML for `GetParameterAttributes`
@@ -27,11 +30,15 @@ impl GetOptions {
         &self,
         writer: &mut xml::EventWriter<W>,
         has_cwmp: bool,
+        options: &GenerateOptions,
     ) -> Result<(), GenerateError> {
         writer.write(XmlEvent::start_element(
-            &cwmp_prefix(has_cwmp, "GetOptions")[..],
+            &cwmp_prefix(options, has_cwmp, "GetOptions")[..],
         ))?;
         write_simple(writer, "OptionName", self.option_name.0.as_ref())?;
+        for e in &self.extra {
+            e.generate(writer)?;
+        }
         writer.write(XmlEvent::end_element())?;
         Ok(())
     }
@@ -47,14 +54,13 @@ impl Arbitrary for GetOptions {
     fn arbitrary(g: &mut Gen) -> Self {
         Self {
             option_name: XmlSafeString::arbitrary(g),
+            extra: vec![],
         }
     }
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
-        Box::new(
-            self.option_name
-                .clone()
-                .shrink()
-                .map(|on| GetOptions { option_name: on }),
-        )
+        Box::new(self.option_name.clone().shrink().map(|on| GetOptions {
+            option_name: on,
+            extra: vec![],
+        }))
     }
 }
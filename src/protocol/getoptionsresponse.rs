@@ -5,17 +5,25 @@ use chrono::{DateTime, Utc};
 use quickcheck::{Arbitrary, Gen};
 use xml::writer::XmlEvent;
 
-use super::{parse_to_int, write_simple, GenerateError, OptionStruct};
+use super::{
+    cwmp_array_type, cwmp_prefix, parse_to_int, write_array_start, write_simple, GenerateError,
+    GenerateOptions, OptionStruct, XmlElement,
+};
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GetOptionsResponse {
     pub option_list: Vec<OptionStruct>,
+    pub extra: Vec<XmlElement>,
 }
 
 impl GetOptionsResponse {
     #[must_use]
     pub fn new(option_list: Vec<OptionStruct>) -> Self {
-        GetOptionsResponse { option_list }
+        GetOptionsResponse {
+            option_list,
+            extra: vec![],
+        }
     }
     /// Generate XML for `GetOptionsResponse`
     ///     
@@ -26,16 +34,14 @@ impl GetOptionsResponse {
         &self,
         writer: &mut xml::EventWriter<W>,
         has_cwmp: bool,
+        options: &GenerateOptions,
     ) -> Result<(), GenerateError> {
-        let ss = if has_cwmp {
-            writer.write(XmlEvent::start_element("cwmp:GetOptionsResponse"))?;
-            format!("cwmp:OptionStruct[{}]", self.option_list.len())
-        } else {
-            writer.write(XmlEvent::start_element("GetOptionsResponse"))?;
-            format!("OptionStruct[{}]", self.option_list.len())
-        };
+        writer.write(XmlEvent::start_element(
+            &cwmp_prefix(options, has_cwmp, "GetOptionsResponse")[..],
+        ))?;
+        let ss = cwmp_array_type(options, has_cwmp, "OptionStruct", self.option_list.len());
 
-        writer.write(XmlEvent::start_element("OptionList").attr("SOAP-ENC:arrayType", &ss[..]))?;
+        write_array_start(writer, options, "OptionList", &ss)?;
 
         for o in &self.option_list {
             writer.write(XmlEvent::start_element("OptionStruct"))?;
@@ -50,11 +56,17 @@ impl GetOptionsResponse {
                 write_simple(writer, "ExpirationDate", &dt.to_rfc3339())?;
             }
             write_simple(writer, "IsTransferable", &o.is_transferable.to_string())?;
+            for e in &o.extra {
+                e.generate(writer)?;
+            }
 
             writer.write(XmlEvent::end_element())?;
         }
 
         writer.write(XmlEvent::end_element())?;
+        for e in &self.extra {
+            e.generate(writer)?;
+        }
         writer.write(XmlEvent::end_element())?;
         Ok(())
     }
@@ -78,12 +90,12 @@ impl GetOptionsResponse {
                     "State" => last.state = parse_to_int(characters, 0),
                     "Mode" => last.mode = characters.into(),
                     "StartDate" => {
-                        if let Ok(dt) = characters.parse::<DateTime<Utc>>() {
+                        if let Ok(dt) = characters.trim().parse::<DateTime<Utc>>() {
                             last.start_date = Some(dt);
                         }
                     }
                     "ExpirationDate" => {
-                        if let Ok(dt) = characters.parse::<DateTime<Utc>>() {
+                        if let Ok(dt) = characters.trim().parse::<DateTime<Utc>>() {
                             last.expiration_date = Some(dt);
                         }
                     }
@@ -105,7 +117,10 @@ impl Arbitrary for GetOptionsResponse {
             self.option_list
                 .clone()
                 .shrink()
-                .map(|ol| GetOptionsResponse { option_list: ol }),
+                .map(|ol| GetOptionsResponse {
+                    option_list: ol,
+                    extra: vec![],
+                }),
         )
     }
 }
@@ -4,11 +4,13 @@ use std::io::Write;
 use quickcheck::{Arbitrary, Gen};
 use xml::writer::XmlEvent;
 
-use super::{cwmp_prefix, write_simple, GenerateError, XmlSafeString};
+use super::{cwmp_prefix, write_simple, GenerateError, GenerateOptions, XmlElement, XmlSafeString};
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GetParameterAttributes {
     pub parameternames: Vec<XmlSafeString>,
+    pub extra: Vec<XmlElement>,
 }
 
 impl GetParameterAttributes {
@@ -16,6 +18,7 @@ impl GetParameterAttributes {
     pub fn new(parameternames: &[&str]) -> Self {
         GetParameterAttributes {
             parameternames: super::convert_to_xml_safe_strings(parameternames),
+            extra: vec![],
         }
     }
     /// Generate XML for `GetParameterAttributes`
@@ -27,15 +30,19 @@ impl GetParameterAttributes {
         &self,
         writer: &mut xml::EventWriter<W>,
         has_cwmp: bool,
+        options: &GenerateOptions,
     ) -> Result<(), GenerateError> {
         writer.write(XmlEvent::start_element(
-            &cwmp_prefix(has_cwmp, "GetParameterAttributes")[..],
+            &cwmp_prefix(options, has_cwmp, "GetParameterAttributes")[..],
         ))?;
         writer.write(XmlEvent::start_element("ParameterNames"))?;
         for p in &self.parameternames {
             write_simple(writer, "string", p.0.as_ref())?;
         }
         writer.write(XmlEvent::end_element())?;
+        for e in &self.extra {
+            e.generate(writer)?;
+        }
         writer.write(XmlEvent::end_element())?;
         Ok(())
     }
@@ -64,6 +71,7 @@ impl Arbitrary for GetParameterAttributes {
     fn arbitrary(g: &mut Gen) -> Self {
         Self {
             parameternames: Vec::<XmlSafeString>::arbitrary(g),
+            extra: vec![],
         }
     }
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
@@ -71,7 +79,10 @@ impl Arbitrary for GetParameterAttributes {
             self.parameternames
                 .clone()
                 .shrink()
-                .map(|pn| GetParameterAttributes { parameternames: pn }),
+                .map(|pn| GetParameterAttributes {
+                    parameternames: pn,
+                    extra: vec![],
+                }),
         )
     }
 }
@@ -1,4 +1,7 @@
-use super::{write_simple, GenerateError, ParameterAttribute, XmlSafeString};
+use super::{
+    cwmp_array_type, cwmp_prefix, write_array_start, write_simple, GenerateError, GenerateOptions,
+    ParameterAttribute, XmlElement, XmlSafeString,
+};
 use std::io::Write;
 
 #[cfg(test)]
@@ -6,14 +9,19 @@ use quickcheck::{Arbitrary, Gen};
 use xml::writer::XmlEvent;
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GetParameterAttributesResponse {
     pub parameters: Vec<ParameterAttribute>,
+    pub extra: Vec<XmlElement>,
 }
 
 impl GetParameterAttributesResponse {
     #[must_use]
     pub fn new(parameters: Vec<ParameterAttribute>) -> Self {
-        GetParameterAttributesResponse { parameters }
+        GetParameterAttributesResponse {
+            parameters,
+            extra: vec![],
+        }
     }
     /// Generate XML for `GetParameterAttributesResponse`
     ///     
@@ -24,36 +32,42 @@ impl GetParameterAttributesResponse {
         &self,
         writer: &mut xml::EventWriter<W>,
         has_cwmp: bool,
+        options: &GenerateOptions,
     ) -> Result<(), GenerateError> {
-        let ss = if has_cwmp {
-            writer.write(XmlEvent::start_element(
-                "cwmp:GetParameterAttributesResponse",
-            ))?;
-            format!("cwmp:ParameterAttributeStruct[{}]", self.parameters.len())
-        } else {
-            writer.write(XmlEvent::start_element("GetParameterAttributesResponse"))?;
-            format!("ParameterAttributeStruct[{}]", self.parameters.len())
-        };
+        writer.write(XmlEvent::start_element(
+            &cwmp_prefix(options, has_cwmp, "GetParameterAttributesResponse")[..],
+        ))?;
+        let ss = cwmp_array_type(
+            options,
+            has_cwmp,
+            "ParameterAttributeStruct",
+            self.parameters.len(),
+        );
 
-        writer
-            .write(XmlEvent::start_element("ParameterList").attr("SOAP-ENC:arrayType", &ss[..]))?;
+        write_array_start(writer, options, "ParameterList", &ss)?;
 
         for p in &self.parameters {
             writer.write(XmlEvent::start_element("ParameterAttributeStruct"))?;
             write_simple(writer, "Name", p.name.0.as_ref())?;
             write_simple(writer, "Notification", p.notification.0.as_ref())?;
             let als = format!("xsd:string[{}]", p.accesslist.len());
-            writer.write(XmlEvent::start_element("AccessList").attr("SOAP-ENC:arrayType", &als))?;
+            write_array_start(writer, options, "AccessList", &als)?;
 
             for a in &p.accesslist {
                 write_simple(writer, "string", a.0.as_ref())?;
             }
 
             writer.write(XmlEvent::end_element())?;
+            for e in &p.extra {
+                e.generate(writer)?;
+            }
             writer.write(XmlEvent::end_element())?;
         }
 
         writer.write(XmlEvent::end_element())?;
+        for e in &self.extra {
+            e.generate(writer)?;
+        }
         writer.write(XmlEvent::end_element())?;
         Ok(())
     }
@@ -105,6 +119,7 @@ impl Arbitrary for GetParameterAttributesResponse {
     fn arbitrary(g: &mut Gen) -> Self {
         Self {
             parameters: Vec::<ParameterAttribute>::arbitrary(g),
+            extra: vec![],
         }
     }
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
@@ -112,7 +127,10 @@ impl Arbitrary for GetParameterAttributesResponse {
             self.parameters
                 .clone()
                 .shrink()
-                .map(|p| GetParameterAttributesResponse { parameters: p }),
+                .map(|p| GetParameterAttributesResponse {
+                    parameters: p,
+                    extra: vec![],
+                }),
         )
     }
 }
@@ -1,4 +1,7 @@
-use super::{cwmp_prefix, parse_to_int, write_simple, GenerateError, XmlSafeString};
+use super::{
+    cwmp_prefix, parse_to_int, write_simple, GenerateError, GenerateOptions, XmlElement,
+    XmlSafeString,
+};
 use std::io::Write;
 
 #[cfg(test)]
@@ -6,9 +9,11 @@ use quickcheck::{Arbitrary, Gen};
 use xml::writer::XmlEvent;
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GetParameterNames {
     pub parameter_path: XmlSafeString,
     pub next_level: u32,
+    pub extra: Vec<XmlElement>,
 }
 impl GetParameterNames {
     #[must_use]
@@ -16,6 +21,7 @@ impl GetParameterNames {
         GetParameterNames {
             parameter_path: parameter_path.into(),
             next_level,
+            extra: vec![],
         }
     }
     /// Generate XML for `GetParameterNames`
@@ -27,12 +33,16 @@ impl GetParameterNames {
         &self,
         writer: &mut xml::EventWriter<W>,
         has_cwmp: bool,
+        options: &GenerateOptions,
     ) -> Result<(), GenerateError> {
         writer.write(XmlEvent::start_element(
-            &cwmp_prefix(has_cwmp, "GetParameterNames")[..],
+            &cwmp_prefix(options, has_cwmp, "GetParameterNames")[..],
         ))?;
         write_simple(writer, "ParameterPath", self.parameter_path.0.as_ref())?;
         write_simple(writer, "NextLevel", &self.next_level.to_string())?;
+        for e in &self.extra {
+            e.generate(writer)?;
+        }
         writer.write(XmlEvent::end_element())?;
         Ok(())
     }
@@ -51,6 +61,7 @@ impl Arbitrary for GetParameterNames {
         Self {
             parameter_path: XmlSafeString::arbitrary(g),
             next_level: u32::arbitrary(g),
+            extra: vec![],
         }
     }
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
@@ -60,6 +71,7 @@ impl Arbitrary for GetParameterNames {
                 .map(|(pp, nl)| GetParameterNames {
                     parameter_path: pp,
                     next_level: nl,
+                    extra: vec![],
                 }),
         )
     }
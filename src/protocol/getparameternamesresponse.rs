@@ -1,4 +1,7 @@
-use super::{parse_to_int, write_simple, GenerateError, ParameterInfoStruct};
+use super::{
+    cwmp_array_type, cwmp_prefix, parse_to_int, write_array_start, write_simple, GenerateError,
+    GenerateOptions, ParameterInfoStruct, XmlElement,
+};
 use std::io::Write;
 
 #[cfg(test)]
@@ -6,14 +9,19 @@ use quickcheck::{Arbitrary, Gen};
 use xml::writer::XmlEvent;
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GetParameterNamesResponse {
     pub parameter_list: Vec<ParameterInfoStruct>,
+    pub extra: Vec<XmlElement>,
 }
 
 impl GetParameterNamesResponse {
     #[must_use]
     pub fn new(parameter_list: Vec<ParameterInfoStruct>) -> Self {
-        GetParameterNamesResponse { parameter_list }
+        GetParameterNamesResponse {
+            parameter_list,
+            extra: vec![],
+        }
     }
     pub fn start_handler(
         &mut self,
@@ -37,26 +45,34 @@ impl GetParameterNamesResponse {
         &self,
         writer: &mut xml::EventWriter<W>,
         has_cwmp: bool,
+        options: &GenerateOptions,
     ) -> Result<(), GenerateError> {
-        let ss = if has_cwmp {
-            writer.write(XmlEvent::start_element("cwmp:GetParameterNamesResponse"))?;
-            format!("cwmp:ParameterInfoStruct[{}]", self.parameter_list.len())
-        } else {
-            writer.write(XmlEvent::start_element("GetParameterNamesResponse"))?;
-            format!("ParameterInfoStruct[{}]", self.parameter_list.len())
-        };
+        writer.write(XmlEvent::start_element(
+            &cwmp_prefix(options, has_cwmp, "GetParameterNamesResponse")[..],
+        ))?;
+        let ss = cwmp_array_type(
+            options,
+            has_cwmp,
+            "ParameterInfoStruct",
+            self.parameter_list.len(),
+        );
 
-        writer
-            .write(XmlEvent::start_element("ParameterList").attr("SOAP-ENC:arrayType", &ss[..]))?;
+        write_array_start(writer, options, "ParameterList", &ss)?;
 
         for p in &self.parameter_list {
             writer.write(XmlEvent::start_element("ParameterInfoStruct"))?;
             write_simple(writer, "Name", p.name.0.as_ref())?;
             write_simple(writer, "Writable", &p.writable.to_string())?;
+            for e in &p.extra {
+                e.generate(writer)?;
+            }
             writer.write(XmlEvent::end_element())?;
         }
 
         writer.write(XmlEvent::end_element())?;
+        for e in &self.extra {
+            e.generate(writer)?;
+        }
         writer.write(XmlEvent::end_element())?;
         Ok(())
     }
@@ -87,7 +103,10 @@ impl Arbitrary for GetParameterNamesResponse {
             self.parameter_list
                 .clone()
                 .shrink()
-                .map(|pl| GetParameterNamesResponse { parameter_list: pl }),
+                .map(|pl| GetParameterNamesResponse {
+                    parameter_list: pl,
+                    extra: vec![],
+                }),
         )
     }
 }
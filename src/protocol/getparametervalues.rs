@@ -1,4 +1,7 @@
-use super::{convert_to_xml_safe_strings, cwmp_prefix, write_simple, GenerateError, XmlSafeString};
+use super::{
+    convert_to_xml_safe_strings, cwmp_prefix, write_array_start, write_simple, GenerateError,
+    GenerateOptions, XmlElement, XmlSafeString,
+};
 use std::io::Write;
 
 #[cfg(test)]
@@ -6,8 +9,10 @@ use quickcheck::{Arbitrary, Gen};
 use xml::writer::XmlEvent;
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GetParameterValues {
-    parameternames: Vec<XmlSafeString>,
+    pub parameternames: Vec<XmlSafeString>,
+    pub extra: Vec<XmlElement>,
 }
 
 impl GetParameterValues {
@@ -15,6 +20,7 @@ impl GetParameterValues {
     pub fn new(parameternames: &[&str]) -> Self {
         Self {
             parameternames: convert_to_xml_safe_strings(parameternames),
+            extra: vec![],
         }
     }
 
@@ -27,23 +33,20 @@ impl GetParameterValues {
         &self,
         writer: &mut xml::EventWriter<W>,
         has_cwmp: bool,
+        options: &GenerateOptions,
     ) -> Result<(), GenerateError> {
         writer.write(XmlEvent::start_element(
-            &cwmp_prefix(has_cwmp, "GetParameterValues")[..],
+            &cwmp_prefix(options, has_cwmp, "GetParameterValues")[..],
         ))?;
-        writer.write(
-            XmlEvent::start_element("ParameterNames")
-                .attr(
-                    "soapenc:arrayType",
-                    &format!("xsd:string[{}]", self.parameternames.len()),
-                )
-                .attr("xmlns:soapenc", "http://schemas.xmlsoap.org/soap/encoding/")
-                .attr("xmlns:xsd", "http://www.w3.org/2001/XMLSchema"),
-        )?;
+        let ss = format!("xsd:string[{}]", self.parameternames.len());
+        write_array_start(writer, options, "ParameterNames", &ss)?;
         for p in &self.parameternames {
             write_simple(writer, "string", p.0.as_ref())?;
         }
         writer.write(XmlEvent::end_element())?;
+        for e in &self.extra {
+            e.generate(writer)?;
+        }
         writer.write(XmlEvent::end_element())?;
         Ok(())
     }
@@ -72,6 +75,7 @@ impl Arbitrary for GetParameterValues {
     fn arbitrary(g: &mut Gen) -> Self {
         Self {
             parameternames: Vec::<XmlSafeString>::arbitrary(g),
+            extra: vec![],
         }
     }
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
@@ -79,7 +83,10 @@ impl Arbitrary for GetParameterValues {
             self.parameternames
                 .clone()
                 .shrink()
-                .map(|pn| GetParameterValues { parameternames: pn }),
+                .map(|pn| GetParameterValues {
+                    parameternames: pn,
+                    extra: vec![],
+                }),
         )
     }
 }
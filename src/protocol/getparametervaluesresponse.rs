@@ -1,4 +1,7 @@
-use super::{extract_attribute, write_simple, GenerateError, ParameterValue};
+use super::{
+    cwmp_array_type, cwmp_prefix, extract_attribute, write_array_start, write_simple,
+    GenerateError, GenerateOptions, ParamValue, ParameterValue, XmlElement,
+};
 use std::io::Write;
 
 #[cfg(test)]
@@ -6,14 +9,33 @@ use quickcheck::{Arbitrary, Gen};
 use xml::writer::XmlEvent;
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GetParameterValuesResponse {
     pub parameters: Vec<ParameterValue>,
+    pub extra: Vec<XmlElement>,
 }
 
 impl GetParameterValuesResponse {
     #[must_use]
     pub fn new(parameters: Vec<ParameterValue>) -> Self {
-        GetParameterValuesResponse { parameters }
+        GetParameterValuesResponse {
+            parameters,
+            extra: vec![],
+        }
+    }
+
+    /// The typed value of the parameter `name`, if it is in the list
+    #[must_use]
+    pub fn param_value(&self, name: &str) -> Option<ParamValue> {
+        self.parameters
+            .iter()
+            .find(|p| p.name.0 == name)
+            .map(ParameterValue::param_value)
+    }
+
+    /// Add a parameter with a typed value to the list
+    pub fn add_param_value(&mut self, name: &str, value: &ParamValue) {
+        self.parameters.push(ParameterValue::typed(name, value));
     }
 
     /// Generate XML for `GetParameterValuesResponse`
@@ -25,19 +47,18 @@ impl GetParameterValuesResponse {
         &self,
         writer: &mut xml::EventWriter<W>,
         has_cwmp: bool,
+        options: &GenerateOptions,
     ) -> Result<(), GenerateError> {
-        let ss = if has_cwmp {
-            writer.write(XmlEvent::start_element("cwmp:GetParameterValuesResponse"))?;
-            format!("cwmp:ParameterValueStruct[{}]", self.parameters.len())
-        } else {
-            writer.write(XmlEvent::start_element("GetParameterValuesResponse"))?;
-            format!("ParameterValueStruct[{}]", self.parameters.len())
-        };
-        writer.write(
-            XmlEvent::start_element("ParameterList")
-                .attr("xsi:type", "SOAP-ENC:Array")
-                .attr("SOAP-ENC:arrayType", &ss[..]),
-        )?;
+        writer.write(XmlEvent::start_element(
+            &cwmp_prefix(options, has_cwmp, "GetParameterValuesResponse")[..],
+        ))?;
+        let ss = cwmp_array_type(
+            options,
+            has_cwmp,
+            "ParameterValueStruct",
+            self.parameters.len(),
+        );
+        write_array_start(writer, options, "ParameterList", &ss)?;
 
         for p in &self.parameters {
             writer.write(XmlEvent::start_element("ParameterValueStruct"))?;
@@ -45,10 +66,15 @@ impl GetParameterValuesResponse {
             writer.write(XmlEvent::start_element("Value").attr("xsi:type", p.r#type.0.as_ref()))?;
             writer.write(p.value.0.as_ref())?;
             writer.write(XmlEvent::end_element())?;
-
+            for e in &p.extra {
+                e.generate(writer)?;
+            }
             writer.write(XmlEvent::end_element())?;
         }
         writer.write(XmlEvent::end_element())?;
+        for e in &self.extra {
+            e.generate(writer)?;
+        }
         writer.write(XmlEvent::end_element())?;
         Ok(())
     }
@@ -99,7 +125,10 @@ impl Arbitrary for GetParameterValuesResponse {
             self.parameters
                 .clone()
                 .shrink()
-                .map(|p| GetParameterValuesResponse { parameters: p }),
+                .map(|p| GetParameterValuesResponse {
+                    parameters: p,
+                    extra: vec![],
+                }),
         )
     }
 }
@@ -1,8 +1,9 @@
 use std::io::Write;
 
-use super::{cwmp_prefix, write_empty_tag, GenerateError};
+use super::{cwmp_prefix, write_empty_tag, GenerateError, GenerateOptions};
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GetQueuedTransfers {}
 
 impl GetQueuedTransfers {
@@ -15,8 +16,12 @@ impl GetQueuedTransfers {
         &self,
         writer: &mut xml::EventWriter<W>,
         has_cwmp: bool,
+        options: &GenerateOptions,
     ) -> Result<(), GenerateError> {
-        write_empty_tag(writer, &cwmp_prefix(has_cwmp, "GetQueuedTransfers"))?;
+        write_empty_tag(
+            writer,
+            &cwmp_prefix(options, has_cwmp, "GetQueuedTransfers"),
+        )?;
         Ok(())
     }
 }
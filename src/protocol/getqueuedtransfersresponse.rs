@@ -1,4 +1,7 @@
-use super::{write_simple, GenerateError};
+use super::{
+    cwmp_array_type, cwmp_prefix, write_array_start, write_simple, GenerateError, GenerateOptions,
+    XmlElement,
+};
 use super::{QueuedTransferStruct, XmlSafeString};
 use std::io::Write;
 
@@ -7,14 +10,19 @@ use quickcheck::{Arbitrary, Gen};
 use xml::writer::XmlEvent;
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GetQueuedTransfersResponse {
     pub transfer_list: Vec<QueuedTransferStruct>,
+    pub extra: Vec<XmlElement>,
 }
 
 impl GetQueuedTransfersResponse {
     #[must_use]
     pub fn new(transfer_list: Vec<QueuedTransferStruct>) -> Self {
-        GetQueuedTransfersResponse { transfer_list }
+        GetQueuedTransfersResponse {
+            transfer_list,
+            extra: vec![],
+        }
     }
 
     /// Generate XML for `GetQueuedTransfersResponse`
@@ -26,17 +34,19 @@ impl GetQueuedTransfersResponse {
         &self,
         writer: &mut xml::EventWriter<W>,
         has_cwmp: bool,
+        options: &GenerateOptions,
     ) -> Result<(), GenerateError> {
-        let ss = if has_cwmp {
-            writer.write(XmlEvent::start_element("cwmp:GetQueuedTransfersResponse"))?;
-            format!("cwmp:QueuedTransferStruct[{}]", self.transfer_list.len())
-        } else {
-            writer.write(XmlEvent::start_element("GetQueuedTransfersResponse"))?;
-            format!("QueuedTransferStruct[{}]", self.transfer_list.len())
-        };
+        writer.write(XmlEvent::start_element(
+            &cwmp_prefix(options, has_cwmp, "GetQueuedTransfersResponse")[..],
+        ))?;
+        let ss = cwmp_array_type(
+            options,
+            has_cwmp,
+            "QueuedTransferStruct",
+            self.transfer_list.len(),
+        );
 
-        writer
-            .write(XmlEvent::start_element("TransferList").attr("SOAP-ENC:arrayType", &ss[..]))?;
+        write_array_start(writer, options, "TransferList", &ss)?;
 
         for p in &self.transfer_list {
             writer.write(XmlEvent::start_element("QueuedTransferStruct"))?;
@@ -46,9 +56,15 @@ impl GetQueuedTransfersResponse {
             if let Some(s) = &p.state {
                 write_simple(writer, "State", s.0.as_ref())?;
             }
+            for e in &p.extra {
+                e.generate(writer)?;
+            }
             writer.write(XmlEvent::end_element())?;
         }
         writer.write(XmlEvent::end_element())?;
+        for e in &self.extra {
+            e.generate(writer)?;
+        }
         writer.write(XmlEvent::end_element())?;
         Ok(())
     }
@@ -99,7 +115,10 @@ impl Arbitrary for GetQueuedTransfersResponse {
             self.transfer_list
                 .clone()
                 .shrink()
-                .map(|t| GetQueuedTransfersResponse { transfer_list: t }),
+                .map(|t| GetQueuedTransfersResponse {
+                    transfer_list: t,
+                    extra: vec![],
+                }),
         )
     }
 }
@@ -1,13 +1,18 @@
 use std::io::Write;
 
-use super::{cwmp_prefix, write_simple, GenerateError, XmlSafeString};
+use super::{
+    cwmp_prefix, write_array_start, write_simple, GenerateError, GenerateOptions, XmlElement,
+    XmlSafeString,
+};
 #[cfg(test)]
 use quickcheck::{Arbitrary, Gen};
 use xml::writer::XmlEvent;
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GetRPCMethodsResponse {
     pub method_list: Vec<XmlSafeString>,
+    pub extra: Vec<XmlElement>,
 }
 
 impl GetRPCMethodsResponse {
@@ -15,6 +20,7 @@ impl GetRPCMethodsResponse {
     pub fn new(method_list: &[&str]) -> Self {
         GetRPCMethodsResponse {
             method_list: super::convert_to_xml_safe_strings(method_list),
+            extra: vec![],
         }
     }
 
@@ -26,18 +32,22 @@ impl GetRPCMethodsResponse {
         &self,
         writer: &mut xml::EventWriter<W>,
         has_cwmp: bool,
+        options: &GenerateOptions,
     ) -> Result<(), GenerateError> {
         writer.write(XmlEvent::start_element(
-            &cwmp_prefix(has_cwmp, "GetRPCMethodsResponse")[..],
+            &cwmp_prefix(options, has_cwmp, "GetRPCMethodsResponse")[..],
         ))?;
         let ss = format!("xsd:string[{}]", self.method_list.len());
 
-        writer.write(XmlEvent::start_element("MethodList").attr("SOAP-ENC:arrayType", &ss[..]))?;
+        write_array_start(writer, options, "MethodList", &ss)?;
 
         for p in &self.method_list {
             write_simple(writer, "string", p.0.as_ref())?;
         }
         writer.write(XmlEvent::end_element())?;
+        for e in &self.extra {
+            e.generate(writer)?;
+        }
         writer.write(XmlEvent::end_element())?;
         Ok(())
     }
@@ -67,6 +77,7 @@ impl Arbitrary for GetRPCMethodsResponse {
     fn arbitrary(g: &mut Gen) -> Self {
         Self {
             method_list: Vec::<XmlSafeString>::arbitrary(g),
+            extra: vec![],
         }
     }
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
@@ -74,7 +85,10 @@ impl Arbitrary for GetRPCMethodsResponse {
             self.method_list
                 .clone()
                 .shrink()
-                .map(|ml| GetRPCMethodsResponse { method_list: ml }),
+                .map(|ml| GetRPCMethodsResponse {
+                    method_list: ml,
+                    extra: vec![],
+                }),
         )
     }
 }
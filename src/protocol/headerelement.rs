@@ -4,10 +4,12 @@ use quickcheck::{Arbitrary, Gen};
 use rand::Rng;
 
 use super::{
-    HoldRequests, NoMoreRequests, SessionTimeout, SupportedCWMPVersions, UseCWMPVersion, ID,
+    HoldRequests, NoMoreRequests, SessionTimeout, SupportedCWMPVersions, UnknownHeader,
+    UseCWMPVersion, ID,
 };
 
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum HeaderElement {
     ID(ID),
     HoldRequests(HoldRequests),
@@ -15,10 +17,12 @@ pub enum HeaderElement {
     NoMoreRequests(NoMoreRequests),
     SupportedCWMPVersions(SupportedCWMPVersions),
     UseCWMPVersion(UseCWMPVersion),
+    Unknown(UnknownHeader),
 }
 
 #[cfg(test)]
 impl Arbitrary for HeaderElement {
+    #[allow(clippy::useless_vec)]
     fn arbitrary(g: &mut Gen) -> Self {
         let vals = vec![
             HeaderElement::ID(ID::arbitrary(g)),
@@ -55,6 +59,7 @@ impl Arbitrary for HeaderElement {
             HeaderElement::UseCWMPVersion(x) => {
                 Box::new(x.shrink().map(HeaderElement::UseCWMPVersion))
             }
+            HeaderElement::Unknown(_) => quickcheck::empty_shrinker(),
         }
     }
 }
@@ -4,9 +4,10 @@ use std::io::Write;
 use quickcheck::{Arbitrary, Gen};
 use xml::writer::XmlEvent;
 
-use super::{bool2str, cwmp_prefix, GenerateError, XmlSafeString};
+use super::{bool2str, cwmp_prefix, GenerateError, GenerateOptions, XmlSafeString};
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ID {
     pub must_understand: bool,
     pub id: XmlSafeString,
@@ -30,9 +31,10 @@ impl ID {
         &self,
         writer: &mut xml::EventWriter<W>,
         has_cwmp: bool,
+        options: &GenerateOptions,
     ) -> Result<(), GenerateError> {
         writer.write(
-            XmlEvent::start_element(&cwmp_prefix(has_cwmp, "ID")[..])
+            XmlEvent::start_element(&cwmp_prefix(options, has_cwmp, "ID")[..])
                 .attr("mustUnderstand", bool2str(self.must_understand)),
         )?;
         writer.write(self.id.0.as_ref())?;
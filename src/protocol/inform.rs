@@ -9,11 +9,13 @@ use xml::writer::XmlEvent;
 use super::gen_utc_date;
 
 use super::{
-    cwmp_prefix, extract_attribute, parse_to_int, write_simple, DeviceId, EventStruct,
-    GenerateError, ParameterValue,
+    cwmp_array_type, cwmp_prefix, extract_attribute, parse_to_int, write_array_start, write_simple,
+    DeviceId, EventCode, EventStruct, GenerateError, GenerateOptions, ParamValue, ParameterValue,
+    XmlElement,
 };
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Inform {
     pub device_id: DeviceId,
     pub event: Vec<EventStruct>,
@@ -21,6 +23,7 @@ pub struct Inform {
     pub current_time: Option<DateTime<Utc>>,
     pub retry_count: u32,
     pub parameter_list: Vec<ParameterValue>,
+    pub extra: Vec<XmlElement>,
 }
 
 impl Inform {
@@ -40,9 +43,53 @@ impl Inform {
             current_time: Some(current_time),
             retry_count,
             parameter_list,
+            extra: vec![],
         }
     }
 
+    /// The event codes of the Inform, in the order the CPE listed them
+    #[must_use]
+    pub fn event_codes(&self) -> Vec<EventCode> {
+        self.event.iter().map(EventStruct::code).collect()
+    }
+
+    #[must_use]
+    pub fn has_event(&self, code: &EventCode) -> bool {
+        self.event.iter().any(|e| e.code() == *code)
+    }
+
+    /// True for the "0 BOOTSTRAP" Inform of a CPE seen for the first time,
+    /// or after a factory reset
+    #[must_use]
+    pub fn is_bootstrap(&self) -> bool {
+        self.has_event(&EventCode::Bootstrap)
+    }
+
+    /// The command keys reported with `code`, like the keys of the
+    /// Downloads completed in a "M Download" event
+    #[must_use]
+    pub fn command_keys_for(&self, code: &EventCode) -> Vec<&str> {
+        self.event
+            .iter()
+            .filter(|e| e.code() == *code)
+            .map(|e| e.command_key.0.as_ref())
+            .collect()
+    }
+
+    /// The typed value of the parameter `name`, if it is in the list
+    #[must_use]
+    pub fn param_value(&self, name: &str) -> Option<ParamValue> {
+        self.parameter_list
+            .iter()
+            .find(|p| p.name.0 == name)
+            .map(ParameterValue::param_value)
+    }
+
+    /// Add a parameter with a typed value to the list
+    pub fn add_param_value(&mut self, name: &str, value: &ParamValue) {
+        self.parameter_list.push(ParameterValue::typed(name, value));
+    }
+
     /// Generate XML for `Inform`
     ///     
     /// # Errors
@@ -51,9 +98,10 @@ impl Inform {
         &self,
         writer: &mut xml::EventWriter<W>,
         has_cwmp: bool,
+        options: &GenerateOptions,
     ) -> Result<(), GenerateError> {
         writer.write(XmlEvent::start_element(
-            &cwmp_prefix(has_cwmp, "Inform")[..],
+            &cwmp_prefix(options, has_cwmp, "Inform")[..],
         ))?;
         writer.write(XmlEvent::start_element("DeviceId"))?;
         write_simple(
@@ -72,16 +120,22 @@ impl Inform {
             "SerialNumber",
             self.device_id.serial_number.0.as_ref(),
         )?;
+        for e in &self.device_id.extra {
+            e.generate(writer)?;
+        }
         writer.write(XmlEvent::end_element())?;
 
-        let ss = format!("cwmp:EventStruct[{}]", self.event.len());
+        let ss = cwmp_array_type(options, has_cwmp, "EventStruct", self.event.len());
 
-        writer.write(XmlEvent::start_element("Event").attr("SOAP-ENC:arrayType", &ss))?;
+        write_array_start(writer, options, "Event", &ss)?;
 
         for e in &self.event {
             writer.write(XmlEvent::start_element("EventStruct"))?;
             write_simple(writer, "EventCode", e.event_code.0.as_ref())?;
             write_simple(writer, "CommandKey", e.command_key.0.as_ref())?;
+            for x in &e.extra {
+                x.generate(writer)?;
+            }
             writer.write(XmlEvent::end_element())?;
         }
         // Event
@@ -93,8 +147,13 @@ impl Inform {
         }
         write_simple(writer, "RetryCount", &self.retry_count.to_string())?;
 
-        let pls = format!("cwmp:ParameterValueStruct[{}]", self.parameter_list.len());
-        writer.write(XmlEvent::start_element("ParameterList").attr("SOAP-ENC:arrayType", &pls))?;
+        let pls = cwmp_array_type(
+            options,
+            has_cwmp,
+            "ParameterValueStruct",
+            self.parameter_list.len(),
+        );
+        write_array_start(writer, options, "ParameterList", &pls)?;
 
         for p in &self.parameter_list {
             writer.write(XmlEvent::start_element("ParameterValueStruct"))?;
@@ -102,12 +161,18 @@ impl Inform {
             writer.write(XmlEvent::start_element("Value").attr("xsi:type", p.r#type.0.as_ref()))?;
             writer.write(p.value.0.as_ref())?;
             writer.write(XmlEvent::end_element())?; // Value
+            for e in &p.extra {
+                e.generate(writer)?;
+            }
             writer.write(XmlEvent::end_element())?; // ParameterValueStruct
         }
 
         // ParameterList
         writer.write(XmlEvent::end_element())?;
 
+        for e in &self.extra {
+            e.generate(writer)?;
+        }
         writer.write(XmlEvent::end_element())?;
         Ok(())
     }
@@ -156,7 +221,7 @@ impl Inform {
             ["Inform", "MaxEnvelopes"] => self.max_envelopes = parse_to_int(characters, 0),
             ["Inform", "RetryCount"] => self.retry_count = parse_to_int(characters, 0),
             ["Inform", "CurrentTime"] => {
-                if let Ok(dt) = characters.parse::<DateTime<Utc>>() {
+                if let Ok(dt) = characters.trim().parse::<DateTime<Utc>>() {
                     self.current_time = Some(dt);
                 }
             }
@@ -185,6 +250,7 @@ impl Arbitrary for Inform {
             current_time: Some(gen_utc_date(2014, 11, 28, 12, 0, 9)),
             retry_count: u32::arbitrary(g),
             parameter_list: Vec::<ParameterValue>::arbitrary(g),
+            extra: vec![],
         }
     }
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
@@ -204,6 +270,7 @@ impl Arbitrary for Inform {
                     current_time: Some(gen_utc_date(2014, 11, 28, 12, 0, 9)),
                     retry_count: r,
                     parameter_list: p,
+                    extra: vec![],
                 }),
         )
     }
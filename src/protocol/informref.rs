@@ -0,0 +1,391 @@
+use std::borrow::Cow;
+
+use chrono::{DateTime, Utc};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+use super::{
+    cwmp_urn_to_version, parse_to_int, CwmpVersion, DeviceId, EventCode, EventStruct, Inform,
+    ParameterValue, ParseLimits,
+};
+use crate::error::{self, Limit};
+
+/// The `DeviceId` of an `InformRef`
+#[derive(Debug, PartialEq, Eq, Default, Clone)]
+pub struct DeviceIdRef<'a> {
+    pub manufacturer: Cow<'a, str>,
+    pub oui: Cow<'a, str>,
+    pub product_class: Cow<'a, str>,
+    pub serial_number: Cow<'a, str>,
+}
+
+/// An `EventStruct` of an `InformRef`
+#[derive(Debug, PartialEq, Eq, Default, Clone)]
+pub struct EventStructRef<'a> {
+    pub event_code: Cow<'a, str>,
+    pub command_key: Cow<'a, str>,
+}
+
+/// A `ParameterValue` of an `InformRef`
+#[derive(Debug, PartialEq, Eq, Default, Clone)]
+pub struct ParameterValueRef<'a> {
+    pub name: Cow<'a, str>,
+    pub r#type: Cow<'a, str>,
+    pub value: Cow<'a, str>,
+}
+
+/// An Inform borrowing its strings from the XML it was parsed from, see
+/// `parse_inform_ref`
+///
+/// Text is only copied when it has to be unescaped, like a value holding
+/// `&amp;`. `to_inform` makes an `Inform` of it.
+#[derive(Debug, PartialEq, Eq, Default, Clone)]
+pub struct InformRef<'a> {
+    /// The version of the cwmp namespace of the envelope
+    pub cwmp_version: Option<CwmpVersion>,
+    /// The `ID` header, to send back with the `InformResponse`
+    pub id: Option<Cow<'a, str>>,
+    pub device_id: DeviceIdRef<'a>,
+    pub event: Vec<EventStructRef<'a>>,
+    pub max_envelopes: u32,
+    pub current_time: Option<DateTime<Utc>>,
+    pub retry_count: u32,
+    pub parameter_list: Vec<ParameterValueRef<'a>>,
+}
+
+impl InformRef<'_> {
+    /// The event codes of the Inform, in the order the CPE listed them
+    #[must_use]
+    pub fn event_codes(&self) -> Vec<EventCode> {
+        self.event
+            .iter()
+            .map(|e| EventCode::from(e.event_code.as_ref()))
+            .collect()
+    }
+
+    /// The value of the parameter `name`, as written
+    #[must_use]
+    pub fn parameter_value(&self, name: &str) -> Option<&str> {
+        self.parameter_list
+            .iter()
+            .find(|p| p.name == name)
+            .map(|p| p.value.as_ref())
+    }
+
+    /// An owned `Inform` with the same content
+    #[must_use]
+    pub fn to_inform(&self) -> Inform {
+        let d = &self.device_id;
+        Inform {
+            device_id: DeviceId::new(&d.manufacturer, &d.oui, &d.product_class, &d.serial_number),
+            event: self
+                .event
+                .iter()
+                .map(|e| EventStruct::new(&e.event_code, &e.command_key))
+                .collect(),
+            max_envelopes: self.max_envelopes,
+            current_time: self.current_time,
+            retry_count: self.retry_count,
+            parameter_list: self
+                .parameter_list
+                .iter()
+                .map(|p| ParameterValue::new(&p.name, &p.r#type, &p.value))
+                .collect(),
+            extra: vec![],
+        }
+    }
+}
+
+// the elements the parser cares about, anything else is `Other`
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Tag {
+    Envelope,
+    Header,
+    Body,
+    Id,
+    Inform,
+    DeviceId,
+    Manufacturer,
+    Oui,
+    ProductClass,
+    SerialNumber,
+    Event,
+    EventStruct,
+    EventCode,
+    CommandKey,
+    MaxEnvelopes,
+    CurrentTime,
+    RetryCount,
+    ParameterList,
+    ParameterValueStruct,
+    Name,
+    Value,
+    Other,
+}
+
+impl Tag {
+    fn from_local_name(name: &[u8]) -> Self {
+        match name {
+            b"Envelope" => Tag::Envelope,
+            b"Header" => Tag::Header,
+            b"Body" => Tag::Body,
+            b"ID" => Tag::Id,
+            b"Inform" => Tag::Inform,
+            b"DeviceId" => Tag::DeviceId,
+            b"Manufacturer" => Tag::Manufacturer,
+            b"OUI" => Tag::Oui,
+            b"ProductClass" => Tag::ProductClass,
+            b"SerialNumber" => Tag::SerialNumber,
+            b"Event" => Tag::Event,
+            b"EventStruct" => Tag::EventStruct,
+            b"EventCode" => Tag::EventCode,
+            b"CommandKey" => Tag::CommandKey,
+            b"MaxEnvelopes" => Tag::MaxEnvelopes,
+            b"CurrentTime" => Tag::CurrentTime,
+            b"RetryCount" => Tag::RetryCount,
+            b"ParameterList" => Tag::ParameterList,
+            b"ParameterValueStruct" => Tag::ParameterValueStruct,
+            b"Name" => Tag::Name,
+            b"Value" => Tag::Value,
+            _ => Tag::Other,
+        }
+    }
+}
+
+// `part` as a slice of `xml` with its lifetime, if it points into it
+fn reborrow<'a>(xml: &'a str, part: &[u8]) -> Option<&'a str> {
+    let start = (part.as_ptr() as usize).checked_sub(xml.as_ptr() as usize)?;
+    xml.get(start..start.checked_add(part.len())?)
+}
+
+struct Parser<'a> {
+    xml: &'a str,
+    limits: ParseLimits,
+    path: Vec<Tag>,
+    // number of child elements seen so far, one entry per open element
+    child_counts: Vec<usize>,
+    text: Option<Cow<'a, str>>,
+    inform: InformRef<'a>,
+    found: bool,
+}
+
+impl<'a> Parser<'a> {
+    // the same checks as `parse_with`, before the element is handled
+    fn limit_start(&mut self, e: &BytesStart<'a>) -> Result<(), error::Cwmp> {
+        let limits = self.limits;
+        if self.path.len() >= limits.max_depth {
+            return Err(Limit::Depth(limits.max_depth).into());
+        }
+        let mut attributes = 0;
+        for a in e.attributes() {
+            let a = a.map_err(quick_xml::Error::from)?;
+            attributes += 1;
+            if attributes > limits.max_attributes {
+                return Err(Limit::Attributes(limits.max_attributes).into());
+            }
+            if a.value.len() > limits.max_attribute_length {
+                return Err(Limit::AttributeLength(limits.max_attribute_length).into());
+            }
+        }
+        if let Some(count) = self.child_counts.last_mut() {
+            *count += 1;
+            if *count > limits.max_elements {
+                return Err(Limit::Elements(limits.max_elements).into());
+            }
+        }
+        self.child_counts.push(0);
+        Ok(())
+    }
+
+    fn append(&mut self, piece: Cow<'a, str>) -> Result<(), error::Cwmp> {
+        let length = self.text.as_ref().map_or(0, |t| t.len());
+        // CDATA and escaped text come in more than one piece
+        if length.saturating_add(piece.len()) > self.limits.max_text_length {
+            return Err(Limit::TextLength(self.limits.max_text_length).into());
+        }
+        match &mut self.text {
+            Some(t) => t.to_mut().push_str(&piece),
+            None => self.text = Some(piece),
+        }
+        Ok(())
+    }
+
+    fn start(&mut self, e: &BytesStart<'a>) -> Result<(), error::Cwmp> {
+        self.limit_start(e)?;
+        self.path
+            .push(Tag::from_local_name(e.local_name().as_ref()));
+        self.text = None;
+        match self.path[..] {
+            [Tag::Envelope] => {
+                for a in e.attributes() {
+                    let a = a.map_err(quick_xml::Error::from)?;
+                    if a.key.as_ref().starts_with(b"xmlns")
+                        && a.value.starts_with(b"urn:dslforum-org:cwmp-")
+                    {
+                        let urn = String::from_utf8_lossy(&a.value);
+                        self.inform.cwmp_version = Some(cwmp_urn_to_version(&urn));
+                    }
+                }
+            }
+            [Tag::Envelope, Tag::Body, Tag::Inform] => self.found = true,
+            [Tag::Envelope, Tag::Body, Tag::Inform, Tag::Event, Tag::EventStruct] => {
+                self.inform.event.push(EventStructRef::default());
+            }
+            [Tag::Envelope, Tag::Body, Tag::Inform, Tag::ParameterList, Tag::ParameterValueStruct] =>
+            {
+                self.inform
+                    .parameter_list
+                    .push(ParameterValueRef::default());
+            }
+            [Tag::Envelope, Tag::Body, Tag::Inform, Tag::ParameterList, Tag::ParameterValueStruct, Tag::Value] => {
+                for a in e.attributes() {
+                    let a = a.map_err(quick_xml::Error::from)?;
+                    if a.key.local_name().as_ref() == b"type" {
+                        let value = match reborrow(self.xml, &a.value) {
+                            Some(v) => {
+                                quick_xml::escape::unescape(v).map_err(quick_xml::Error::from)?
+                            }
+                            None => Cow::Owned(a.unescape_value()?.into_owned()),
+                        };
+                        if let Some(p) = self.inform.parameter_list.last_mut() {
+                            p.r#type = value;
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn end(&mut self) {
+        self.child_counts.pop();
+        let text = self.text.take().unwrap_or_default();
+        let inform = &mut self.inform;
+        match self.path[..] {
+            [Tag::Envelope, Tag::Header, Tag::Id] => inform.id = Some(text),
+            [Tag::Envelope, Tag::Body, Tag::Inform, Tag::DeviceId, field] => match field {
+                Tag::Manufacturer => inform.device_id.manufacturer = text,
+                Tag::Oui => inform.device_id.oui = text,
+                Tag::ProductClass => inform.device_id.product_class = text,
+                Tag::SerialNumber => inform.device_id.serial_number = text,
+                _ => {}
+            },
+            [Tag::Envelope, Tag::Body, Tag::Inform, Tag::Event, Tag::EventStruct, field] => {
+                if let Some(e) = inform.event.last_mut() {
+                    match field {
+                        Tag::EventCode => e.event_code = text,
+                        Tag::CommandKey => e.command_key = text,
+                        _ => {}
+                    }
+                }
+            }
+            [Tag::Envelope, Tag::Body, Tag::Inform, Tag::MaxEnvelopes] => {
+                inform.max_envelopes = parse_to_int(&text, 0);
+            }
+            [Tag::Envelope, Tag::Body, Tag::Inform, Tag::RetryCount] => {
+                inform.retry_count = parse_to_int(&text, 0);
+            }
+            [Tag::Envelope, Tag::Body, Tag::Inform, Tag::CurrentTime] => {
+                if let Ok(dt) = text.parse::<DateTime<Utc>>() {
+                    inform.current_time = Some(dt);
+                }
+            }
+            [Tag::Envelope, Tag::Body, Tag::Inform, Tag::ParameterList, Tag::ParameterValueStruct, field] => {
+                if let Some(p) = inform.parameter_list.last_mut() {
+                    match field {
+                        Tag::Name => p.name = text,
+                        Tag::Value => p.value = text,
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+        self.path.pop();
+    }
+}
+
+/// Parse an envelope carrying an Inform into an `InformRef` borrowing from
+/// `xml`, `None` if the envelope carries anything else, within the default
+/// `ParseLimits`. Much faster than `parse`, for an ACS taking in many
+/// periodic Informs; only the elements of the Inform and the `ID` header
+/// are looked at.
+///
+/// # Errors
+///
+/// Returns `error::Cwmp::XmlError` if `xml` is not well-formed, and
+/// `error::Cwmp::LimitExceeded` if it goes beyond the default `ParseLimits`
+pub fn parse_inform_ref(xml: &str) -> Result<Option<InformRef<'_>>, error::Cwmp> {
+    parse_inform_ref_with(xml, &ParseLimits::default())
+}
+
+/// `parse_inform_ref` within the given `ParseLimits`
+///
+/// # Errors
+///
+/// Returns `error::Cwmp::XmlError` if `xml` is not well-formed, and
+/// `error::Cwmp::LimitExceeded` if it goes beyond `limits`
+pub fn parse_inform_ref_with<'a>(
+    xml: &'a str,
+    limits: &ParseLimits,
+) -> Result<Option<InformRef<'a>>, error::Cwmp> {
+    if xml.len() > limits.max_bytes {
+        return Err(Limit::Bytes(limits.max_bytes).into());
+    }
+    let mut reader = Reader::from_str(xml);
+    let mut parser = Parser {
+        xml,
+        limits: *limits,
+        path: Vec::with_capacity(8),
+        child_counts: Vec::with_capacity(8),
+        text: None,
+        inform: InformRef::default(),
+        found: false,
+    };
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) => parser.start(&e)?,
+            Event::Empty(e) => {
+                parser.start(&e)?;
+                parser.end();
+            }
+            Event::End(_) => parser.end(),
+            Event::Text(t) => {
+                let piece = t.xml_content().map_err(quick_xml::Error::from)?;
+                parser.append(piece)?;
+            }
+            Event::CData(t) => {
+                let piece = t.xml_content().map_err(quick_xml::Error::from)?;
+                parser.append(piece)?;
+            }
+            Event::GeneralRef(r) => {
+                let piece = match r.resolve_char_ref()? {
+                    Some(c) => c.to_string(),
+                    None => {
+                        let name = r.xml_content().map_err(quick_xml::Error::from)?;
+                        match quick_xml::escape::resolve_predefined_entity(&name) {
+                            Some(s) => s.to_string(),
+                            None => {
+                                let range = 0..name.len();
+                                let e = quick_xml::escape::EscapeError::UnrecognizedEntity(
+                                    range,
+                                    name.into_owned(),
+                                );
+                                return Err(quick_xml::Error::from(e).into());
+                            }
+                        }
+                    }
+                };
+                parser.append(Cow::Owned(piece))?;
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    if let Some(tag) = parser.path.last() {
+        let e = quick_xml::errors::IllFormedError::MissingEndTag(format!("{tag:?}"));
+        return Err(quick_xml::Error::from(e).into());
+    }
+    Ok(parser.found.then_some(parser.inform))
+}
@@ -4,17 +4,22 @@ use std::io::Write;
 use quickcheck::{Arbitrary, Gen};
 use xml::writer::XmlEvent;
 
-use super::{cwmp_prefix, parse_to_int, write_simple, GenerateError};
+use super::{cwmp_prefix, parse_to_int, write_simple, GenerateError, GenerateOptions, XmlElement};
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct InformResponse {
     pub max_envelopes: u16,
+    pub extra: Vec<XmlElement>,
 }
 
 impl InformResponse {
     #[must_use]
     pub fn new(max_envelopes: u16) -> Self {
-        InformResponse { max_envelopes }
+        InformResponse {
+            max_envelopes,
+            extra: vec![],
+        }
     }
 
     /// Generate XML for `InformResponse`
@@ -26,11 +31,15 @@ impl InformResponse {
         &self,
         writer: &mut xml::EventWriter<W>,
         has_cwmp: bool,
+        options: &GenerateOptions,
     ) -> Result<(), GenerateError> {
         writer.write(XmlEvent::start_element(
-            &cwmp_prefix(has_cwmp, "InformResponse")[..],
+            &cwmp_prefix(options, has_cwmp, "InformResponse")[..],
         ))?;
         write_simple(writer, "MaxEnvelopes", &self.max_envelopes.to_string())?;
+        for e in &self.extra {
+            e.generate(writer)?;
+        }
         writer.write(XmlEvent::end_element())?;
         Ok(())
     }
@@ -51,7 +60,10 @@ impl Arbitrary for InformResponse {
             self.max_envelopes
                 .clone()
                 .shrink()
-                .map(|me| InformResponse { max_envelopes: me }),
+                .map(|me| InformResponse {
+                    max_envelopes: me,
+                    extra: vec![],
+                }),
         )
     }
 }
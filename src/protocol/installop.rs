@@ -1,15 +1,21 @@
 #[cfg(test)]
 use quickcheck::{Arbitrary, Gen};
 
-use super::XmlSafeString;
+use super::{XmlElement, XmlSafeString};
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct InstallOp {
     pub url: XmlSafeString,
     pub uuid: XmlSafeString,
     pub username: XmlSafeString,
     pub password: XmlSafeString,
     pub execution_env_ref: XmlSafeString,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub extra: Vec<XmlElement>,
 }
 
 impl InstallOp {
@@ -27,6 +33,7 @@ impl InstallOp {
             username: username.into(),
             password: password.into(),
             execution_env_ref: execution_env_ref.into(),
+            extra: vec![],
         }
     }
 }
@@ -40,6 +47,7 @@ impl Arbitrary for InstallOp {
             username: XmlSafeString::arbitrary(g),
             password: XmlSafeString::arbitrary(g),
             execution_env_ref: XmlSafeString::arbitrary(g),
+            extra: vec![],
         }
     }
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
@@ -58,6 +66,7 @@ impl Arbitrary for InstallOp {
                     username: un,
                     password: pw,
                     execution_env_ref: eer,
+                    extra: vec![],
                 }),
         )
     }
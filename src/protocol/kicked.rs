@@ -4,13 +4,15 @@ use std::io::Write;
 use quickcheck::{Arbitrary, Gen};
 use xml::writer::XmlEvent;
 
-use super::{cwmp_prefix, write_simple, GenerateError, XmlSafeString};
+use super::{cwmp_prefix, write_simple, GenerateError, GenerateOptions, XmlElement, XmlSafeString};
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Kicked {
     pub command: XmlSafeString,
     pub referer: XmlSafeString,
     pub arg: XmlSafeString,
     pub next: XmlSafeString,
+    pub extra: Vec<XmlElement>,
 }
 
 impl Kicked {
@@ -21,6 +23,7 @@ impl Kicked {
             referer: referer.into(),
             arg: arg.into(),
             next: next.into(),
+            extra: vec![],
         }
     }
 
@@ -33,14 +36,18 @@ impl Kicked {
         &self,
         writer: &mut xml::EventWriter<W>,
         has_cwmp: bool,
+        options: &GenerateOptions,
     ) -> Result<(), GenerateError> {
         writer.write(XmlEvent::start_element(
-            &cwmp_prefix(has_cwmp, "Kicked")[..],
+            &cwmp_prefix(options, has_cwmp, "Kicked")[..],
         ))?;
         write_simple(writer, "Command", self.command.0.as_ref())?;
         write_simple(writer, "Referer", self.referer.0.as_ref())?;
         write_simple(writer, "Arg", self.arg.0.as_ref())?;
         write_simple(writer, "Next", self.next.0.as_ref())?;
+        for e in &self.extra {
+            e.generate(writer)?;
+        }
         writer.write(XmlEvent::end_element())?;
         Ok(())
     }
@@ -71,6 +78,7 @@ impl Arbitrary for Kicked {
             referer: XmlSafeString::arbitrary(g),
             arg: XmlSafeString::arbitrary(g),
             next: XmlSafeString::arbitrary(g),
+            extra: vec![],
         }
     }
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
@@ -87,6 +95,7 @@ impl Arbitrary for Kicked {
                     referer: r,
                     arg: a,
                     next: n,
+                    extra: vec![],
                 }),
         )
     }
@@ -4,11 +4,13 @@ use std::io::Write;
 use quickcheck::{Arbitrary, Gen};
 use xml::writer::XmlEvent;
 
-use super::{cwmp_prefix, write_simple, GenerateError, XmlSafeString};
+use super::{cwmp_prefix, write_simple, GenerateError, GenerateOptions, XmlElement, XmlSafeString};
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct KickedResponse {
     pub next_url: XmlSafeString,
+    pub extra: Vec<XmlElement>,
 }
 
 impl KickedResponse {
@@ -16,6 +18,7 @@ impl KickedResponse {
     pub fn new(next_url: &str) -> Self {
         KickedResponse {
             next_url: next_url.into(),
+            extra: vec![],
         }
     }
 
@@ -28,11 +31,15 @@ impl KickedResponse {
         &self,
         writer: &mut xml::EventWriter<W>,
         has_cwmp: bool,
+        options: &GenerateOptions,
     ) -> Result<(), GenerateError> {
         writer.write(XmlEvent::start_element(
-            &cwmp_prefix(has_cwmp, "KickedResponse")[..],
+            &cwmp_prefix(options, has_cwmp, "KickedResponse")[..],
         ))?;
         write_simple(writer, "NextURL", self.next_url.0.as_ref())?;
+        for e in &self.extra {
+            e.generate(writer)?;
+        }
         writer.write(XmlEvent::end_element())?;
         Ok(())
     }
@@ -48,14 +55,13 @@ impl Arbitrary for KickedResponse {
     fn arbitrary(g: &mut Gen) -> Self {
         Self {
             next_url: XmlSafeString::arbitrary(g),
+            extra: vec![],
         }
     }
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
-        Box::new(
-            self.next_url
-                .clone()
-                .shrink()
-                .map(|n| KickedResponse { next_url: n }),
-        )
+        Box::new(self.next_url.clone().shrink().map(|n| KickedResponse {
+            next_url: n,
+            extra: vec![],
+        }))
     }
 }
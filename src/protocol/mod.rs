@@ -1,6 +1,7 @@
-use std::error::Error;
+use crate::error::Validation;
 use std::fmt;
 use std::io::Write;
+use xml::common::TextPosition;
 use xml::writer::XmlEvent;
 #[cfg(test)]
 extern crate quickcheck;
@@ -21,6 +22,8 @@ mod autonomoustransfercomplete;
 mod autonomoustransfercompleteresponse;
 mod autonopresult;
 mod bodyelement;
+mod bodyelementkind;
+mod capture;
 mod canceltransfer;
 mod canceltransferresponse;
 mod changedustate;
@@ -29,15 +32,20 @@ mod cwmpversion;
 mod deleteobject;
 mod deleteobjectresponse;
 mod deviceid;
+mod direction;
 mod download;
 mod downloadresponse;
 mod dustatechangecomplete;
 mod dustatechangecompleteresponse;
 mod envelope;
+mod eventcode;
 mod eventstruct;
 mod factoryreset;
 mod factoryresetresponse;
 mod fault;
+mod faultcode;
+mod filetype;
+mod generateoptions;
 mod getallqueuedtransfers;
 mod getallqueuedtransfersresponse;
 mod getoptions;
@@ -57,6 +65,8 @@ mod holdrequests;
 mod id;
 mod inform;
 mod informresponse;
+#[cfg(feature = "borrowed")]
+mod informref;
 mod installop;
 mod kicked;
 mod kickedresponse;
@@ -66,6 +76,9 @@ mod optionstruct;
 mod parameterattribute;
 mod parameterinfostruct;
 mod parametervalue;
+mod paramvalue;
+mod parselimits;
+mod parseoptions;
 mod queuedtransferstruct;
 mod reboot;
 mod rebootresponse;
@@ -75,23 +88,30 @@ mod scheduledownload;
 mod scheduledownloadresponse;
 mod scheduleinform;
 mod scheduleinformresponse;
+mod schema;
 mod sessiontimeout;
 mod setparameterattributes;
 mod setparameterattributesresponse;
 mod setparameterattributesstruct;
 mod setparametervalues;
+mod setparametervaluesfault;
 mod setparametervaluesresponse;
 mod setvouchers;
 mod setvouchersresponse;
+mod strict;
+mod structuralerror;
 mod supportedcwmpversions;
 mod timewindow;
 mod transfercomplete;
 mod transfercompleteresponse;
 mod uninstallop;
+mod unknownheader;
 mod updateop;
 mod upload;
 mod uploadresponse;
 mod usecwmpversion;
+mod vendorrpc;
+mod xmlelement;
 
 pub use addobject::AddObject;
 pub use addobjectresponse::AddObjectResponse;
@@ -103,23 +123,29 @@ pub use autonomoustransfercomplete::AutonomousTransferComplete;
 pub use autonomoustransfercompleteresponse::AutonomousTransferCompleteResponse;
 pub use autonopresult::AutonOpResult;
 pub use bodyelement::BodyElement;
+pub use bodyelementkind::BodyElementKind;
 pub use canceltransfer::CancelTransfer;
 pub use canceltransferresponse::CancelTransferResponse;
-pub use changedustate::ChangeDUState;
+pub use changedustate::{ChangeDUState, ChangeDUStateBuilder};
 pub use changedustateresponse::ChangeDUStateResponse;
-pub use cwmpversion::CwmpVersion;
+pub use cwmpversion::{negotiate, CwmpVersion};
 pub use deleteobject::DeleteObject;
 pub use deleteobjectresponse::DeleteObjectResponse;
 pub use deviceid::DeviceId;
-pub use download::Download;
+pub use direction::Direction;
+pub use download::{Download, DownloadBuilder};
 pub use downloadresponse::DownloadResponse;
 pub use dustatechangecomplete::DUStateChangeComplete;
 pub use dustatechangecompleteresponse::DUStateChangeCompleteResponse;
 pub use envelope::Envelope;
+pub use eventcode::EventCode;
 pub use eventstruct::EventStruct;
 pub use factoryreset::FactoryReset;
 pub use factoryresetresponse::FactoryResetResponse;
 pub use fault::{Fault, FaultDetail, FaultStruct};
+pub use faultcode::FaultCode;
+pub use filetype::FileType;
+pub use generateoptions::GenerateOptions;
 pub use getallqueuedtransfers::GetAllQueuedTransfers;
 pub use getallqueuedtransfersresponse::GetAllQueuedTransfersResponse;
 pub use getoptions::GetOptions;
@@ -139,6 +165,11 @@ pub use holdrequests::HoldRequests;
 pub use id::ID;
 pub use inform::Inform;
 pub use informresponse::InformResponse;
+#[cfg(feature = "borrowed")]
+pub use informref::{
+    parse_inform_ref, parse_inform_ref_with, DeviceIdRef, EventStructRef, InformRef,
+    ParameterValueRef,
+};
 pub use installop::InstallOp;
 pub use kicked::Kicked;
 pub use kickedresponse::KickedResponse;
@@ -148,12 +179,15 @@ pub use optionstruct::OptionStruct;
 pub use parameterattribute::ParameterAttribute;
 pub use parameterinfostruct::ParameterInfoStruct;
 pub use parametervalue::ParameterValue;
+pub use paramvalue::{ParamValue, UNKNOWN_TIME};
+pub use parselimits::ParseLimits;
+pub use parseoptions::ParseOptions;
 pub use queuedtransferstruct::QueuedTransferStruct;
 pub use reboot::Reboot;
 pub use rebootresponse::RebootResponse;
 pub use requestdownload::RequestDownload;
 pub use requestdownloadresponse::RequestDownloadResponse;
-pub use scheduledownload::ScheduleDownload;
+pub use scheduledownload::{ScheduleDownload, ScheduleDownloadBuilder};
 pub use scheduledownloadresponse::ScheduleDownloadResponse;
 pub use scheduleinform::ScheduleInform;
 pub use scheduleinformresponse::ScheduleInformResponse;
@@ -161,18 +195,23 @@ pub use sessiontimeout::SessionTimeout;
 pub use setparameterattributes::SetParameterAttributes;
 pub use setparameterattributesresponse::SetParameterAttributesResponse;
 pub use setparametervalues::SetParameterValues;
+pub use setparametervaluesfault::SetParameterValuesFault;
 pub use setparametervaluesresponse::SetParameterValuesResponse;
 pub use setvouchers::SetVouchers;
 pub use setvouchersresponse::SetVouchersResponse;
+pub use structuralerror::{StructuralError, StructuralErrorKind};
 pub use supportedcwmpversions::SupportedCWMPVersions;
 pub use timewindow::TimeWindow;
 pub use transfercomplete::TransferComplete;
 pub use transfercompleteresponse::TransferCompleteResponse;
 pub use uninstallop::UninstallOp;
+pub use unknownheader::UnknownHeader;
 pub use updateop::UpdateOp;
-pub use upload::Upload;
+pub use upload::{Upload, UploadBuilder};
 pub use uploadresponse::UploadResponse;
 pub use usecwmpversion::UseCWMPVersion;
+pub use vendorrpc::{DynVendorRpc, VendorRegistry, VendorRpc};
+pub use xmlelement::{XmlAttribute, XmlElement, XmlName, XmlNode};
 
 #[cfg(test)]
 const VALID_CHARS: &[u8] =
@@ -209,6 +248,23 @@ fn write_empty_tag<W: Write>(
     Ok(())
 }
 
+// start an array element, typed with the encoding prefix of the options
+fn write_array_start<W: Write>(
+    writer: &mut xml::EventWriter<W>,
+    options: &GenerateOptions,
+    name: &str,
+    array_type: &str,
+) -> Result<(), GenerateError> {
+    let array_type_attr = format!("{}:arrayType", options.encoding_prefix);
+    let xsi_type = format!("{}:Array", options.encoding_prefix);
+    let mut start = XmlEvent::start_element(name).attr(&array_type_attr[..], array_type);
+    if options.array_xsi_type {
+        start = start.attr("xsi:type", &xsi_type[..]);
+    }
+    writer.write(start)?;
+    Ok(())
+}
+
 fn write_fault_struct<W: Write>(
     writer: &mut xml::EventWriter<W>,
     fault: &FaultStruct,
@@ -216,6 +272,9 @@ fn write_fault_struct<W: Write>(
     writer.write(XmlEvent::start_element("FaultStruct"))?;
     write_simple(writer, "FaultCode", &fault.code.to_string())?;
     write_simple(writer, "FaultString", fault.string.0.as_ref())?;
+    for e in &fault.extra {
+        e.generate(writer)?;
+    }
     writer.write(XmlEvent::end_element())?;
     Ok(())
 }
@@ -241,6 +300,8 @@ pub fn gen_utc_date(year: i32, mon: u32, day: u32, hour: u32, min: u32, sec: u32
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct XmlSafeString(pub String);
 
 impl From<&str> for XmlSafeString {
@@ -286,6 +347,13 @@ impl Arbitrary for XmlSafeString {
 pub enum GenerateError {
     FromUtf8Error(std::string::FromUtf8Error),
     XmlError(xml::writer::Error),
+    /// A body element needing a newer CWMP version than the envelope's,
+    /// refused with `GenerateOptions::check_version`
+    Incompatible {
+        element: String,
+        required: CwmpVersion,
+        version: CwmpVersion,
+    },
 }
 
 impl From<xml::writer::Error> for GenerateError {
@@ -304,6 +372,14 @@ impl fmt::Display for GenerateError {
         match *self {
             GenerateError::FromUtf8Error(ref err) => std::fmt::Display::fmt(&err, f),
             GenerateError::XmlError(ref err) => std::fmt::Display::fmt(&err, f),
+            GenerateError::Incompatible {
+                ref element,
+                ref required,
+                ref version,
+            } => write!(
+                f,
+                "{element} requires CWMP {required}, the envelope is {version}"
+            ),
         }
     }
 }
@@ -312,6 +388,16 @@ impl fmt::Debug for GenerateError {
         match *self {
             GenerateError::FromUtf8Error(ref err) => std::fmt::Debug::fmt(&err, f),
             GenerateError::XmlError(ref err) => std::fmt::Debug::fmt(&err, f),
+            GenerateError::Incompatible {
+                ref element,
+                ref required,
+                ref version,
+            } => f
+                .debug_struct("Incompatible")
+                .field("element", element)
+                .field("required", required)
+                .field("version", version)
+                .finish(),
         }
     }
 }
@@ -330,14 +416,31 @@ fn extract_attribute(
     }
 }
 
-fn cwmp_prefix(envelope_has_cwmp_version: bool, postfix: &str) -> String {
+fn cwmp_prefix(
+    options: &GenerateOptions,
+    envelope_has_cwmp_version: bool,
+    postfix: &str,
+) -> String {
     if envelope_has_cwmp_version {
-        format!("cwmp:{postfix}")
+        format!("{}:{postfix}", options.cwmp_prefix)
     } else {
         postfix.to_string()
     }
 }
 
+// the arrayType of a CWMP struct array, like "cwmp:EventStruct[2]"
+fn cwmp_array_type(
+    options: &GenerateOptions,
+    envelope_has_cwmp_version: bool,
+    struct_name: &str,
+    len: usize,
+) -> String {
+    format!(
+        "{}[{len}]",
+        cwmp_prefix(options, envelope_has_cwmp_version, struct_name)
+    )
+}
+
 // parses urns like "urn:dslforum-org:cwmp-1-0" into
 // CwmpVersion, i.e. (1,0) in this example
 fn cwmp_urn_to_version(urn: &str) -> CwmpVersion {
@@ -369,14 +472,49 @@ fn parse_to_int<T: Parseable + std::str::FromStr>(chars: &str, default: T) -> T
     }
 }
 
+// refuse an empty mandatory field of a message being built
+fn check_mandatory(field: &str, value: &str) -> Result<(), Validation> {
+    if value.is_empty() {
+        return Err(Validation::Missing(field.to_string()));
+    }
+    Ok(())
+}
+
+// refuse a string field longer than its TR-069 limit, counted in characters
+fn check_length(field: &str, value: &str, max: usize) -> Result<(), Validation> {
+    let length = value.chars().count();
+    if length > max {
+        return Err(Validation::TooLong {
+            field: field.to_string(),
+            length,
+            max,
+        });
+    }
+    Ok(())
+}
+
 impl State {
     #[must_use]
     pub fn new() -> Self {
+        State::with_options(ParseOptions::default())
+    }
+    #[must_use]
+    pub fn with_options(options: ParseOptions) -> Self {
         State {
             last_text: String::new(),
             envelope: Envelope::default(),
             path: vec![],
             error: None,
+            options,
+            position: TextPosition::new(),
+            children: vec![],
+            scopes: vec![],
+            capture: vec![],
+            capture_target: capture::Target::Body,
+            empty_body_element: None,
+            child_counts: vec![],
+            text_length: 0,
+            limit_exceeded: None,
         }
     }
     pub fn start_handler(
@@ -385,21 +523,74 @@ impl State {
         attributes: &[xml::attribute::OwnedAttribute],
         namespace: &xml::namespace::Namespace,
     ) {
+        if self.limit_exceeded.is_some() || self.limit_start(attributes) {
+            return;
+        }
         // push a copy of the name into the current path
         self.path.push(name.local_name.to_string());
+        if self.options.strict {
+            self.strict_start(name);
+        }
+        if self.capture_start(name, attributes, namespace) {
+            return;
+        }
+        if self.path.len() == 3 && self.path[1] == "Body" {
+            if let Some(rpc) = self.options.vendor_rpcs.create(name) {
+                self.envelope.body.push(BodyElement::Vendor(rpc));
+            }
+        }
 
         self.envelope
             .start_handler(&self.path, name, attributes, namespace);
     }
 
     pub fn end_handler(&mut self, name: &xml::name::OwnedName) {
+        if self.limit_exceeded.is_some() {
+            return;
+        }
+        self.limit_end();
+        if self.options.strict {
+            self.strict_end();
+        }
+        let captured = self.capture_end();
+        if self.options.check_version && self.path.len() == 3 && self.path[1] == "Body" {
+            self.check_version();
+        }
         // pop the name from the current path
         self.path.pop();
-        self.envelope.end_handler(&self.path, name);
+        if !captured {
+            self.envelope.end_handler(&self.path, name);
+        }
+    }
+
+    // called at the end of a body element, before its name is popped
+    fn check_version(&mut self) {
+        let (Some(version), Some(be)) = (&self.envelope.cwmp_version, self.envelope.body.last())
+        else {
+            return;
+        };
+        let required = be.min_version();
+        if required > *version {
+            let kind = StructuralErrorKind::Incompatible {
+                required,
+                version: version.clone(),
+            };
+            self.fail(kind);
+        }
     }
     pub fn characters(&mut self, characters: &String) {
+        if self.limit_exceeded.is_some() || self.limit_characters(characters) {
+            return;
+        }
         self.last_text = String::from(characters);
-        self.envelope.characters(&self.path, characters);
+        let characters = if self.options.strict {
+            self.strict_characters(characters)
+        } else {
+            characters
+        };
+        if !self.capture_characters(characters) {
+            self.envelope.characters(&self.path, characters);
+        }
     }
 }
 
@@ -413,5 +604,24 @@ pub struct State {
     pub path: Vec<String>,
     pub last_text: String,
     pub envelope: Envelope,
-    pub error: Option<Box<dyn Error + Send + Sync>>,
+    pub error: Option<StructuralError>,
+    pub options: ParseOptions,
+    // position of the event currently being handled, kept up to date by the reader loop
+    pub position: TextPosition,
+    // names of the direct children seen so far, one entry per open element (strict mode only)
+    children: Vec<Vec<String>>,
+    // namespaces in scope of the open elements down to the body elements
+    scopes: Vec<xml::namespace::Namespace>,
+    // unknown elements being captured, outermost first, with the namespaces in scope of each
+    capture: Vec<(XmlElement, xml::namespace::Namespace)>,
+    capture_target: capture::Target,
+    // the current body element when it is one without arguments, kept as an
+    // unknown body element instead if a child shows up
+    empty_body_element: Option<XmlElement>,
+    // number of child elements seen so far, one entry per open element
+    child_counts: Vec<usize>,
+    // length of the text of the current element so far
+    text_length: usize,
+    // the first of the `ParseLimits` the envelope went beyond
+    pub limit_exceeded: Option<crate::error::Limit>,
 }
@@ -4,9 +4,10 @@ use std::io::Write;
 use quickcheck::{Arbitrary, Gen};
 use xml::writer::XmlEvent;
 
-use super::{bool2str, cwmp_prefix, GenerateError};
+use super::{bool2str, cwmp_prefix, GenerateError, GenerateOptions};
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NoMoreRequests {
     pub must_understand: bool,
     pub value: u8,
@@ -30,9 +31,10 @@ impl NoMoreRequests {
         &self,
         writer: &mut xml::EventWriter<W>,
         has_cwmp: bool,
+        options: &GenerateOptions,
     ) -> Result<(), GenerateError> {
         writer.write(
-            XmlEvent::start_element(&cwmp_prefix(has_cwmp, "NoMoreRequests")[..])
+            XmlEvent::start_element(&cwmp_prefix(options, has_cwmp, "NoMoreRequests")[..])
                 .attr("mustUnderstand", bool2str(self.must_understand)),
         )?;
         writer.write(&self.value.to_string()[..])?;
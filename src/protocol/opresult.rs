@@ -4,9 +4,10 @@ use quickcheck::{Arbitrary, Gen};
 
 #[cfg(test)]
 use super::gen_utc_date;
-use super::{FaultStruct, XmlSafeString};
+use super::{FaultStruct, XmlElement, XmlSafeString};
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OpResult {
     pub uuid: XmlSafeString,
     pub deployment_unit_ref: XmlSafeString,
@@ -17,6 +18,11 @@ pub struct OpResult {
     pub start_time: Option<DateTime<Utc>>,
     pub complete_time: Option<DateTime<Utc>>,
     pub fault: FaultStruct,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub extra: Vec<XmlElement>,
 }
 
 impl OpResult {
@@ -43,6 +49,7 @@ impl OpResult {
             start_time: Some(start_time),
             complete_time: Some(complete_time),
             fault,
+            extra: vec![],
         }
     }
 }
@@ -60,6 +67,7 @@ impl Arbitrary for OpResult {
             start_time: Some(gen_utc_date(2014, 11, 28, 12, 0, 9)),
             complete_time: Some(gen_utc_date(2014, 11, 29, 12, 0, 9)),
             fault: FaultStruct::arbitrary(g),
+            extra: vec![],
         }
     }
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
@@ -84,6 +92,7 @@ impl Arbitrary for OpResult {
                     start_time: Some(gen_utc_date(2014, 11, 28, 12, 0, 9)),
                     complete_time: Some(gen_utc_date(2014, 11, 29, 12, 0, 9)),
                     fault: f,
+                    extra: vec![],
                 }),
         )
     }
@@ -4,9 +4,10 @@ use quickcheck::{Arbitrary, Gen};
 
 #[cfg(test)]
 use super::gen_utc_date;
-use super::XmlSafeString;
+use super::{XmlElement, XmlSafeString};
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OptionStruct {
     pub option_name: XmlSafeString,
     pub voucher_sn: XmlSafeString,
@@ -15,6 +16,11 @@ pub struct OptionStruct {
     pub start_date: Option<DateTime<Utc>>,
     pub expiration_date: Option<DateTime<Utc>>,
     pub is_transferable: u8,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub extra: Vec<XmlElement>,
 }
 
 impl OptionStruct {
@@ -36,6 +42,7 @@ impl OptionStruct {
             start_date: Some(start_date),
             expiration_date: Some(expiration_date),
             is_transferable,
+            extra: vec![],
         }
     }
 }
@@ -51,6 +58,7 @@ impl Arbitrary for OptionStruct {
             start_date: Some(gen_utc_date(2014, 11, 28, 12, 0, 9)),
             expiration_date: Some(gen_utc_date(2014, 11, 29, 12, 0, 9)),
             is_transferable: u8::arbitrary(g),
+            extra: vec![],
         }
     }
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
@@ -71,6 +79,7 @@ impl Arbitrary for OptionStruct {
                     is_transferable: i,
                     start_date: Some(gen_utc_date(2014, 11, 28, 12, 0, 9)),
                     expiration_date: Some(gen_utc_date(2014, 11, 29, 12, 0, 9)),
+                    extra: vec![],
                 }),
         )
     }
@@ -1,13 +1,19 @@
 #[cfg(test)]
 use quickcheck::{Arbitrary, Gen};
 
-use super::{convert_to_xml_safe_strings, XmlSafeString};
+use super::{convert_to_xml_safe_strings, XmlElement, XmlSafeString};
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ParameterAttribute {
     pub name: XmlSafeString,
     pub notification: XmlSafeString,
     pub accesslist: Vec<XmlSafeString>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub extra: Vec<XmlElement>,
 }
 impl ParameterAttribute {
     #[must_use]
@@ -16,6 +22,7 @@ impl ParameterAttribute {
             name: name.into(),
             notification: notification.into(),
             accesslist: convert_to_xml_safe_strings(accesslist),
+            extra: vec![],
         }
     }
 }
@@ -27,6 +34,7 @@ impl Arbitrary for ParameterAttribute {
             name: XmlSafeString::arbitrary(g),
             notification: XmlSafeString::arbitrary(g),
             accesslist: Vec::<XmlSafeString>::arbitrary(g),
+            extra: vec![],
         }
     }
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
@@ -41,6 +49,7 @@ impl Arbitrary for ParameterAttribute {
                     name: n,
                     notification: no,
                     accesslist: a,
+                    extra: vec![],
                 }),
         )
     }
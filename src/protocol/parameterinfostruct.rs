@@ -1,12 +1,18 @@
 #[cfg(test)]
 use quickcheck::{Arbitrary, Gen};
 
-use super::XmlSafeString;
+use super::{XmlElement, XmlSafeString};
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ParameterInfoStruct {
     pub name: XmlSafeString,
     pub writable: u8,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub extra: Vec<XmlElement>,
 }
 
 impl ParameterInfoStruct {
@@ -15,6 +21,7 @@ impl ParameterInfoStruct {
         ParameterInfoStruct {
             name: name.into(),
             writable,
+            extra: vec![],
         }
     }
 }
@@ -31,6 +38,7 @@ impl Arbitrary for ParameterInfoStruct {
                 .map(|(n, w)| ParameterInfoStruct {
                     name: n,
                     writable: w,
+                    extra: vec![],
                 }),
         )
     }
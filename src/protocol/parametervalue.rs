@@ -1,13 +1,19 @@
 #[cfg(test)]
 use quickcheck::{Arbitrary, Gen};
 
-use super::XmlSafeString;
+use super::{ParamValue, XmlElement, XmlSafeString};
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ParameterValue {
     pub name: XmlSafeString,
     pub r#type: XmlSafeString,
     pub value: XmlSafeString,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub extra: Vec<XmlElement>,
 }
 
 impl ParameterValue {
@@ -17,6 +23,28 @@ impl ParameterValue {
             name: name.into(),
             r#type: param_type.into(),
             value: value.into(),
+            extra: vec![],
+        }
+    }
+
+    /// A parameter with a typed value
+    #[must_use]
+    pub fn typed(name: &str, value: &ParamValue) -> Self {
+        ParameterValue::new(name, value.xsd_type(), &value.to_string())
+    }
+
+    /// The typed value, or `ParamValue::Unknown` with the type and value as
+    /// written if the value is invalid for its type or not in the form
+    /// `ParameterValue::typed` writes, like `1` for an `xsd:boolean`
+    ///
+    /// `ParameterValue::typed` turns the result back into the same
+    /// `ParameterValue`. `ParamValue::parse` reads every valid form.
+    #[must_use]
+    pub fn param_value(&self) -> ParamValue {
+        let unknown = || ParamValue::Unknown(self.r#type.0.clone(), self.value.0.clone());
+        match ParamValue::parse(&self.r#type.0, &self.value.0) {
+            Ok(v) if v.xsd_type() == self.r#type.0 && v.to_string() == self.value.0 => v,
+            _ => unknown(),
         }
     }
 }
@@ -28,6 +56,7 @@ impl Arbitrary for ParameterValue {
             name: XmlSafeString::arbitrary(g),
             r#type: XmlSafeString::arbitrary(g),
             value: XmlSafeString::arbitrary(g),
+            extra: vec![],
         }
     }
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
@@ -38,6 +67,7 @@ impl Arbitrary for ParameterValue {
                     name: n,
                     r#type: t,
                     value: v,
+                    extra: vec![],
                 }),
         )
     }
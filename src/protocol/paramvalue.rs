@@ -0,0 +1,199 @@
+use std::fmt;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chrono::{DateTime, SecondsFormat, Utc};
+#[cfg(test)]
+use quickcheck::{Arbitrary, Gen};
+
+use crate::error::Value;
+
+#[cfg(test)]
+use super::gen_utc_date;
+
+/// The TR-069 way of saying a date and time is not known
+pub const UNKNOWN_TIME: &str = "0001-01-01T00:00:00Z";
+
+/// A parameter value, typed after its `xsi:type`
+///
+/// `ParamValue::parse` reads every valid lexical form, like `1` for an
+/// `xsd:boolean`, any time zone offset for an `xsd:dateTime` or a type
+/// without its `xsd:` prefix, and reports invalid values.
+/// `ParameterValue::param_value` keeps invalid values, and valid ones in
+/// another form than their `Display`, as `Unknown` instead.
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ParamValue {
+    String(String),
+    Int(i32),
+    UnsignedInt(u32),
+    Long(i64),
+    UnsignedLong(u64),
+    Boolean(bool),
+    /// `None` is the unknown time, `0001-01-01T00:00:00Z`
+    DateTime(Option<DateTime<Utc>>),
+    Base64(Vec<u8>),
+    HexBinary(Vec<u8>),
+    /// Any other type, or a value kept as it was written: (type, raw value)
+    Unknown(String, String),
+}
+
+impl ParamValue {
+    /// Parse the raw `value` of a parameter of type `xsd_type`, like `xsd:unsignedInt`.
+    /// Types without a variant of their own become `Unknown`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `error::Value` if `value` is not valid for `xsd_type`,
+    /// like `-1` or `4294967296` for an `xsd:unsignedInt`.
+    pub fn parse(xsd_type: &str, value: &str) -> Result<Self, Value> {
+        let invalid = || Value::Invalid {
+            xsd_type: xsd_type.to_string(),
+            value: value.to_string(),
+        };
+        let trimmed = value.trim();
+        let parsed = match local_type(xsd_type) {
+            "string" => ParamValue::String(value.to_string()),
+            "int" => ParamValue::Int(parse_integer(xsd_type, trimmed)?),
+            "unsignedInt" => ParamValue::UnsignedInt(parse_integer(xsd_type, trimmed)?),
+            "long" => ParamValue::Long(parse_integer(xsd_type, trimmed)?),
+            "unsignedLong" => ParamValue::UnsignedLong(parse_integer(xsd_type, trimmed)?),
+            "boolean" => match trimmed {
+                "true" | "1" => ParamValue::Boolean(true),
+                "false" | "0" => ParamValue::Boolean(false),
+                _ => return Err(invalid()),
+            },
+            "dateTime" if trimmed == UNKNOWN_TIME => ParamValue::DateTime(None),
+            "dateTime" => ParamValue::DateTime(Some(
+                trimmed.parse::<DateTime<Utc>>().map_err(|_| invalid())?,
+            )),
+            "base64" | "base64Binary" => ParamValue::Base64(
+                STANDARD
+                    .decode(strip_whitespace(value))
+                    .map_err(|_| invalid())?,
+            ),
+            "hexBinary" => ParamValue::HexBinary(decode_hex(trimmed).ok_or_else(invalid)?),
+            _ => ParamValue::Unknown(xsd_type.to_string(), value.to_string()),
+        };
+        Ok(parsed)
+    }
+
+    /// The `xsi:type` the value is written with
+    #[must_use]
+    pub fn xsd_type(&self) -> &str {
+        match self {
+            ParamValue::String(_) => "xsd:string",
+            ParamValue::Int(_) => "xsd:int",
+            ParamValue::UnsignedInt(_) => "xsd:unsignedInt",
+            ParamValue::Long(_) => "xsd:long",
+            ParamValue::UnsignedLong(_) => "xsd:unsignedLong",
+            ParamValue::Boolean(_) => "xsd:boolean",
+            ParamValue::DateTime(_) => "xsd:dateTime",
+            ParamValue::Base64(_) => "xsd:base64",
+            ParamValue::HexBinary(_) => "xsd:hexBinary",
+            ParamValue::Unknown(t, _) => t,
+        }
+    }
+
+    /// Check an `Unknown` value of a known type, the typed variants are always valid
+    ///
+    /// # Errors
+    ///
+    /// Returns an `error::Value` if the raw value does not parse as its type
+    pub fn validate(&self) -> Result<(), Value> {
+        match self {
+            ParamValue::Unknown(t, v) => ParamValue::parse(t, v).map(|_| ()),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for ParamValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParamValue::String(s) => write!(f, "{s}"),
+            ParamValue::Int(i) => write!(f, "{i}"),
+            ParamValue::UnsignedInt(i) => write!(f, "{i}"),
+            ParamValue::Long(i) => write!(f, "{i}"),
+            ParamValue::UnsignedLong(i) => write!(f, "{i}"),
+            ParamValue::Boolean(b) => write!(f, "{b}"),
+            ParamValue::DateTime(None) => write!(f, "{UNKNOWN_TIME}"),
+            ParamValue::DateTime(Some(dt)) => {
+                write!(f, "{}", dt.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+            }
+            ParamValue::Base64(b) => write!(f, "{}", STANDARD.encode(b)),
+            ParamValue::HexBinary(b) => b.iter().try_for_each(|x| write!(f, "{x:02x}")),
+            ParamValue::Unknown(_, v) => write!(f, "{v}"),
+        }
+    }
+}
+
+// "xsd:unsignedInt" and "unsignedInt" are the same type
+fn local_type(xsd_type: &str) -> &str {
+    xsd_type.rsplit(':').next().unwrap_or(xsd_type)
+}
+
+fn parse_integer<T>(xsd_type: &str, value: &str) -> Result<T, Value>
+where
+    T: std::str::FromStr<Err = std::num::ParseIntError>,
+{
+    value.parse::<T>().map_err(|e| {
+        let xsd_type = xsd_type.to_string();
+        let value = value.to_string();
+        match e.kind() {
+            std::num::IntErrorKind::PosOverflow | std::num::IntErrorKind::NegOverflow => {
+                Value::OutOfRange { xsd_type, value }
+            }
+            // a minus sign in front of an unsigned type
+            std::num::IntErrorKind::InvalidDigit
+                if value.starts_with('-') && value[1..].bytes().all(|b| b.is_ascii_digit()) =>
+            {
+                Value::OutOfRange { xsd_type, value }
+            }
+            _ => Value::Invalid { xsd_type, value },
+        }
+    })
+}
+
+fn strip_whitespace(value: &str) -> String {
+    value.chars().filter(|c| !c.is_whitespace()).collect()
+}
+
+fn decode_hex(value: &str) -> Option<Vec<u8>> {
+    if value.len() % 2 != 0 || !value.is_ascii() {
+        return None;
+    }
+    (0..value.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&value[i..i + 2], 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+impl Arbitrary for ParamValue {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let time = gen_utc_date(
+            2000 + i32::from(u8::arbitrary(g) % 50),
+            1 + u32::from(u8::arbitrary(g) % 12),
+            1 + u32::from(u8::arbitrary(g) % 28),
+            u32::from(u8::arbitrary(g) % 24),
+            u32::from(u8::arbitrary(g) % 60),
+            u32::from(u8::arbitrary(g) % 60),
+        );
+        match u8::arbitrary(g) % 10 {
+            0 => ParamValue::String(super::XmlSafeString::arbitrary(g).0),
+            1 => ParamValue::Int(i32::arbitrary(g)),
+            2 => ParamValue::UnsignedInt(u32::arbitrary(g)),
+            3 => ParamValue::Long(i64::arbitrary(g)),
+            4 => ParamValue::UnsignedLong(u64::arbitrary(g)),
+            5 => ParamValue::Boolean(bool::arbitrary(g)),
+            6 => ParamValue::DateTime(if bool::arbitrary(g) { Some(time) } else { None }),
+            7 => ParamValue::Base64(Vec::<u8>::arbitrary(g)),
+            8 => ParamValue::HexBinary(Vec::<u8>::arbitrary(g)),
+            _ => ParamValue::Unknown(
+                String::from("xsd:anyURI"),
+                super::XmlSafeString::arbitrary(g).0,
+            ),
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use xml::attribute::OwnedAttribute;
+
+use super::State;
+use crate::error::Limit;
+
+/// Bounds on what an envelope may contain, checked while it is parsed so
+/// that hostile input fails early instead of exhausting memory
+///
+/// The reader itself stops at the lengths and the number of attributes, so
+/// that it does not buffer more of them than allowed.
+///
+/// The defaults leave ample room for real envelopes, like a
+/// `GetParameterValuesResponse` for a whole data model; `unlimited` turns
+/// every check off.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ParseLimits {
+    /// The size of the envelope, in bytes
+    pub max_bytes: usize,
+    /// How deep elements may be nested, the Envelope being at depth 1
+    pub max_depth: usize,
+    /// The number of child elements of any element, like the
+    /// `ParameterValueStruct` entries of a `ParameterList`
+    pub max_elements: usize,
+    /// The length of the text of an element, in bytes
+    pub max_text_length: usize,
+    /// The number of attributes of an element
+    pub max_attributes: usize,
+    /// The length of the value of an attribute, in bytes
+    pub max_attribute_length: usize,
+}
+
+impl ParseLimits {
+    #[must_use]
+    pub fn new() -> Self {
+        ParseLimits::default()
+    }
+    /// No limits at all, for trusted input only
+    #[must_use]
+    pub fn unlimited() -> Self {
+        ParseLimits {
+            max_bytes: usize::MAX,
+            max_depth: usize::MAX,
+            max_elements: usize::MAX,
+            max_text_length: usize::MAX,
+            max_attributes: usize::MAX,
+            max_attribute_length: usize::MAX,
+        }
+    }
+    #[must_use]
+    pub fn max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+    #[must_use]
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+    #[must_use]
+    pub fn max_elements(mut self, max_elements: usize) -> Self {
+        self.max_elements = max_elements;
+        self
+    }
+    #[must_use]
+    pub fn max_text_length(mut self, max_text_length: usize) -> Self {
+        self.max_text_length = max_text_length;
+        self
+    }
+    #[must_use]
+    pub fn max_attributes(mut self, max_attributes: usize) -> Self {
+        self.max_attributes = max_attributes;
+        self
+    }
+    #[must_use]
+    pub fn max_attribute_length(mut self, max_attribute_length: usize) -> Self {
+        self.max_attribute_length = max_attribute_length;
+        self
+    }
+}
+
+impl Default for ParseLimits {
+    fn default() -> Self {
+        ParseLimits {
+            max_bytes: 16 * 1024 * 1024,
+            max_depth: 64,
+            max_elements: 100_000,
+            max_text_length: 1024 * 1024,
+            max_attributes: 64,
+            max_attribute_length: 64 * 1024,
+        }
+    }
+}
+
+impl State {
+    fn exceed(&mut self, limit: Limit) {
+        // only the first limit is reported
+        self.limit_exceeded.get_or_insert(limit);
+    }
+
+    // called before the element name is pushed onto the path, true if the
+    // element goes beyond a limit and must not be handled
+    pub(super) fn limit_start(&mut self, attributes: &[OwnedAttribute]) -> bool {
+        let limits = self.options.limits;
+        if self.path.len() >= limits.max_depth {
+            self.exceed(Limit::Depth(limits.max_depth));
+        } else if attributes.len() > limits.max_attributes {
+            self.exceed(Limit::Attributes(limits.max_attributes));
+        } else if attributes
+            .iter()
+            .any(|a| a.value.len() > limits.max_attribute_length)
+        {
+            self.exceed(Limit::AttributeLength(limits.max_attribute_length));
+        } else if let Some(count) = self.child_counts.last_mut() {
+            *count += 1;
+            if *count > limits.max_elements {
+                self.exceed(Limit::Elements(limits.max_elements));
+            }
+        }
+        if self.limit_exceeded.is_some() {
+            return true;
+        }
+        self.child_counts.push(0);
+        self.text_length = 0;
+        false
+    }
+
+    pub(super) fn limit_end(&mut self) {
+        self.child_counts.pop();
+        self.text_length = 0;
+    }
+
+    // true if the characters make the text of the element too long
+    pub(super) fn limit_characters(&mut self, characters: &str) -> bool {
+        // CDATA and escaped text can come in more than one event
+        self.text_length = self.text_length.saturating_add(characters.len());
+        if self.text_length > self.options.limits.max_text_length {
+            self.exceed(Limit::TextLength(self.options.limits.max_text_length));
+        }
+        self.limit_exceeded.is_some()
+    }
+}
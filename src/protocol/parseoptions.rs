@@ -0,0 +1,47 @@
+use super::{ParseLimits, VendorRegistry};
+
+/// Options controlling how an envelope is parsed
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ParseOptions {
+    /// Reject unknown elements, missing mandatory elements, and integers or
+    /// dateTimes that cannot be parsed, instead of silently skipping them.
+    pub strict: bool,
+    /// Reject body elements that need a newer CWMP version than the
+    /// namespace of the envelope
+    pub check_version: bool,
+    /// Vendor RPCs to parse into a `BodyElement::Vendor` rather than keep
+    /// as a `BodyElement::Unknown`
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub vendor_rpcs: VendorRegistry,
+    /// Bounds on the size and shape of the envelope
+    pub limits: ParseLimits,
+}
+
+impl ParseOptions {
+    #[must_use]
+    pub fn new() -> Self {
+        ParseOptions::default()
+    }
+    #[must_use]
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+    #[must_use]
+    pub fn check_version(mut self, check_version: bool) -> Self {
+        self.check_version = check_version;
+        self
+    }
+    #[must_use]
+    pub fn vendor_rpcs(mut self, vendor_rpcs: VendorRegistry) -> Self {
+        self.vendor_rpcs = vendor_rpcs;
+        self
+    }
+    #[must_use]
+    pub fn limits(mut self, limits: ParseLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+}
@@ -1,12 +1,18 @@
 #[cfg(test)]
 use quickcheck::{Arbitrary, Gen};
 
-use super::XmlSafeString;
+use super::{XmlElement, XmlSafeString};
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct QueuedTransferStruct {
     pub command_key: Option<XmlSafeString>,
     pub state: Option<XmlSafeString>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub extra: Vec<XmlElement>,
 }
 
 impl QueuedTransferStruct {
@@ -15,6 +21,7 @@ impl QueuedTransferStruct {
         QueuedTransferStruct {
             command_key: command_key.map(Into::into),
             state: state.map(Into::into),
+            extra: vec![],
         }
     }
 }
@@ -25,6 +32,7 @@ impl Arbitrary for QueuedTransferStruct {
         Self {
             command_key: Option::<XmlSafeString>::arbitrary(g),
             state: Option::<XmlSafeString>::arbitrary(g),
+            extra: vec![],
         }
     }
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
@@ -34,6 +42,7 @@ impl Arbitrary for QueuedTransferStruct {
                 .map(|(c, s)| QueuedTransferStruct {
                     command_key: c,
                     state: s,
+                    extra: vec![],
                 }),
         )
     }
@@ -4,11 +4,13 @@ use std::io::Write;
 use quickcheck::{Arbitrary, Gen};
 use xml::writer::XmlEvent;
 
-use super::{cwmp_prefix, write_simple, GenerateError, XmlSafeString};
+use super::{cwmp_prefix, write_simple, GenerateError, GenerateOptions, XmlElement, XmlSafeString};
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Reboot {
     pub command_key: XmlSafeString,
+    pub extra: Vec<XmlElement>,
 }
 
 impl Reboot {
@@ -16,6 +18,7 @@ impl Reboot {
     pub fn new(command_key: &str) -> Self {
         Reboot {
             command_key: command_key.into(),
+            extra: vec![],
         }
     }
 
@@ -28,11 +31,15 @@ impl Reboot {
         &self,
         writer: &mut xml::EventWriter<W>,
         has_cwmp: bool,
+        options: &GenerateOptions,
     ) -> Result<(), GenerateError> {
         writer.write(XmlEvent::start_element(
-            &cwmp_prefix(has_cwmp, "Reboot")[..],
+            &cwmp_prefix(options, has_cwmp, "Reboot")[..],
         ))?;
         write_simple(writer, "CommandKey", self.command_key.0.as_ref())?;
+        for e in &self.extra {
+            e.generate(writer)?;
+        }
         writer.write(XmlEvent::end_element())?;
         Ok(())
     }
@@ -48,14 +55,13 @@ impl Arbitrary for Reboot {
     fn arbitrary(g: &mut Gen) -> Self {
         Self {
             command_key: XmlSafeString::arbitrary(g),
+            extra: vec![],
         }
     }
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
-        Box::new(
-            self.command_key
-                .clone()
-                .shrink()
-                .map(|c| Reboot { command_key: c }),
-        )
+        Box::new(self.command_key.clone().shrink().map(|c| Reboot {
+            command_key: c,
+            extra: vec![],
+        }))
     }
 }
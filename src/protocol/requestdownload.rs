@@ -1,14 +1,19 @@
 use std::io::Write;
 
-use super::{cwmp_prefix, write_simple, ArgStruct, GenerateError, XmlSafeString};
+use super::{
+    cwmp_array_type, cwmp_prefix, write_array_start, write_simple, ArgStruct, FileType,
+    GenerateError, GenerateOptions, XmlElement, XmlSafeString,
+};
 #[cfg(test)]
 use quickcheck::{Arbitrary, Gen};
 use xml::writer::XmlEvent;
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RequestDownload {
     pub file_type: XmlSafeString,
     pub file_type_arg: Vec<ArgStruct>,
+    pub extra: Vec<XmlElement>,
 }
 
 impl RequestDownload {
@@ -17,8 +22,13 @@ impl RequestDownload {
         RequestDownload {
             file_type: file_type.into(),
             file_type_arg,
+            extra: vec![],
         }
     }
+    #[must_use]
+    pub fn file_type(&self) -> FileType {
+        FileType::from(self.file_type.0.as_ref())
+    }
 
     /// Generate XML for `RequestDownload`
     ///     
@@ -29,24 +39,30 @@ impl RequestDownload {
         &self,
         writer: &mut xml::EventWriter<W>,
         has_cwmp: bool,
+        options: &GenerateOptions,
     ) -> Result<(), GenerateError> {
         writer.write(XmlEvent::start_element(
-            &cwmp_prefix(has_cwmp, "RequestDownload")[..],
+            &cwmp_prefix(options, has_cwmp, "RequestDownload")[..],
         ))?;
         write_simple(writer, "FileType", self.file_type.0.as_ref())?;
-        let argss = format!("cwmp:ArgStruct[{}]", self.file_type_arg.len());
-        writer
-            .write(XmlEvent::start_element("FileTypeArg").attr("SOAP-ENC:arrayType", &argss[..]))?;
+        let argss = cwmp_array_type(options, has_cwmp, "ArgStruct", self.file_type_arg.len());
+        write_array_start(writer, options, "FileTypeArg", &argss)?;
 
         for a in &self.file_type_arg {
             writer.write(XmlEvent::start_element("ArgStruct"))?;
             write_simple(writer, "Name", a.name.0.as_ref())?;
             write_simple(writer, "Value", a.value.0.as_ref())?;
+            for e in &a.extra {
+                e.generate(writer)?;
+            }
             writer.write(XmlEvent::end_element())?;
         }
 
         // FileTypeArg
         writer.write(XmlEvent::end_element())?;
+        for e in &self.extra {
+            e.generate(writer)?;
+        }
         writer.write(XmlEvent::end_element())?;
         Ok(())
     }
@@ -87,6 +103,7 @@ impl Arbitrary for RequestDownload {
         Self {
             file_type: XmlSafeString::arbitrary(g),
             file_type_arg: Vec::<ArgStruct>::arbitrary(g),
+            extra: vec![],
         }
     }
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
@@ -96,6 +113,7 @@ impl Arbitrary for RequestDownload {
                 .map(|(ft, fta)| RequestDownload {
                     file_type: ft,
                     file_type_arg: fta,
+                    extra: vec![],
                 }),
         )
     }
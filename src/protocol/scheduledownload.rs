@@ -2,11 +2,16 @@ use std::io::Write;
 
 use xml::writer::XmlEvent;
 
-use super::{cwmp_prefix, parse_to_int, write_simple, GenerateError, TimeWindow, XmlSafeString};
+use super::{
+    check_length, check_mandatory, cwmp_array_type, cwmp_prefix, parse_to_int, write_array_start,
+    write_simple, FileType, GenerateError, GenerateOptions, TimeWindow, XmlElement, XmlSafeString,
+};
+use crate::error::Validation;
 #[cfg(test)]
 use quickcheck::{Arbitrary, Gen};
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ScheduleDownload {
     pub command_key: XmlSafeString,
     pub file_type: XmlSafeString,
@@ -16,6 +21,7 @@ pub struct ScheduleDownload {
     pub file_size: u32,
     pub target_filename: XmlSafeString,
     pub timewindow_list: Vec<TimeWindow>,
+    pub extra: Vec<XmlElement>,
 }
 
 impl ScheduleDownload {
@@ -40,8 +46,19 @@ impl ScheduleDownload {
             file_size,
             target_filename: target_filename.into(),
             timewindow_list,
+            extra: vec![],
         }
     }
+
+    /// A builder checking the fields against their TR-069 limits
+    #[must_use]
+    pub fn builder() -> ScheduleDownloadBuilder {
+        ScheduleDownloadBuilder::new()
+    }
+    #[must_use]
+    pub fn file_type(&self) -> FileType {
+        FileType::from(self.file_type.0.as_ref())
+    }
     pub fn start_handler(
         &mut self,
         path: &[&str],
@@ -63,9 +80,10 @@ impl ScheduleDownload {
         &self,
         writer: &mut xml::EventWriter<W>,
         has_cwmp: bool,
+        options: &GenerateOptions,
     ) -> Result<(), GenerateError> {
         writer.write(XmlEvent::start_element(
-            &cwmp_prefix(has_cwmp, "ScheduleDownload")[..],
+            &cwmp_prefix(options, has_cwmp, "ScheduleDownload")[..],
         ))?;
         write_simple(writer, "CommandKey", self.command_key.0.as_ref())?;
         write_simple(writer, "FileType", self.file_type.0.as_ref())?;
@@ -74,9 +92,13 @@ impl ScheduleDownload {
         write_simple(writer, "Password", self.password.0.as_ref())?;
         write_simple(writer, "FileSize", &self.file_size.to_string())?;
         write_simple(writer, "TargetFileName", self.target_filename.0.as_ref())?;
-        let ts = format!("cwmp:TimeWindowStruct[{}]", self.timewindow_list.len());
-        writer
-            .write(XmlEvent::start_element("TimeWindowList").attr("SOAP-ENC:arrayType", &ts[..]))?;
+        let ts = cwmp_array_type(
+            options,
+            has_cwmp,
+            "TimeWindowStruct",
+            self.timewindow_list.len(),
+        );
+        write_array_start(writer, options, "TimeWindowList", &ts)?;
 
         for t in &self.timewindow_list {
             writer.write(XmlEvent::start_element("TimeWindowStruct"))?;
@@ -85,11 +107,17 @@ impl ScheduleDownload {
             write_simple(writer, "WindowMode", t.window_mode.0.as_ref())?;
             write_simple(writer, "UserMessage", t.user_message.0.as_ref())?;
             write_simple(writer, "MaxRetries", &t.max_retries.to_string())?;
+            for e in &t.extra {
+                e.generate(writer)?;
+            }
             writer.write(XmlEvent::end_element())?;
         }
 
         // TimeWindownList
         writer.write(XmlEvent::end_element())?;
+        for e in &self.extra {
+            e.generate(writer)?;
+        }
         writer.write(XmlEvent::end_element())?;
         Ok(())
     }
@@ -134,6 +162,103 @@ impl ScheduleDownload {
     }
 }
 
+/// Builds a `ScheduleDownload`, checking its fields against TR-069 Table 46
+///
+/// The file type, URL and one or two time windows are mandatory, everything
+/// else defaults to empty or zero.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, PartialEq, Eq, Default, Clone)]
+pub struct ScheduleDownloadBuilder {
+    schedule_download: ScheduleDownload,
+}
+
+impl ScheduleDownloadBuilder {
+    #[must_use]
+    pub fn new() -> Self {
+        ScheduleDownloadBuilder::default()
+    }
+    #[must_use]
+    pub fn command_key(mut self, command_key: &str) -> Self {
+        self.schedule_download.command_key = command_key.into();
+        self
+    }
+    #[must_use]
+    pub fn file_type(mut self, file_type: FileType) -> Self {
+        self.schedule_download.file_type = file_type.to_string().as_str().into();
+        self
+    }
+    #[must_use]
+    pub fn url(mut self, url: &str) -> Self {
+        self.schedule_download.url = url.into();
+        self
+    }
+    #[must_use]
+    pub fn username(mut self, username: &str) -> Self {
+        self.schedule_download.username = username.into();
+        self
+    }
+    #[must_use]
+    pub fn password(mut self, password: &str) -> Self {
+        self.schedule_download.password = password.into();
+        self
+    }
+    #[must_use]
+    pub fn file_size(mut self, file_size: u32) -> Self {
+        self.schedule_download.file_size = file_size;
+        self
+    }
+    #[must_use]
+    pub fn target_filename(mut self, target_filename: &str) -> Self {
+        self.schedule_download.target_filename = target_filename.into();
+        self
+    }
+    /// Add a time window, a `ScheduleDownload` takes one or two
+    #[must_use]
+    pub fn time_window(mut self, time_window: TimeWindow) -> Self {
+        self.schedule_download.timewindow_list.push(time_window);
+        self
+    }
+
+    /// The `ScheduleDownload`, once its fields are checked
+    ///
+    /// # Errors
+    ///
+    /// Returns `error::Validation::Missing` without a file type or URL,
+    /// `error::Validation::Count` without one or two time windows, and
+    /// `error::Validation::TooLong` for a field over its size limit
+    pub fn build(self) -> Result<ScheduleDownload, Validation> {
+        let sd = self.schedule_download;
+        check_mandatory("FileType", &sd.file_type.0)?;
+        check_mandatory("URL", &sd.url.0)?;
+        if !(1..=2).contains(&sd.timewindow_list.len()) {
+            return Err(Validation::Count {
+                field: "TimeWindowList".to_string(),
+                count: sd.timewindow_list.len(),
+                min: 1,
+                max: 2,
+            });
+        }
+        check_length("CommandKey", &sd.command_key.0, 32)?;
+        check_length("FileType", &sd.file_type.0, 64)?;
+        if !sd.file_type().is_download() {
+            return Err(Validation::WrongFileType {
+                file_type: sd.file_type.0,
+                rpc: "ScheduleDownload".to_string(),
+            });
+        }
+        check_length("URL", &sd.url.0, 256)?;
+        check_length("Username", &sd.username.0, 256)?;
+        check_length("Password", &sd.password.0, 256)?;
+        check_length("TargetFileName", &sd.target_filename.0, 256)?;
+        for tw in &sd.timewindow_list {
+            check_mandatory("WindowMode", &tw.window_mode.0)?;
+            check_length("WindowMode", &tw.window_mode.0, 64)?;
+            check_length("UserMessage", &tw.user_message.0, 256)?;
+        }
+        Ok(sd)
+    }
+}
+
 #[cfg(test)]
 impl Arbitrary for ScheduleDownload {
     fn arbitrary(g: &mut Gen) -> Self {
@@ -146,6 +271,7 @@ impl Arbitrary for ScheduleDownload {
             file_size: u32::arbitrary(g),
             target_filename: XmlSafeString::arbitrary(g),
             timewindow_list: Vec::<TimeWindow>::arbitrary(g),
+            extra: vec![],
         }
     }
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
@@ -170,6 +296,7 @@ impl Arbitrary for ScheduleDownload {
                     file_size: fs,
                     target_filename: tf,
                     timewindow_list: tl,
+                    extra: vec![],
                 }),
         )
     }
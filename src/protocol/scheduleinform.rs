@@ -2,14 +2,19 @@ use std::io::Write;
 
 use xml::writer::XmlEvent;
 
-use super::{cwmp_prefix, parse_to_int, write_simple, GenerateError, XmlSafeString};
+use super::{
+    cwmp_prefix, parse_to_int, write_simple, GenerateError, GenerateOptions, XmlElement,
+    XmlSafeString,
+};
 #[cfg(test)]
 use quickcheck::{Arbitrary, Gen};
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ScheduleInform {
     pub delay_seconds: u32,
     pub command_key: XmlSafeString,
+    pub extra: Vec<XmlElement>,
 }
 
 impl ScheduleInform {
@@ -18,6 +23,7 @@ impl ScheduleInform {
         ScheduleInform {
             delay_seconds,
             command_key: command_key.into(),
+            extra: vec![],
         }
     }
 
@@ -30,12 +36,16 @@ impl ScheduleInform {
         &self,
         writer: &mut xml::EventWriter<W>,
         has_cwmp: bool,
+        options: &GenerateOptions,
     ) -> Result<(), GenerateError> {
         writer.write(XmlEvent::start_element(
-            &cwmp_prefix(has_cwmp, "ScheduleInform")[..],
+            &cwmp_prefix(options, has_cwmp, "ScheduleInform")[..],
         ))?;
         write_simple(writer, "DelaySeconds", &self.delay_seconds.to_string())?;
         write_simple(writer, "CommandKey", self.command_key.0.as_ref())?;
+        for e in &self.extra {
+            e.generate(writer)?;
+        }
         writer.write(XmlEvent::end_element())?;
         Ok(())
     }
@@ -58,6 +68,7 @@ impl Arbitrary for ScheduleInform {
         Self {
             delay_seconds: u32::arbitrary(g),
             command_key: XmlSafeString::arbitrary(g),
+            extra: vec![],
         }
     }
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
@@ -67,6 +78,7 @@ impl Arbitrary for ScheduleInform {
                 .map(|(d, c)| ScheduleInform {
                     delay_seconds: d,
                     command_key: c,
+                    extra: vec![],
                 }),
         )
     }
@@ -0,0 +1,299 @@
+// the elements the parsers of the body elements understand, by path below
+// the Body, starting with the name of the body element
+
+use Child::{Mandatory, Optional};
+
+// a child element, and whether its parent is complete without it. The
+// entries of arrays are optional, however many an array needs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Child {
+    Mandatory(&'static str),
+    Optional(&'static str),
+}
+
+impl Child {
+    pub(super) fn name(self) -> &'static str {
+        match self {
+            Mandatory(name) | Optional(name) => name,
+        }
+    }
+
+    pub(super) fn mandatory(self) -> Option<&'static str> {
+        match self {
+            Mandatory(name) => Some(name),
+            Optional(_) => None,
+        }
+    }
+}
+
+// the direct children of a body element, `None` if it is not a built-in one
+fn body_element_children(body_element: &str) -> Option<&'static [Child]> {
+    let children: &'static [Child] = match body_element {
+        "AddObject" | "DeleteObject" => &[Mandatory("ObjectName"), Mandatory("ParameterKey")],
+        "AddObjectResponse" => &[Mandatory("InstanceNumber"), Mandatory("Status")],
+        "AutonomousDUStateChangeComplete" => &[Mandatory("Results")],
+        "AutonomousTransferComplete" => &[
+            Mandatory("AnnounceURL"),
+            Mandatory("TransferURL"),
+            Mandatory("IsDownload"),
+            Mandatory("FileType"),
+            Mandatory("FileSize"),
+            Mandatory("TargetFileName"),
+            Mandatory("FaultStruct"),
+            Mandatory("StartTime"),
+            Mandatory("CompleteTime"),
+        ],
+        "CancelTransfer" | "Reboot" => &[Mandatory("CommandKey")],
+        "ChangeDUState" => &[Mandatory("CommandKey"), Mandatory("Operations")],
+        "DeleteObjectResponse" | "SetParameterValuesResponse" => &[Mandatory("Status")],
+        "Download" => &[
+            Mandatory("CommandKey"),
+            Mandatory("FileType"),
+            Mandatory("URL"),
+            Mandatory("Username"),
+            Mandatory("Password"),
+            Mandatory("FileSize"),
+            Mandatory("TargetFileName"),
+            Mandatory("DelaySeconds"),
+            Mandatory("SuccessURL"),
+            Mandatory("FailureURL"),
+        ],
+        "DownloadResponse" | "UploadResponse" => &[
+            Mandatory("Status"),
+            Mandatory("StartTime"),
+            Mandatory("CompleteTime"),
+        ],
+        "DUStateChangeComplete" => &[Mandatory("CommandKey"), Mandatory("Results")],
+        "Fault" => &[
+            Mandatory("faultcode"),
+            Mandatory("faultstring"),
+            Mandatory("detail"),
+        ],
+        "GetAllQueuedTransfersResponse" | "GetQueuedTransfersResponse" => {
+            &[Mandatory("TransferList")]
+        }
+        "GetOptions" => &[Mandatory("OptionName")],
+        "GetOptionsResponse" => &[Mandatory("OptionList")],
+        "GetParameterAttributes" | "GetParameterValues" => &[Mandatory("ParameterNames")],
+        "GetParameterAttributesResponse"
+        | "GetParameterNamesResponse"
+        | "GetParameterValuesResponse"
+        | "SetParameterAttributes" => &[Mandatory("ParameterList")],
+        "GetParameterNames" => &[Mandatory("ParameterPath"), Mandatory("NextLevel")],
+        "GetRPCMethodsResponse" => &[Mandatory("MethodList")],
+        "Inform" => &[
+            Mandatory("DeviceId"),
+            Mandatory("Event"),
+            Mandatory("MaxEnvelopes"),
+            Mandatory("CurrentTime"),
+            Mandatory("RetryCount"),
+            Mandatory("ParameterList"),
+        ],
+        "InformResponse" => &[Mandatory("MaxEnvelopes")],
+        "Kicked" => &[
+            Mandatory("Command"),
+            Mandatory("Referer"),
+            Mandatory("Arg"),
+            Mandatory("Next"),
+        ],
+        "KickedResponse" => &[Mandatory("NextURL")],
+        "RequestDownload" => &[Mandatory("FileType"), Mandatory("FileTypeArg")],
+        "ScheduleDownload" => &[
+            Mandatory("CommandKey"),
+            Mandatory("FileType"),
+            Mandatory("URL"),
+            Mandatory("Username"),
+            Mandatory("Password"),
+            Mandatory("FileSize"),
+            Mandatory("TargetFileName"),
+            Mandatory("TimeWindowList"),
+        ],
+        "ScheduleInform" => &[Mandatory("DelaySeconds"), Mandatory("CommandKey")],
+        "SetParameterValues" => &[Mandatory("ParameterList"), Mandatory("ParameterKey")],
+        "SetVouchers" => &[Mandatory("VoucherList")],
+        "TransferComplete" => &[
+            Mandatory("CommandKey"),
+            Mandatory("FaultStruct"),
+            Mandatory("StartTime"),
+            Mandatory("CompleteTime"),
+        ],
+        "Upload" => &[
+            Mandatory("CommandKey"),
+            Mandatory("FileType"),
+            Mandatory("URL"),
+            Mandatory("Username"),
+            Mandatory("Password"),
+            Mandatory("DelaySeconds"),
+        ],
+        "AutonomousDUStateChangeCompleteResponse"
+        | "AutonomousTransferCompleteResponse"
+        | "CancelTransferResponse"
+        | "ChangeDUStateResponse"
+        | "DUStateChangeCompleteResponse"
+        | "FactoryReset"
+        | "FactoryResetResponse"
+        | "GetAllQueuedTransfers"
+        | "GetQueuedTransfers"
+        | "GetRPCMethods"
+        | "RebootResponse"
+        | "RequestDownloadResponse"
+        | "ScheduleDownloadResponse"
+        | "ScheduleInformResponse"
+        | "SetParameterAttributesResponse"
+        | "SetVouchersResponse"
+        | "TransferCompleteResponse" => &[],
+        _ => return None,
+    };
+    Some(children)
+}
+
+// the direct children of an element below a body element, given that the
+// element itself is known; anything not listed here only carries text
+fn struct_children(path: &[&str]) -> &'static [Child] {
+    match path {
+        ["Inform", "DeviceId"] => &[
+            Mandatory("Manufacturer"),
+            Mandatory("OUI"),
+            Mandatory("ProductClass"),
+            Mandatory("SerialNumber"),
+        ],
+        ["Inform", "Event"] => &[Optional("EventStruct")],
+        ["Inform", "Event", "EventStruct"] => &[Mandatory("EventCode"), Mandatory("CommandKey")],
+        ["Inform" | "GetParameterValuesResponse" | "SetParameterValues", "ParameterList"] => {
+            &[Optional("ParameterValueStruct")]
+        }
+        [_, "ParameterList", "ParameterValueStruct"] => &[Mandatory("Name"), Mandatory("Value")],
+        ["GetParameterNamesResponse", "ParameterList"] => &[Optional("ParameterInfoStruct")],
+        [_, "ParameterList", "ParameterInfoStruct"] => &[Mandatory("Name"), Mandatory("Writable")],
+        ["GetParameterAttributesResponse", "ParameterList"] => {
+            &[Optional("ParameterAttributeStruct")]
+        }
+        [_, "ParameterList", "ParameterAttributeStruct"] => &[
+            Mandatory("Name"),
+            Mandatory("Notification"),
+            Mandatory("AccessList"),
+        ],
+        ["SetParameterAttributes", "ParameterList"] => &[Optional("SetParameterAttributesStruct")],
+        [_, "ParameterList", "SetParameterAttributesStruct"] => &[
+            Mandatory("Name"),
+            Mandatory("NotificationChange"),
+            Mandatory("Notification"),
+            Mandatory("AccessListChange"),
+            Mandatory("AccessList"),
+        ],
+        [_, "ParameterList", _, "AccessList"]
+        | ["GetParameterAttributes" | "GetParameterValues", "ParameterNames"]
+        | ["GetRPCMethodsResponse", "MethodList"] => &[Optional("string")],
+        ["SetVouchers", "VoucherList"] => &[Optional("base64")],
+        [.., "FaultStruct"] => &[Mandatory("FaultCode"), Mandatory("FaultString")],
+        ["GetQueuedTransfersResponse", "TransferList"] => &[Optional("QueuedTransferStruct")],
+        // optional in the model, which leaves them out when they are not set
+        [_, "TransferList", "QueuedTransferStruct"] => {
+            &[Optional("CommandKey"), Optional("State")]
+        }
+        ["GetAllQueuedTransfersResponse", "TransferList"] => &[Optional("AllQueuedTransferStruct")],
+        [_, "TransferList", "AllQueuedTransferStruct"] => &[
+            Mandatory("CommandKey"),
+            Mandatory("State"),
+            Mandatory("IsDownload"),
+            Mandatory("FileType"),
+            Mandatory("FileSize"),
+            Mandatory("TargetFileName"),
+        ],
+        ["GetOptionsResponse", "OptionList"] => &[Optional("OptionStruct")],
+        [_, "OptionList", "OptionStruct"] => &[
+            Mandatory("OptionName"),
+            Mandatory("VoucherSN"),
+            Mandatory("State"),
+            Mandatory("Mode"),
+            Optional("StartDate"),
+            Optional("ExpirationDate"),
+            Mandatory("IsTransferable"),
+        ],
+        ["ScheduleDownload", "TimeWindowList"] => &[Optional("TimeWindowStruct")],
+        [_, "TimeWindowList", "TimeWindowStruct"] => &[
+            Mandatory("WindowStart"),
+            Mandatory("WindowEnd"),
+            Mandatory("WindowMode"),
+            Mandatory("UserMessage"),
+            Mandatory("MaxRetries"),
+        ],
+        ["RequestDownload", "FileTypeArg"] => &[Optional("ArgStruct")],
+        [_, "FileTypeArg", "ArgStruct"] => &[Mandatory("Name"), Mandatory("Value")],
+        ["ChangeDUState", "Operations"] => &[
+            Optional("InstallOpStruct"),
+            Optional("UpdateOpStruct"),
+            Optional("UninstallOpStruct"),
+        ],
+        [_, "Operations", "InstallOpStruct"] => &[
+            Mandatory("URL"),
+            Mandatory("UUID"),
+            Mandatory("Username"),
+            Mandatory("Password"),
+            Mandatory("ExecutionEnvRef"),
+        ],
+        [_, "Operations", "UpdateOpStruct"] => &[
+            Mandatory("URL"),
+            Mandatory("UUID"),
+            Mandatory("Username"),
+            Mandatory("Password"),
+            Mandatory("Version"),
+        ],
+        [_, "Operations", "UninstallOpStruct"] => &[
+            Mandatory("URL"),
+            Mandatory("UUID"),
+            Mandatory("ExecutionEnvRef"),
+        ],
+        ["DUStateChangeComplete", "Results"] => &[Optional("OpResultStruct")],
+        [_, "Results", "OpResultStruct"] => &[
+            Mandatory("UUID"),
+            Mandatory("DeploymentUnitRef"),
+            Mandatory("Version"),
+            Mandatory("CurrentState"),
+            Mandatory("Resolved"),
+            Mandatory("ExecutionUnitRefList"),
+            Mandatory("StartTime"),
+            Mandatory("CompleteTime"),
+            Mandatory("Fault"),
+        ],
+        ["AutonomousDUStateChangeComplete", "Results"] => &[Optional("AutonOpResultStruct")],
+        [_, "Results", "AutonOpResultStruct"] => &[
+            Mandatory("UUID"),
+            Mandatory("DeploymentUnitRef"),
+            Mandatory("Version"),
+            Mandatory("CurrentState"),
+            Mandatory("Resolved"),
+            Mandatory("ExecutionUnitRefList"),
+            Mandatory("StartTime"),
+            Mandatory("CompleteTime"),
+            Mandatory("Fault"),
+            Mandatory("OperationPerformed"),
+        ],
+        [_, "Results", _, "Fault"] => &[Mandatory("FaultStruct")],
+        ["Fault", "detail"] => &[Mandatory("Fault")],
+        ["Fault", "detail", "Fault"] => &[
+            Mandatory("FaultCode"),
+            Mandatory("FaultString"),
+            Optional("SetParameterValuesFault"),
+        ],
+        ["Fault", "detail", "Fault", "SetParameterValuesFault"] => &[
+            Mandatory("ParameterName"),
+            Mandatory("FaultCode"),
+            Mandatory("FaultString"),
+        ],
+        _ => &[],
+    }
+}
+
+// the direct children the parsers understand for the element at `path`,
+// `None` if the element itself is not understood, like a vendor RPC or an
+// element unknown to its parent
+pub(super) fn known_children(path: &[&str]) -> Option<&'static [Child]> {
+    match path {
+        [] => None,
+        [body_element] => body_element_children(body_element),
+        [parent @ .., name] => known_children(parent)
+            .filter(|siblings| siblings.iter().any(|c| c.name() == *name))
+            .map(|_| struct_children(path)),
+    }
+}
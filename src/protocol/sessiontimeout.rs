@@ -4,9 +4,10 @@ use std::io::Write;
 use quickcheck::{Arbitrary, Gen};
 use xml::writer::XmlEvent;
 
-use super::{bool2str, cwmp_prefix, GenerateError};
+use super::{bool2str, cwmp_prefix, GenerateError, GenerateOptions};
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SessionTimeout {
     pub must_understand: bool,
     pub timeout: u32,
@@ -30,9 +31,10 @@ impl SessionTimeout {
         &self,
         writer: &mut xml::EventWriter<W>,
         has_cwmp: bool,
+        options: &GenerateOptions,
     ) -> Result<(), GenerateError> {
         writer.write(
-            XmlEvent::start_element(&cwmp_prefix(has_cwmp, "SessionTimeout")[..])
+            XmlEvent::start_element(&cwmp_prefix(options, has_cwmp, "SessionTimeout")[..])
                 .attr("mustUnderstand", bool2str(self.must_understand)),
         )?;
         writer.write(&self.timeout.to_string()[..])?;
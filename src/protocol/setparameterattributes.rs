@@ -3,19 +3,25 @@ use std::io::Write;
 use xml::writer::XmlEvent;
 
 use super::{
-    cwmp_prefix, parse_to_int, setparameterattributesstruct::SetParameterAttributesStruct,
-    write_simple, GenerateError, XmlSafeString,
+    cwmp_array_type, cwmp_prefix, parse_to_int,
+    setparameterattributesstruct::SetParameterAttributesStruct, write_array_start, write_simple,
+    GenerateError, GenerateOptions, XmlElement, XmlSafeString,
 };
 #[cfg(test)]
 use quickcheck::{Arbitrary, Gen};
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SetParameterAttributes {
     pub parameter_list: Vec<SetParameterAttributesStruct>,
+    pub extra: Vec<XmlElement>,
 }
 impl SetParameterAttributes {
     #[must_use]
     pub fn new(parameter_list: Vec<SetParameterAttributesStruct>) -> Self {
-        SetParameterAttributes { parameter_list }
+        SetParameterAttributes {
+            parameter_list,
+            extra: vec![],
+        }
     }
     pub fn start_handler(
         &mut self,
@@ -46,17 +52,19 @@ impl SetParameterAttributes {
         &self,
         writer: &mut xml::EventWriter<W>,
         has_cwmp: bool,
+        options: &GenerateOptions,
     ) -> Result<(), GenerateError> {
         writer.write(XmlEvent::start_element(
-            &cwmp_prefix(has_cwmp, "SetParameterAttributes")[..],
+            &cwmp_prefix(options, has_cwmp, "SetParameterAttributes")[..],
         ))?;
 
-        let pas = format!(
-            "cwmp:SetParameterAttributesStruct[{}]",
-            self.parameter_list.len()
+        let pas = cwmp_array_type(
+            options,
+            has_cwmp,
+            "SetParameterAttributesStruct",
+            self.parameter_list.len(),
         );
-        writer
-            .write(XmlEvent::start_element("ParameterList").attr("SOAP-ENC:arrayType", &pas[..]))?;
+        write_array_start(writer, options, "ParameterList", &pas)?;
 
         for p in &self.parameter_list {
             writer.write(XmlEvent::start_element("SetParameterAttributesStruct"))?;
@@ -77,11 +85,17 @@ impl SetParameterAttributes {
                 write_simple(writer, "string", al.0.as_ref())?;
             }
             writer.write(XmlEvent::end_element())?; // AccessList
+            for e in &p.extra {
+                e.generate(writer)?;
+            }
             writer.write(XmlEvent::end_element())?; // SetParameterAttributesStruct
         }
 
         // ParameterList
         writer.write(XmlEvent::end_element())?;
+        for e in &self.extra {
+            e.generate(writer)?;
+        }
         writer.write(XmlEvent::end_element())?;
         Ok(())
     }
@@ -121,7 +135,10 @@ impl Arbitrary for SetParameterAttributes {
             self.parameter_list
                 .clone()
                 .shrink()
-                .map(|pl| SetParameterAttributes { parameter_list: pl }),
+                .map(|pl| SetParameterAttributes {
+                    parameter_list: pl,
+                    extra: vec![],
+                }),
         )
     }
 }
@@ -1,15 +1,21 @@
 #[cfg(test)]
 use quickcheck::{Arbitrary, Gen};
 
-use super::{convert_to_xml_safe_strings, XmlSafeString};
+use super::{convert_to_xml_safe_strings, XmlElement, XmlSafeString};
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SetParameterAttributesStruct {
     pub name: XmlSafeString,
     pub notification_change: u8,
     pub notification: u8,
     pub access_list_change: u8,
     pub access_list: Vec<XmlSafeString>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub extra: Vec<XmlElement>,
 }
 
 impl SetParameterAttributesStruct {
@@ -27,6 +33,7 @@ impl SetParameterAttributesStruct {
             notification,
             access_list_change,
             access_list: convert_to_xml_safe_strings(access_list),
+            extra: vec![],
         }
     }
 }
@@ -40,6 +47,7 @@ impl Arbitrary for SetParameterAttributesStruct {
             notification: u8::arbitrary(g),
             access_list_change: u8::arbitrary(g),
             access_list: Vec::<XmlSafeString>::arbitrary(g),
+            extra: vec![],
         }
     }
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
@@ -58,6 +66,7 @@ impl Arbitrary for SetParameterAttributesStruct {
                     notification: n,
                     access_list_change: alc,
                     access_list: al,
+                    extra: vec![],
                 }),
         )
     }
@@ -3,15 +3,19 @@ use std::io::Write;
 use xml::writer::XmlEvent;
 
 use super::{
-    extract_attribute, write_empty_tag, write_simple, GenerateError, ParameterValue, XmlSafeString,
+    cwmp_array_type, cwmp_prefix, extract_attribute, write_array_start, write_empty_tag,
+    write_simple, GenerateError, GenerateOptions, ParamValue, ParameterValue, XmlElement,
+    XmlSafeString,
 };
 #[cfg(test)]
 use quickcheck::{Arbitrary, Gen};
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SetParameterValues {
     pub parameter_list: Vec<ParameterValue>,
     pub parameter_key: Option<XmlSafeString>,
+    pub extra: Vec<XmlElement>,
 }
 
 impl SetParameterValues {
@@ -20,9 +24,24 @@ impl SetParameterValues {
         Self {
             parameter_list: parameter_list.iter().copied().cloned().collect(),
             parameter_key: parameter_key.map(XmlSafeString::from),
+            extra: vec![],
         }
     }
 
+    /// The typed value of the parameter `name`, if it is in the list
+    #[must_use]
+    pub fn param_value(&self, name: &str) -> Option<ParamValue> {
+        self.parameter_list
+            .iter()
+            .find(|p| p.name.0 == name)
+            .map(ParameterValue::param_value)
+    }
+
+    /// Add a parameter with a typed value to the list
+    pub fn add_param_value(&mut self, name: &str, value: &ParamValue) {
+        self.parameter_list.push(ParameterValue::typed(name, value));
+    }
+
     /// Generate XML for `SetParameterValues`
     ///     
     /// # Errors
@@ -32,14 +51,17 @@ impl SetParameterValues {
         &self,
         writer: &mut xml::EventWriter<W>,
         has_cwmp: bool,
+        options: &GenerateOptions,
     ) -> Result<(), GenerateError> {
-        let pvs = if has_cwmp {
-            writer.write(XmlEvent::start_element("cwmp:SetParameterValues"))?;
-            format!("cwmp:ParameterValueStruct[{}]", self.parameter_list.len())
-        } else {
-            writer.write(XmlEvent::start_element("SetParameterValues"))?;
-            format!("ParameterValueStruct[{}]", self.parameter_list.len())
-        };
+        writer.write(XmlEvent::start_element(
+            &cwmp_prefix(options, has_cwmp, "SetParameterValues")[..],
+        ))?;
+        let pvs = cwmp_array_type(
+            options,
+            has_cwmp,
+            "ParameterValueStruct",
+            self.parameter_list.len(),
+        );
 
         if let Some(pk) = &self.parameter_key {
             write_simple(writer, "ParameterKey", pk.0.as_ref())?;
@@ -47,9 +69,7 @@ impl SetParameterValues {
         if self.parameter_list.is_empty() {
             write_empty_tag(writer, "ParameterList")?;
         } else {
-            writer.write(
-                XmlEvent::start_element("ParameterList").attr("SOAP-ENC:arrayType", &pvs[..]),
-            )?;
+            write_array_start(writer, options, "ParameterList", &pvs)?;
 
             for p in &self.parameter_list {
                 writer.write(XmlEvent::start_element("ParameterValueStruct"))?;
@@ -59,10 +79,16 @@ impl SetParameterValues {
                 )?;
                 writer.write(p.value.0.as_ref())?;
                 writer.write(XmlEvent::end_element())?; // Value
+                for e in &p.extra {
+                    e.generate(writer)?;
+                }
                 writer.write(XmlEvent::end_element())?;
             }
             writer.write(XmlEvent::end_element())?;
         }
+        for e in &self.extra {
+            e.generate(writer)?;
+        }
         writer.write(XmlEvent::end_element())?;
         Ok(())
     }
@@ -114,6 +140,7 @@ impl Arbitrary for SetParameterValues {
         Self {
             parameter_list: Vec::<ParameterValue>::arbitrary(g),
             parameter_key: Option::<XmlSafeString>::arbitrary(g),
+            extra: vec![],
         }
     }
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
@@ -123,6 +150,7 @@ impl Arbitrary for SetParameterValues {
                 .map(|(pl, pk)| SetParameterValues {
                     parameter_list: pl,
                     parameter_key: pk,
+                    extra: vec![],
                 }),
         )
     }
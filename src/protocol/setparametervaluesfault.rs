@@ -0,0 +1,62 @@
+#[cfg(test)]
+use quickcheck::{Arbitrary, Gen};
+
+use super::{FaultCode, XmlElement, XmlSafeString};
+
+/// Per parameter fault returned by the CPE when a `SetParameterValues` fails
+#[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetParameterValuesFault {
+    pub parameter_name: XmlSafeString,
+    pub fault_code: u32,
+    pub fault_string: XmlSafeString,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub extra: Vec<XmlElement>,
+}
+
+impl SetParameterValuesFault {
+    #[must_use]
+    pub fn new(parameter_name: &str, fault_code: u32, fault_string: &str) -> Self {
+        SetParameterValuesFault {
+            parameter_name: parameter_name.into(),
+            fault_code,
+            fault_string: fault_string.into(),
+            extra: vec![],
+        }
+    }
+    #[must_use]
+    pub fn code(&self) -> FaultCode {
+        FaultCode::from(self.fault_code)
+    }
+}
+
+#[cfg(test)]
+impl Arbitrary for SetParameterValuesFault {
+    fn arbitrary(g: &mut Gen) -> Self {
+        Self {
+            parameter_name: XmlSafeString::arbitrary(g),
+            fault_code: u32::arbitrary(g),
+            fault_string: XmlSafeString::arbitrary(g),
+            extra: vec![],
+        }
+    }
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        Box::new(
+            (
+                self.parameter_name.clone(),
+                self.fault_code,
+                self.fault_string.clone(),
+            )
+                .shrink()
+                .map(|(n, c, s)| SetParameterValuesFault {
+                    parameter_name: n,
+                    fault_code: c,
+                    fault_string: s,
+                    extra: vec![],
+                }),
+        )
+    }
+}
@@ -2,19 +2,24 @@ use std::io::Write;
 
 use xml::writer::XmlEvent;
 
-use super::{cwmp_prefix, parse_to_int, write_simple, GenerateError};
+use super::{cwmp_prefix, parse_to_int, write_simple, GenerateError, GenerateOptions, XmlElement};
 #[cfg(test)]
 use quickcheck::{Arbitrary, Gen};
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SetParameterValuesResponse {
     pub status: u32,
+    pub extra: Vec<XmlElement>,
 }
 
 impl SetParameterValuesResponse {
     #[must_use]
     pub fn new(status: u32) -> Self {
-        SetParameterValuesResponse { status }
+        SetParameterValuesResponse {
+            status,
+            extra: vec![],
+        }
     }
 
     /// Generate XML for `SetParameterValuesResponse`
@@ -26,11 +31,15 @@ impl SetParameterValuesResponse {
         &self,
         writer: &mut xml::EventWriter<W>,
         has_cwmp: bool,
+        options: &GenerateOptions,
     ) -> Result<(), GenerateError> {
         writer.write(XmlEvent::start_element(
-            &cwmp_prefix(has_cwmp, "SetParameterValuesResponse")[..],
+            &cwmp_prefix(options, has_cwmp, "SetParameterValuesResponse")[..],
         ))?;
         write_simple(writer, "Status", &self.status.to_string())?;
+        for e in &self.extra {
+            e.generate(writer)?;
+        }
         writer.write(XmlEvent::end_element())?;
         Ok(())
     }
@@ -51,7 +60,10 @@ impl Arbitrary for SetParameterValuesResponse {
             self.status
                 .clone()
                 .shrink()
-                .map(|s| SetParameterValuesResponse { status: s }),
+                .map(|s| SetParameterValuesResponse {
+                    status: s,
+                    extra: vec![],
+                }),
         )
     }
 }
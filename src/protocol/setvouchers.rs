@@ -2,13 +2,18 @@ use std::io::Write;
 
 use xml::writer::XmlEvent;
 
-use super::{convert_to_xml_safe_strings, cwmp_prefix, write_simple, GenerateError, XmlSafeString};
+use super::{
+    convert_to_xml_safe_strings, cwmp_prefix, write_array_start, write_simple, GenerateError,
+    GenerateOptions, XmlElement, XmlSafeString,
+};
 #[cfg(test)]
 use quickcheck::{Arbitrary, Gen};
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SetVouchers {
     pub voucher_list: Vec<XmlSafeString>,
+    pub extra: Vec<XmlElement>,
 }
 
 impl SetVouchers {
@@ -16,6 +21,7 @@ impl SetVouchers {
     pub fn new(voucher_list: &[&str]) -> Self {
         SetVouchers {
             voucher_list: convert_to_xml_safe_strings(voucher_list),
+            extra: vec![],
         }
     }
 
@@ -28,19 +34,22 @@ impl SetVouchers {
         &self,
         writer: &mut xml::EventWriter<W>,
         has_cwmp: bool,
+        options: &GenerateOptions,
     ) -> Result<(), GenerateError> {
         writer.write(XmlEvent::start_element(
-            &cwmp_prefix(has_cwmp, "SetVouchers")[..],
+            &cwmp_prefix(options, has_cwmp, "SetVouchers")[..],
         ))?;
 
         let vls = format!("base64[{}]", self.voucher_list.len());
-        writer
-            .write(XmlEvent::start_element("VoucherList").attr("SOAP-ENC:arrayType", &vls[..]))?;
+        write_array_start(writer, options, "VoucherList", &vls)?;
 
         for v in &self.voucher_list {
             write_simple(writer, "base64", v.0.as_ref())?;
         }
         writer.write(XmlEvent::end_element())?; // VoucherList
+        for e in &self.extra {
+            e.generate(writer)?;
+        }
         writer.write(XmlEvent::end_element())?;
         Ok(())
     }
@@ -69,14 +78,13 @@ impl Arbitrary for SetVouchers {
     fn arbitrary(g: &mut Gen) -> Self {
         Self {
             voucher_list: Vec::<XmlSafeString>::arbitrary(g),
+            extra: vec![],
         }
     }
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
-        Box::new(
-            self.voucher_list
-                .clone()
-                .shrink()
-                .map(|vl| SetVouchers { voucher_list: vl }),
-        )
+        Box::new(self.voucher_list.clone().shrink().map(|vl| SetVouchers {
+            voucher_list: vl,
+            extra: vec![],
+        }))
     }
 }
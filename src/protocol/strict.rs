@@ -0,0 +1,239 @@
+use chrono::{DateTime, Utc};
+
+use super::schema::{self, Child};
+use super::{State, StructuralError, StructuralErrorKind};
+
+// the kind of text content an element is expected to carry, as far
+// as the parser of the owning struct is concerned
+#[derive(Debug, Clone, Copy)]
+enum TextKind {
+    U8,
+    U16,
+    U32,
+    I32,
+    DateTime,
+}
+
+impl TextKind {
+    fn check(self, characters: &str) -> Option<StructuralErrorKind> {
+        let valid = match self {
+            TextKind::U8 => characters.parse::<u8>().is_ok(),
+            TextKind::U16 => characters.parse::<u16>().is_ok(),
+            TextKind::U32 => characters.parse::<u32>().is_ok(),
+            TextKind::I32 => characters.parse::<i32>().is_ok(),
+            TextKind::DateTime => characters.parse::<DateTime<Utc>>().is_ok(),
+        };
+        match (valid, self) {
+            (true, _) => None,
+            (false, TextKind::DateTime) => {
+                Some(StructuralErrorKind::InvalidDateTime(characters.to_string()))
+            }
+            (false, _) => Some(StructuralErrorKind::InvalidInteger(characters.to_string())),
+        }
+    }
+}
+
+fn text_kind(path: &[&str]) -> Option<TextKind> {
+    match path {
+        ["Envelope", "Header", "NoMoreRequests"]
+        | ["Envelope", "Body", "AutonomousTransferComplete", "IsDownload"]
+        | ["Envelope", "Body", "GetAllQueuedTransfersResponse", "TransferList", "AllQueuedTransferStruct", "IsDownload"]
+        | ["Envelope", "Body", "GetOptionsResponse", "OptionList", "OptionStruct", "State" | "IsTransferable"]
+        | ["Envelope", "Body", "GetParameterNamesResponse", "ParameterList", "ParameterInfoStruct", "Writable"]
+        | ["Envelope", "Body", "SetParameterAttributes", "ParameterList", "SetParameterAttributesStruct", "NotificationChange" | "Notification" | "AccessListChange"]
+        | ["Envelope", "Body", "UploadResponse", "Status"] => Some(TextKind::U8),
+        ["Envelope", "Body", "InformResponse", "MaxEnvelopes"] => Some(TextKind::U16),
+        ["Envelope", "Header", "SessionTimeout"]
+        | ["Envelope", "Body", "AddObjectResponse", "InstanceNumber"]
+        | ["Envelope", "Body", "AutonomousTransferComplete", "FileSize"]
+        | ["Envelope", "Body", "AutonomousTransferComplete" | "TransferComplete", "FaultStruct", "FaultCode"]
+        | ["Envelope", "Body", "AutonomousDUStateChangeComplete", "Results", "AutonOpResultStruct", "Fault", "FaultStruct", "FaultCode"]
+        | ["Envelope", "Body", "Download", "FileSize" | "DelaySeconds"]
+        | ["Envelope", "Body", "DUStateChangeComplete", "Results", "OpResultStruct", "Resolved"]
+        | ["Envelope", "Body", "DUStateChangeComplete", "Results", "OpResultStruct", "Fault", "FaultStruct", "FaultCode"]
+        | ["Envelope", "Body", "Fault", "detail", "Fault", "FaultCode"]
+        | ["Envelope", "Body", "Fault", "detail", "Fault", "SetParameterValuesFault", "FaultCode"]
+        | ["Envelope", "Body", "GetAllQueuedTransfersResponse", "TransferList", "AllQueuedTransferStruct", "FileSize"]
+        | ["Envelope", "Body", "GetParameterNames", "NextLevel"]
+        | ["Envelope", "Body", "Inform", "MaxEnvelopes" | "RetryCount"]
+        | ["Envelope", "Body", "ScheduleDownload", "FileSize"]
+        | ["Envelope", "Body", "ScheduleDownload", "TimeWindowList", "TimeWindowStruct", "WindowStart" | "WindowEnd"]
+        | ["Envelope", "Body", "ScheduleInform", "DelaySeconds"]
+        | ["Envelope", "Body", "SetParameterValuesResponse", "Status"]
+        | ["Envelope", "Body", "Upload", "DelaySeconds"] => Some(TextKind::U32),
+        ["Envelope", "Body", "ScheduleDownload", "TimeWindowList", "TimeWindowStruct", "MaxRetries"] => {
+            Some(TextKind::I32)
+        }
+        ["Envelope", "Body", "AutonomousDUStateChangeComplete", "Results", "AutonOpResultStruct", "StartTime" | "CompleteTime"]
+        | ["Envelope", "Body", "AutonomousTransferComplete"
+        | "DownloadResponse"
+        | "TransferComplete"
+        | "UploadResponse", "StartTime" | "CompleteTime"]
+        | ["Envelope", "Body", "DUStateChangeComplete", "Results", "OpResultStruct", "StartTime" | "CompleteTime"]
+        | ["Envelope", "Body", "GetOptionsResponse", "OptionList", "OptionStruct", "StartDate" | "ExpirationDate"]
+        | ["Envelope", "Body", "Inform", "CurrentTime"] => Some(TextKind::DateTime),
+        _ => None,
+    }
+}
+
+pub(super) fn is_header_element(name: &str) -> bool {
+    matches!(
+        name,
+        "ID" | "HoldRequests"
+            | "NoMoreRequests"
+            | "SessionTimeout"
+            | "SupportedCWMPVersions"
+            | "UseCWMPVersion"
+    )
+}
+
+fn is_body_element(name: &str) -> bool {
+    matches!(
+        name,
+        "AddObject"
+            | "AddObjectResponse"
+            | "AutonomousDUStateChangeComplete"
+            | "AutonomousDUStateChangeCompleteResponse"
+            | "AutonomousTransferComplete"
+            | "AutonomousTransferCompleteResponse"
+            | "CancelTransfer"
+            | "CancelTransferResponse"
+            | "ChangeDUState"
+            | "ChangeDUStateResponse"
+            | "DeleteObject"
+            | "DeleteObjectResponse"
+            | "Download"
+            | "DownloadResponse"
+            | "DUStateChangeComplete"
+            | "DUStateChangeCompleteResponse"
+            | "FactoryReset"
+            | "FactoryResetResponse"
+            | "Fault"
+            | "GetAllQueuedTransfers"
+            | "GetAllQueuedTransfersResponse"
+            | "GetOptions"
+            | "GetOptionsResponse"
+            | "GetParameterAttributes"
+            | "GetParameterAttributesResponse"
+            | "GetParameterNames"
+            | "GetParameterNamesResponse"
+            | "GetParameterValues"
+            | "GetParameterValuesResponse"
+            | "GetQueuedTransfers"
+            | "GetQueuedTransfersResponse"
+            | "GetRPCMethods"
+            | "GetRPCMethodsResponse"
+            | "Inform"
+            | "InformResponse"
+            | "Kicked"
+            | "KickedResponse"
+            | "Reboot"
+            | "RebootResponse"
+            | "RequestDownload"
+            | "RequestDownloadResponse"
+            | "ScheduleDownload"
+            | "ScheduleDownloadResponse"
+            | "ScheduleInform"
+            | "ScheduleInformResponse"
+            | "SetParameterAttributes"
+            | "SetParameterAttributesResponse"
+            | "SetParameterValues"
+            | "SetParameterValuesResponse"
+            | "SetVouchers"
+            | "SetVouchersResponse"
+            | "TransferComplete"
+            | "TransferCompleteResponse"
+            | "Upload"
+            | "UploadResponse"
+    )
+}
+
+// the direct children that must be present for an element to be complete
+fn mandatory_children(path: &[&str]) -> impl Iterator<Item = &'static str> {
+    let children: &[Child] = match path {
+        ["Envelope"] => &[Child::Mandatory("Body")],
+        ["Envelope", "Body", below @ ..] => schema::known_children(below).unwrap_or_default(),
+        _ => &[],
+    };
+    children.iter().filter_map(|c| c.mandatory())
+}
+
+impl State {
+    pub(super) fn fail(&mut self, kind: StructuralErrorKind) {
+        // only the first problem is reported
+        if self.error.is_none() {
+            self.error = Some(StructuralError::new(
+                &self.path,
+                self.position.row + 1,
+                self.position.column + 1,
+                kind,
+            ));
+        }
+    }
+
+    // called after the element name has been pushed onto the path
+    pub(super) fn strict_start(&mut self, name: &xml::name::OwnedName) {
+        let known = {
+            let path_pattern: Vec<&str> = self.path.iter().map(AsRef::as_ref).collect();
+            match &path_pattern[..] {
+                ["Envelope"] | ["Envelope", "Header" | "Body"] => true,
+                ["Envelope", "Header", header_element] => is_header_element(header_element),
+                ["Envelope", "Body", body_element] => {
+                    is_body_element(body_element) || self.options.vendor_rpcs.create(name).is_some()
+                }
+                // below a body element, checked against the children its parser understands;
+                // the content of vendor RPCs and of elements already reported is not
+                ["Envelope", "Body", below @ .., name] => schema::known_children(below)
+                    .is_none_or(|children| children.iter().any(|c| c.name() == *name)),
+                _ => false,
+            }
+        };
+        if !known {
+            self.fail(StructuralErrorKind::UnknownElement);
+        }
+
+        if let (Some(name), Some(siblings)) = (self.path.last(), self.children.last_mut()) {
+            siblings.push(name.clone());
+        }
+        self.children.push(vec![]);
+        self.last_text.clear();
+    }
+
+    // called before the element name is popped from the path
+    pub(super) fn strict_end(&mut self) {
+        let seen = self.children.pop().unwrap_or_default();
+        let problem = {
+            let path_pattern: Vec<&str> = self.path.iter().map(AsRef::as_ref).collect();
+            // an empty element never produces a characters event, so check it here
+            let empty_text = if self.last_text.is_empty() {
+                text_kind(&path_pattern).and_then(|k| k.check(""))
+            } else {
+                None
+            };
+            empty_text.or_else(|| {
+                mandatory_children(&path_pattern)
+                    .find(|&m| !seen.iter().any(|s| s == m))
+                    .map(|m| StructuralErrorKind::MissingElement(m.to_string()))
+            })
+        };
+        if let Some(kind) = problem {
+            self.fail(kind);
+        }
+    }
+
+    // the text to hand to the parsers, integers and dateTimes without the
+    // whitespace xsd allows around them
+    pub(super) fn strict_characters<'a>(&mut self, characters: &'a str) -> &'a str {
+        let kind = {
+            let path_pattern: Vec<&str> = self.path.iter().map(AsRef::as_ref).collect();
+            text_kind(&path_pattern)
+        };
+        let Some(kind) = kind else {
+            return characters;
+        };
+        if let Some(problem) = kind.check(characters.trim()) {
+            self.fail(problem);
+        }
+        characters.trim()
+    }
+}
@@ -0,0 +1,79 @@
+use std::fmt;
+
+use super::CwmpVersion;
+
+/// The reason a document was rejected by a strict parse
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StructuralErrorKind {
+    /// An element that is not part of the CWMP envelope structure
+    UnknownElement,
+    /// A mandatory child element was not present
+    MissingElement(String),
+    /// Text that should have been an integer, but could not be parsed as one
+    InvalidInteger(String),
+    /// Text that should have been an `xsd:dateTime`, but could not be parsed as one
+    InvalidDateTime(String),
+    /// A body element needing a newer CWMP version than the envelope namespace
+    Incompatible {
+        required: CwmpVersion,
+        version: CwmpVersion,
+    },
+}
+
+impl fmt::Display for StructuralErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StructuralErrorKind::UnknownElement => write!(f, "unknown element"),
+            StructuralErrorKind::MissingElement(name) => {
+                write!(f, "missing mandatory element {name}")
+            }
+            StructuralErrorKind::InvalidInteger(text) => {
+                write!(f, "\"{text}\" is not a valid integer")
+            }
+            StructuralErrorKind::InvalidDateTime(text) => {
+                write!(f, "\"{text}\" is not a valid dateTime")
+            }
+            StructuralErrorKind::Incompatible { required, version } => {
+                write!(f, "requires CWMP {required}, the envelope is {version}")
+            }
+        }
+    }
+}
+
+/// A structural problem found while parsing in strict mode, along with
+/// the element path and the position in the document where it was found.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StructuralError {
+    pub path: String,
+    pub line: u64,
+    pub column: u64,
+    pub kind: StructuralErrorKind,
+}
+
+impl StructuralError {
+    #[must_use]
+    pub fn new(path: &[String], line: u64, column: u64, kind: StructuralErrorKind) -> Self {
+        StructuralError {
+            path: format!("/{}", path.join("/")),
+            line,
+            column,
+            kind,
+        }
+    }
+}
+
+impl fmt::Display for StructuralError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} at {} (line {}, column {})",
+            self.kind, self.path, self.line, self.column
+        )
+    }
+}
+
+impl std::error::Error for StructuralError {}
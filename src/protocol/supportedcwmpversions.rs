@@ -4,9 +4,10 @@ use std::io::Write;
 use quickcheck::{Arbitrary, Gen};
 use xml::writer::XmlEvent;
 
-use super::{bool2str, cwmp_prefix, GenerateError, XmlSafeString};
+use super::{bool2str, cwmp_prefix, CwmpVersion, GenerateError, GenerateOptions, XmlSafeString};
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SupportedCWMPVersions {
     pub must_understand: bool,
     pub value: XmlSafeString,
@@ -21,6 +22,19 @@ impl SupportedCWMPVersions {
         }
     }
 
+    /// The header listing `versions`
+    #[must_use]
+    pub fn from_versions(must_understand: bool, versions: &[CwmpVersion]) -> Self {
+        let value: Vec<String> = versions.iter().map(ToString::to_string).collect();
+        SupportedCWMPVersions::new(must_understand, &value.join(","))
+    }
+
+    /// The versions listed, skipping the ones that cannot be parsed
+    #[must_use]
+    pub fn versions(&self) -> Vec<CwmpVersion> {
+        CwmpVersion::parse_list(&self.value.0)
+    }
+
     /// Generate XML for `TransferComplete`
     ///     
     /// # Errors
@@ -30,9 +44,10 @@ impl SupportedCWMPVersions {
         &self,
         writer: &mut xml::EventWriter<W>,
         has_cwmp: bool,
+        options: &GenerateOptions,
     ) -> Result<(), GenerateError> {
         writer.write(
-            XmlEvent::start_element(&cwmp_prefix(has_cwmp, "SupportedCWMPVersions")[..])
+            XmlEvent::start_element(&cwmp_prefix(options, has_cwmp, "SupportedCWMPVersions")[..])
                 .attr("mustUnderstand", bool2str(self.must_understand)),
         )?;
         writer.write(self.value.0.as_ref())?;
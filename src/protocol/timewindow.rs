@@ -1,15 +1,21 @@
 #[cfg(test)]
 use quickcheck::{Arbitrary, Gen};
 
-use super::XmlSafeString;
+use super::{XmlElement, XmlSafeString};
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TimeWindow {
     pub window_start: u32,
     pub window_end: u32,
     pub window_mode: XmlSafeString,
     pub user_message: XmlSafeString,
     pub max_retries: i32,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub extra: Vec<XmlElement>,
 }
 impl TimeWindow {
     #[must_use]
@@ -26,6 +32,7 @@ impl TimeWindow {
             window_mode: window_mode.into(),
             user_message: user_message.into(),
             max_retries,
+            extra: vec![],
         }
     }
 }
@@ -39,6 +46,7 @@ impl Arbitrary for TimeWindow {
             window_mode: XmlSafeString::arbitrary(g),
             user_message: XmlSafeString::arbitrary(g),
             max_retries: i32::arbitrary(g),
+            extra: vec![],
         }
     }
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
@@ -57,6 +65,7 @@ impl Arbitrary for TimeWindow {
                     window_mode: wm,
                     user_message: um,
                     max_retries: mr,
+                    extra: vec![],
                 }),
         )
     }
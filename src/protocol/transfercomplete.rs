@@ -7,17 +7,19 @@ use xml::writer::XmlEvent;
 use super::gen_utc_date;
 use super::{
     cwmp_prefix, parse_to_int, write_fault_struct, write_simple, FaultStruct, GenerateError,
-    XmlSafeString,
+    GenerateOptions, XmlElement, XmlSafeString,
 };
 #[cfg(test)]
 use quickcheck::{Arbitrary, Gen};
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TransferComplete {
     pub command_key: XmlSafeString,
     pub fault: FaultStruct,
     pub start_time: Option<DateTime<Utc>>,
     pub complete_time: Option<DateTime<Utc>>,
+    pub extra: Vec<XmlElement>,
 }
 
 impl TransferComplete {
@@ -33,6 +35,7 @@ impl TransferComplete {
             fault,
             start_time,
             complete_time,
+            extra: vec![],
         }
     }
 
@@ -45,9 +48,10 @@ impl TransferComplete {
         &self,
         writer: &mut xml::EventWriter<W>,
         has_cwmp: bool,
+        options: &GenerateOptions,
     ) -> Result<(), GenerateError> {
         writer.write(XmlEvent::start_element(
-            &cwmp_prefix(has_cwmp, "TransferComplete")[..],
+            &cwmp_prefix(options, has_cwmp, "TransferComplete")[..],
         ))?;
         write_simple(writer, "CommandKey", self.command_key.0.as_ref())?;
         write_fault_struct(writer, &self.fault)?;
@@ -58,6 +62,9 @@ impl TransferComplete {
             write_simple(writer, "CompleteTime", &dt.to_rfc3339())?;
         }
 
+        for e in &self.extra {
+            e.generate(writer)?;
+        }
         writer.write(XmlEvent::end_element())?;
         Ok(())
     }
@@ -65,12 +72,12 @@ impl TransferComplete {
         match *path {
             ["TransferComplete", "CommandKey"] => self.command_key = characters.into(),
             ["TransferComplete", "StartTime"] => {
-                if let Ok(dt) = characters.parse::<DateTime<Utc>>() {
+                if let Ok(dt) = characters.trim().parse::<DateTime<Utc>>() {
                     self.start_time = Some(dt);
                 }
             }
             ["TransferComplete", "CompleteTime"] => {
-                if let Ok(dt) = characters.parse::<DateTime<Utc>>() {
+                if let Ok(dt) = characters.trim().parse::<DateTime<Utc>>() {
                     self.complete_time = Some(dt);
                 }
             }
@@ -92,6 +99,7 @@ impl Arbitrary for TransferComplete {
             fault: FaultStruct::arbitrary(g),
             start_time: Some(gen_utc_date(2014, 11, 28, 12, 0, 9)),
             complete_time: Some(gen_utc_date(2014, 11, 29, 12, 0, 9)),
+            extra: vec![],
         }
     }
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
@@ -103,6 +111,7 @@ impl Arbitrary for TransferComplete {
                     fault: f,
                     start_time: Some(gen_utc_date(2014, 11, 28, 12, 0, 9)),
                     complete_time: Some(gen_utc_date(2014, 11, 29, 12, 0, 9)),
+                    extra: vec![],
                 }),
         )
     }
@@ -1,13 +1,19 @@
 #[cfg(test)]
 use quickcheck::{Arbitrary, Gen};
 
-use super::XmlSafeString;
+use super::{XmlElement, XmlSafeString};
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UninstallOp {
     pub url: XmlSafeString,
     pub uuid: XmlSafeString,
     pub execution_env_ref: XmlSafeString,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub extra: Vec<XmlElement>,
 }
 
 impl UninstallOp {
@@ -17,6 +23,7 @@ impl UninstallOp {
             url: url.into(),
             uuid: uuid.into(),
             execution_env_ref: execution_env_ref.into(),
+            extra: vec![],
         }
     }
 }
@@ -28,6 +35,7 @@ impl Arbitrary for UninstallOp {
             url: XmlSafeString::arbitrary(g),
             uuid: XmlSafeString::arbitrary(g),
             execution_env_ref: XmlSafeString::arbitrary(g),
+            extra: vec![],
         }
     }
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
@@ -42,6 +50,7 @@ impl Arbitrary for UninstallOp {
                     url: u,
                     uuid: uu,
                     execution_env_ref: eer,
+                    extra: vec![],
                 }),
         )
     }
@@ -0,0 +1,46 @@
+use super::{XmlAttribute, XmlElement, XmlName};
+
+/// A header element this crate does not model, kept as it was received so
+/// it is generated again, and so that `Envelope::validate` can tell whether
+/// it had to be understood
+#[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UnknownHeader {
+    pub name: String,
+    pub must_understand: bool,
+    pub element: XmlElement,
+}
+
+impl UnknownHeader {
+    /// An empty header element, with a `mustUnderstand` attribute if it must be understood
+    #[must_use]
+    pub fn new(name: &str, must_understand: bool) -> Self {
+        let mut element = XmlElement::new(XmlName::new(name));
+        if must_understand {
+            element.attributes.push(XmlAttribute {
+                name: XmlName {
+                    local_name: "mustUnderstand".to_string(),
+                    namespace: Some("http://schemas.xmlsoap.org/soap/envelope/".to_string()),
+                    prefix: Some("SOAP-ENV".to_string()),
+                },
+                value: "1".to_string(),
+            });
+        }
+        UnknownHeader::from_element(element)
+    }
+
+    /// The header for an element received as is
+    #[must_use]
+    pub fn from_element(element: XmlElement) -> Self {
+        // unlike the known headers, unknown ones are optional unless told otherwise
+        let must_understand = element
+            .attributes
+            .iter()
+            .any(|a| a.name.local_name == "mustUnderstand" && super::str2bool(&a.value));
+        UnknownHeader {
+            name: element.name.local_name.clone(),
+            must_understand,
+            element,
+        }
+    }
+}
@@ -1,15 +1,21 @@
 #[cfg(test)]
 use quickcheck::{Arbitrary, Gen};
 
-use super::XmlSafeString;
+use super::{XmlElement, XmlSafeString};
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UpdateOp {
     pub url: XmlSafeString,
     pub uuid: XmlSafeString,
     pub username: XmlSafeString,
     pub password: XmlSafeString,
     pub version: XmlSafeString,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub extra: Vec<XmlElement>,
 }
 
 impl UpdateOp {
@@ -21,6 +27,7 @@ impl UpdateOp {
             username: username.into(),
             password: password.into(),
             version: version.into(),
+            extra: vec![],
         }
     }
 }
@@ -34,6 +41,7 @@ impl Arbitrary for UpdateOp {
             username: XmlSafeString::arbitrary(g),
             password: XmlSafeString::arbitrary(g),
             version: XmlSafeString::arbitrary(g),
+            extra: vec![],
         }
     }
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
@@ -52,6 +60,7 @@ impl Arbitrary for UpdateOp {
                     username: un,
                     password: pw,
                     version: v,
+                    extra: vec![],
                 }),
         )
     }
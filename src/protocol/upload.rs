@@ -4,9 +4,14 @@ use std::io::Write;
 use quickcheck::{Arbitrary, Gen};
 use xml::writer::XmlEvent;
 
-use super::{cwmp_prefix, parse_to_int, GenerateError, XmlSafeString};
+use super::{
+    check_length, check_mandatory, cwmp_prefix, parse_to_int, FileType, GenerateError,
+    GenerateOptions, XmlElement, XmlSafeString,
+};
+use crate::error::Validation;
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Upload {
     pub command_key: XmlSafeString,
     pub file_type: XmlSafeString,
@@ -14,6 +19,7 @@ pub struct Upload {
     pub username: XmlSafeString,
     pub password: XmlSafeString,
     pub delay_seconds: u32,
+    pub extra: Vec<XmlElement>,
 }
 
 impl Upload {
@@ -33,9 +39,20 @@ impl Upload {
             username: username.into(),
             password: password.into(),
             delay_seconds,
+            extra: vec![],
         }
     }
 
+    /// A builder checking the fields against their TR-069 limits
+    #[must_use]
+    pub fn builder() -> UploadBuilder {
+        UploadBuilder::new()
+    }
+    #[must_use]
+    pub fn file_type(&self) -> FileType {
+        FileType::from(self.file_type.0.as_ref())
+    }
+
     /// Generate XML for `Upload`
     ///     
     /// # Errors
@@ -45,9 +62,10 @@ impl Upload {
         &self,
         writer: &mut xml::EventWriter<W>,
         has_cwmp: bool,
+        options: &GenerateOptions,
     ) -> Result<(), GenerateError> {
         writer.write(XmlEvent::start_element(
-            &cwmp_prefix(has_cwmp, "Upload")[..],
+            &cwmp_prefix(options, has_cwmp, "Upload")[..],
         ))?;
         writer.write(XmlEvent::start_element("CommandKey"))?;
         writer.write(self.command_key.0.as_ref())?;
@@ -67,6 +85,9 @@ impl Upload {
         writer.write(XmlEvent::start_element("DelaySeconds"))?;
         let s: String = self.delay_seconds.to_string();
         writer.write(&s[..])?;
+        for e in &self.extra {
+            e.generate(writer)?;
+        }
         writer.write(XmlEvent::end_element())?;
 
         let e: XmlEvent = XmlEvent::end_element().into();
@@ -86,6 +107,77 @@ impl Upload {
     }
 }
 
+/// Builds an `Upload`, checking its fields against TR-069 Table 32
+///
+/// The file type and URL are mandatory, everything else defaults to empty
+/// or zero.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, PartialEq, Eq, Default, Clone)]
+pub struct UploadBuilder {
+    upload: Upload,
+}
+
+impl UploadBuilder {
+    #[must_use]
+    pub fn new() -> Self {
+        UploadBuilder::default()
+    }
+    #[must_use]
+    pub fn command_key(mut self, command_key: &str) -> Self {
+        self.upload.command_key = command_key.into();
+        self
+    }
+    #[must_use]
+    pub fn file_type(mut self, file_type: FileType) -> Self {
+        self.upload.file_type = file_type.to_string().as_str().into();
+        self
+    }
+    #[must_use]
+    pub fn url(mut self, url: &str) -> Self {
+        self.upload.url = url.into();
+        self
+    }
+    #[must_use]
+    pub fn username(mut self, username: &str) -> Self {
+        self.upload.username = username.into();
+        self
+    }
+    #[must_use]
+    pub fn password(mut self, password: &str) -> Self {
+        self.upload.password = password.into();
+        self
+    }
+    #[must_use]
+    pub fn delay_seconds(mut self, delay_seconds: u32) -> Self {
+        self.upload.delay_seconds = delay_seconds;
+        self
+    }
+
+    /// The `Upload`, once its fields are checked
+    ///
+    /// # Errors
+    ///
+    /// Returns `error::Validation::Missing` without a file type or URL, and
+    /// `error::Validation::TooLong` for a field over its size limit
+    pub fn build(self) -> Result<Upload, Validation> {
+        let u = self.upload;
+        check_mandatory("FileType", &u.file_type.0)?;
+        check_mandatory("URL", &u.url.0)?;
+        check_length("CommandKey", &u.command_key.0, 32)?;
+        check_length("FileType", &u.file_type.0, 64)?;
+        if !u.file_type().is_upload() {
+            return Err(Validation::WrongFileType {
+                file_type: u.file_type.0,
+                rpc: "Upload".to_string(),
+            });
+        }
+        check_length("URL", &u.url.0, 256)?;
+        check_length("Username", &u.username.0, 256)?;
+        check_length("Password", &u.password.0, 256)?;
+        Ok(u)
+    }
+}
+
 #[cfg(test)]
 impl Arbitrary for Upload {
     fn arbitrary(g: &mut Gen) -> Self {
@@ -96,6 +188,7 @@ impl Arbitrary for Upload {
             username: XmlSafeString::arbitrary(g),
             password: XmlSafeString::arbitrary(g),
             delay_seconds: u32::arbitrary(g),
+            extra: vec![],
         }
     }
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
@@ -116,6 +209,7 @@ impl Arbitrary for Upload {
                     username: un,
                     password: pw,
                     delay_seconds: ds,
+                    extra: vec![],
                 }),
         )
     }
@@ -5,15 +5,17 @@ use xml::writer::XmlEvent;
 
 #[cfg(test)]
 use super::gen_utc_date;
-use super::{cwmp_prefix, parse_to_int, write_simple, GenerateError};
+use super::{cwmp_prefix, parse_to_int, write_simple, GenerateError, GenerateOptions, XmlElement};
 #[cfg(test)]
 use quickcheck::{Arbitrary, Gen};
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UploadResponse {
     pub status: u8,
     pub start_time: Option<DateTime<Utc>>,
     pub complete_time: Option<DateTime<Utc>>,
+    pub extra: Vec<XmlElement>,
 }
 
 impl UploadResponse {
@@ -27,6 +29,7 @@ impl UploadResponse {
             status,
             start_time,
             complete_time,
+            extra: vec![],
         }
     }
 
@@ -39,9 +42,10 @@ impl UploadResponse {
         &self,
         writer: &mut xml::EventWriter<W>,
         has_cwmp: bool,
+        options: &GenerateOptions,
     ) -> Result<(), GenerateError> {
         writer.write(XmlEvent::start_element(
-            &cwmp_prefix(has_cwmp, "UploadResponse")[..],
+            &cwmp_prefix(options, has_cwmp, "UploadResponse")[..],
         ))?;
         write_simple(writer, "Status", &self.status.to_string())?;
         if let Some(dt) = self.start_time {
@@ -50,6 +54,9 @@ impl UploadResponse {
         if let Some(dt) = self.complete_time {
             write_simple(writer, "CompleteTime", &dt.to_rfc3339())?;
         }
+        for e in &self.extra {
+            e.generate(writer)?;
+        }
         writer.write(XmlEvent::end_element())?;
         Ok(())
     }
@@ -57,12 +64,12 @@ impl UploadResponse {
         match *path {
             ["UploadResponse", "Status"] => self.status = parse_to_int(characters, 0),
             ["UploadResponse", "StartTime"] => {
-                if let Ok(dt) = characters.parse::<DateTime<Utc>>() {
+                if let Ok(dt) = characters.trim().parse::<DateTime<Utc>>() {
                     self.start_time = Some(dt);
                 }
             }
             ["UploadResponse", "CompleteTime"] => {
-                if let Ok(dt) = characters.parse::<DateTime<Utc>>() {
+                if let Ok(dt) = characters.trim().parse::<DateTime<Utc>>() {
                     self.complete_time = Some(dt);
                 }
             }
@@ -85,6 +92,7 @@ impl Arbitrary for UploadResponse {
             status: s,
             start_time: Some(gen_utc_date(2014, 11, 28, 12, 0, 9)),
             complete_time: Some(gen_utc_date(2014, 11, 29, 12, 0, 9)),
+            extra: vec![],
         }))
     }
 }
@@ -4,9 +4,10 @@ use std::io::Write;
 use quickcheck::{Arbitrary, Gen};
 use xml::writer::XmlEvent;
 
-use super::{bool2str, cwmp_prefix, GenerateError};
+use super::{bool2str, cwmp_prefix, CwmpVersion, GenerateError, GenerateOptions};
 
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UseCWMPVersion {
     pub must_understand: bool,
     pub value: String,
@@ -21,6 +22,12 @@ impl UseCWMPVersion {
         }
     }
 
+    /// The version to use, `None` if it cannot be parsed
+    #[must_use]
+    pub fn version(&self) -> Option<CwmpVersion> {
+        CwmpVersion::parse(&self.value)
+    }
+
     /// Generate XML for the `UseCWMPVersion` message type
     ///     
     /// # Arguments
@@ -35,9 +42,10 @@ impl UseCWMPVersion {
         &self,
         writer: &mut xml::EventWriter<W>,
         has_cwmp: bool,
+        options: &GenerateOptions,
     ) -> Result<(), GenerateError> {
         writer.write(
-            XmlEvent::start_element(&cwmp_prefix(has_cwmp, "UseCWMPVersion")[..])
+            XmlEvent::start_element(&cwmp_prefix(options, has_cwmp, "UseCWMPVersion")[..])
                 .attr("mustUnderstand", bool2str(self.must_understand)),
         )?;
         writer.write(&self.value.to_string()[..])?;
@@ -0,0 +1,179 @@
+use super::{GenerateError, GenerateOptions, XmlElement, XmlName};
+use std::any::Any;
+use std::fmt;
+use std::io::Write;
+
+/// A vendor specific RPC, like `X_00D09E_Diagnostics`, parsed and generated
+/// the way the built-in RPCs are
+///
+/// Register the type in a `VendorRegistry` and pass it to `parse_with` in
+/// the `ParseOptions` for the body element to become a `BodyElement::Vendor`.
+/// The handlers get the path below the Body, starting with the name of the
+/// RPC. Deriving `Clone`, `PartialEq` and `Default` is enough for the rest.
+pub trait VendorRpc: DynVendorRpc + fmt::Debug + Send + Sync {
+    /// The name of the body element
+    fn name(&self) -> &str;
+
+    /// The namespace URI of the body element, `None` for the cwmp namespace
+    /// of the envelope
+    fn namespace(&self) -> Option<&str>;
+
+    /// Called for the start of the body element and of every element below it
+    fn start_handler(
+        &mut self,
+        path: &[&str],
+        name: &xml::name::OwnedName,
+        attributes: &[xml::attribute::OwnedAttribute],
+    );
+
+    /// Called for the text of the body element and of every element below it
+    fn characters(&mut self, path: &[&str], characters: &str);
+
+    /// The body element to generate, written out like an unknown one
+    fn to_element(&self, has_cwmp: bool, options: &GenerateOptions) -> XmlElement;
+
+    /// Generate XML for the body element, the one of `to_element`, which is
+    /// also what an `Envelope` writes out
+    ///
+    /// # Errors
+    ///
+    /// Any errors encountered while writing to `writer` will be returned.
+    fn generate(
+        &self,
+        writer: &mut xml::EventWriter<&mut dyn Write>,
+        has_cwmp: bool,
+        options: &GenerateOptions,
+    ) -> Result<(), GenerateError> {
+        self.to_element(has_cwmp, options).generate(writer)
+    }
+
+    /// The name of the body element, prefixed like the built-in RPCs when it
+    /// is in the cwmp namespace, or in its own namespace, made the default
+    /// one on the element
+    fn element_name(&self, has_cwmp: bool, options: &GenerateOptions) -> XmlName {
+        let mut name = XmlName::new(self.name());
+        match self.namespace() {
+            None if has_cwmp => name.prefix = Some(options.cwmp_prefix.clone()),
+            None => {}
+            Some(namespace) => name.namespace = Some(namespace.to_string()),
+        }
+        name
+    }
+}
+
+/// Lets a `BodyElement::Vendor` be cloned and compared. Implemented for every
+/// `VendorRpc` that is `Clone` and `PartialEq`.
+pub trait DynVendorRpc {
+    fn clone_box(&self) -> Box<dyn VendorRpc>;
+    fn as_any(&self) -> &dyn Any;
+    fn eq_dyn(&self, other: &dyn VendorRpc) -> bool;
+}
+
+impl<T: VendorRpc + Clone + PartialEq + 'static> DynVendorRpc for T {
+    fn clone_box(&self) -> Box<dyn VendorRpc> {
+        Box::new(self.clone())
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn eq_dyn(&self, other: &dyn VendorRpc) -> bool {
+        other.as_any().downcast_ref::<T>() == Some(self)
+    }
+}
+
+impl dyn VendorRpc {
+    /// The RPC as the type it was registered with
+    #[must_use]
+    pub fn downcast_ref<T: VendorRpc + 'static>(&self) -> Option<&T> {
+        self.as_any().downcast_ref::<T>()
+    }
+}
+
+impl Clone for Box<dyn VendorRpc> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+impl PartialEq for dyn VendorRpc {
+    fn eq(&self, other: &Self) -> bool {
+        self.eq_dyn(other)
+    }
+}
+
+#[derive(Clone)]
+struct Registration {
+    name: String,
+    namespace: Option<String>,
+    create: fn() -> Box<dyn VendorRpc>,
+}
+
+fn create<T: VendorRpc + Default + 'static>() -> Box<dyn VendorRpc> {
+    Box::new(T::default())
+}
+
+/// The vendor RPCs `parse_with` knows about, by name and namespace
+#[allow(clippy::module_name_repetitions)]
+#[derive(Default, Clone)]
+pub struct VendorRegistry {
+    rpcs: Vec<Registration>,
+}
+
+impl VendorRegistry {
+    #[must_use]
+    pub fn new() -> Self {
+        VendorRegistry::default()
+    }
+
+    /// Parse the body elements named like `T::default()` into a `T`
+    #[must_use]
+    pub fn register<T: VendorRpc + Default + 'static>(mut self) -> Self {
+        let rpc = T::default();
+        self.rpcs.push(Registration {
+            name: rpc.name().to_string(),
+            namespace: rpc.namespace().map(ToString::to_string),
+            create: create::<T>,
+        });
+        self
+    }
+
+    /// A new, empty RPC for a body element, `None` if it is not registered.
+    /// RPCs without a namespace of their own match the cwmp namespace of any
+    /// version, and no namespace at all.
+    #[must_use]
+    pub fn create(&self, name: &xml::name::OwnedName) -> Option<Box<dyn VendorRpc>> {
+        self.rpcs
+            .iter()
+            .find(|r| {
+                r.name == name.local_name
+                    && match (&r.namespace, &name.namespace) {
+                        (Some(registered), Some(namespace)) => registered == namespace,
+                        (Some(_), None) => false,
+                        (None, Some(namespace)) => namespace.starts_with("urn:dslforum-org:cwmp-"),
+                        (None, None) => true,
+                    }
+            })
+            .map(|r| (r.create)())
+    }
+}
+
+impl fmt::Debug for VendorRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list()
+            .entries(self.rpcs.iter().map(|r| &r.name))
+            .finish()
+    }
+}
+
+impl PartialEq for VendorRegistry {
+    fn eq(&self, other: &Self) -> bool {
+        self.rpcs.len() == other.rpcs.len()
+            && self
+                .rpcs
+                .iter()
+                .zip(&other.rpcs)
+                .all(|(a, b)| a.name == b.name && a.namespace == b.namespace)
+    }
+}
+
+impl Eq for VendorRegistry {}
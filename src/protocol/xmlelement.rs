@@ -0,0 +1,191 @@
+use std::io::Write;
+
+#[cfg(test)]
+use quickcheck::{Arbitrary, Gen};
+use xml::writer::XmlEvent;
+
+use super::GenerateError;
+
+/// A qualified XML name, as found in the parsed document
+#[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct XmlName {
+    pub local_name: String,
+    pub namespace: Option<String>,
+    pub prefix: Option<String>,
+}
+
+impl XmlName {
+    #[must_use]
+    pub fn new(local_name: &str) -> Self {
+        XmlName {
+            local_name: local_name.to_string(),
+            namespace: None,
+            prefix: None,
+        }
+    }
+    /// The name as written, "prefix:local_name"
+    #[must_use]
+    pub fn qualified(&self) -> String {
+        match &self.prefix {
+            Some(prefix) => format!("{prefix}:{}", self.local_name),
+            None => self.local_name.clone(),
+        }
+    }
+}
+
+impl From<&xml::name::OwnedName> for XmlName {
+    fn from(name: &xml::name::OwnedName) -> Self {
+        XmlName {
+            local_name: name.local_name.clone(),
+            namespace: name.namespace.clone(),
+            prefix: name.prefix.clone(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct XmlAttribute {
+    pub name: XmlName,
+    pub value: String,
+}
+
+/// The content of an `XmlElement`
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum XmlNode {
+    Element(XmlElement),
+    Text(String),
+}
+
+/// An XML element kept as it was received, for the parts of an envelope
+/// this crate does not model, like vendor RPCs and vendor arguments of the
+/// known RPCs. Generating it writes it back with the same meaning, the
+/// namespaces it uses being declared on it.
+#[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct XmlElement {
+    pub name: XmlName,
+    pub attributes: Vec<XmlAttribute>,
+    /// Namespace declarations, prefix and URI, the empty prefix being the
+    /// default namespace
+    pub namespaces: Vec<(String, String)>,
+    pub children: Vec<XmlNode>,
+}
+
+impl XmlElement {
+    #[must_use]
+    pub fn new(name: XmlName) -> Self {
+        XmlElement {
+            name,
+            ..XmlElement::default()
+        }
+    }
+
+    /// The text of the element, its text children put together
+    #[must_use]
+    pub fn text(&self) -> String {
+        self.children
+            .iter()
+            .filter_map(|c| match c {
+                XmlNode::Text(t) => Some(t.as_str()),
+                XmlNode::Element(_) => None,
+            })
+            .collect()
+    }
+
+    /// The first child element with the given local name
+    #[must_use]
+    pub fn child(&self, local_name: &str) -> Option<&XmlElement> {
+        self.children.iter().find_map(|c| match c {
+            XmlNode::Element(e) if e.name.local_name == local_name => Some(e),
+            _ => None,
+        })
+    }
+
+    /// The value of the attribute with the given local name
+    #[must_use]
+    pub fn attribute(&self, local_name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|a| a.name.local_name == local_name)
+            .map(|a| a.value.as_str())
+    }
+
+    /// Generate XML for the element and everything below it
+    ///
+    /// # Errors
+    ///
+    /// Any errors encountered while writing to `writer` will be returned.
+    pub fn generate<W: Write>(
+        &self,
+        writer: &mut xml::EventWriter<W>,
+    ) -> Result<(), GenerateError> {
+        let name = self.name.qualified();
+        let attribute_names: Vec<String> =
+            self.attributes.iter().map(|a| a.name.qualified()).collect();
+        let mut start = XmlEvent::start_element(name.as_str());
+        // the prefixes in use are bound again, declarations already in scope are not repeated
+        let used = std::iter::once(&self.name).chain(self.attributes.iter().map(|a| &a.name));
+        for n in used {
+            if let (Some(prefix), Some(uri)) = (&n.prefix, &n.namespace) {
+                start = start.ns(prefix.as_str(), uri.as_str());
+            }
+        }
+        if let (None, Some(uri)) = (&self.name.prefix, &self.name.namespace) {
+            if !uri.is_empty() {
+                start = start.default_ns(uri.as_str());
+            }
+        }
+        for (prefix, uri) in &self.namespaces {
+            start = if prefix.is_empty() {
+                start.default_ns(uri.as_str())
+            } else {
+                start.ns(prefix.as_str(), uri.as_str())
+            };
+        }
+        for (a, name) in self.attributes.iter().zip(&attribute_names) {
+            start = start.attr(name.as_str(), &a.value);
+        }
+        writer.write(start)?;
+        for child in &self.children {
+            match child {
+                XmlNode::Element(e) => e.generate(writer)?,
+                XmlNode::Text(t) => writer.write(XmlEvent::characters(t))?,
+            }
+        }
+        writer.write(XmlEvent::end_element())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+impl Arbitrary for XmlElement {
+    // a vendor extension, named so that no parser knows it
+    fn arbitrary(g: &mut Gen) -> Self {
+        let name = |g: &mut Gen| XmlName::new(&format!("X_ABC_{}", u16::arbitrary(g)));
+        let text = |g: &mut Gen| XmlNode::Text(format!("v{}", u32::arbitrary(g)));
+        let mut element = XmlElement::new(name(g));
+        if bool::arbitrary(g) {
+            element.name.prefix = Some("abc".to_string());
+            element.name.namespace = Some("urn:abc".to_string());
+        }
+        if bool::arbitrary(g) {
+            element.attributes.push(XmlAttribute {
+                name: XmlName::new("id"),
+                value: u32::arbitrary(g).to_string(),
+            });
+        }
+        if bool::arbitrary(g) {
+            element.children.push(text(g));
+        } else {
+            for _ in 0..usize::arbitrary(g) % 3 {
+                let mut child = XmlElement::new(name(g));
+                child.children.push(text(g));
+                element.children.push(XmlNode::Element(child));
+            }
+        }
+        element
+    }
+}
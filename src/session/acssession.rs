@@ -0,0 +1,294 @@
+use std::collections::VecDeque;
+
+use crate::error::Session;
+use crate::protocol::{
+    negotiate, AutonomousDUStateChangeCompleteResponse, AutonomousTransferCompleteResponse,
+    BodyElement, CwmpVersion, DUStateChangeCompleteResponse, DeviceId, Envelope, Fault, FaultCode,
+    GetRPCMethodsResponse, HeaderElement, HoldRequests, Inform, InformResponse, KickedResponse,
+    RequestDownloadResponse, TransferCompleteResponse, UseCWMPVersion, ID,
+};
+
+use super::{envelope_id, settle, single_body_element, Exchange, SessionAction};
+
+// the methods an ACS answers, as reported in GetRPCMethodsResponse
+const ACS_METHODS: [&str; 8] = [
+    "Inform",
+    "GetRPCMethods",
+    "TransferComplete",
+    "AutonomousTransferComplete",
+    "DUStateChangeComplete",
+    "AutonomousDUStateChangeComplete",
+    "RequestDownload",
+    "Kicked",
+];
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Phase {
+    // nothing received yet, the CPE must open with an Inform
+    AwaitingInform,
+    // the CPE is sending its own requests, until it sends an empty envelope
+    CpeRequests,
+    // the ACS is sending its queued requests, one at a time
+    AcsRequests,
+    Finished,
+}
+
+/// The ACS side of a TR-069 session.
+///
+/// Feed every envelope received from the CPE to `handle` (`None` for an empty
+/// HTTP POST), and send back what it returns. The session answers the Inform
+/// and the CPE originated requests itself, then sends the RPCs queued with
+/// `queue` one at a time, matching each response to its request by the `ID`
+/// header. When the queue is empty the session ends.
+///
+/// A CPE listing its `SupportedCWMPVersions` in the Inform gets the highest
+/// version both ends support in a `UseCWMPVersion` header, and that version
+/// is used for the rest of the session.
+#[derive(Debug, Clone)]
+pub struct AcsSession {
+    phase: Phase,
+    cwmp_version: Option<CwmpVersion>,
+    supported_versions: Vec<CwmpVersion>,
+    inform: Option<Inform>,
+    queue: VecDeque<BodyElement>,
+    outstanding: Option<(String, BodyElement)>,
+    exchanges: Vec<Exchange>,
+    cpe_requests: Vec<BodyElement>,
+    hold_requests: bool,
+    no_more_requests: bool,
+    next_id: u32,
+}
+
+impl Default for AcsSession {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AcsSession {
+    #[must_use]
+    pub fn new() -> Self {
+        AcsSession {
+            phase: Phase::AwaitingInform,
+            cwmp_version: None,
+            supported_versions: CwmpVersion::KNOWN.to_vec(),
+            inform: None,
+            queue: VecDeque::new(),
+            outstanding: None,
+            exchanges: vec![],
+            cpe_requests: vec![],
+            hold_requests: false,
+            no_more_requests: false,
+            next_id: 1,
+        }
+    }
+
+    /// Queue an RPC to be sent to the CPE once it has finished sending its own requests
+    pub fn queue(&mut self, rpc: BodyElement) {
+        self.queue.push_back(rpc);
+    }
+
+    /// Number of queued RPCs not yet sent to the CPE
+    #[must_use]
+    pub fn pending(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Include a `HoldRequests` header in the envelopes sent to the CPE,
+    /// asking it not to send any requests of its own
+    pub fn set_hold_requests(&mut self, hold: bool) {
+        self.hold_requests = hold;
+    }
+
+    /// The versions offered to CPEs that list their own, all known versions by default
+    pub fn set_supported_versions(&mut self, versions: &[CwmpVersion]) {
+        self.supported_versions = versions.to_vec();
+    }
+
+    /// The Inform that started the session
+    #[must_use]
+    pub fn inform(&self) -> Option<&Inform> {
+        self.inform.as_ref()
+    }
+
+    #[must_use]
+    pub fn device_id(&self) -> Option<&DeviceId> {
+        self.inform.as_ref().map(|i| &i.device_id)
+    }
+
+    /// The CWMP version used for every envelope sent in the session: the
+    /// negotiated one, or else the version of the Inform
+    #[must_use]
+    pub fn cwmp_version(&self) -> Option<&CwmpVersion> {
+        self.cwmp_version.as_ref()
+    }
+
+    /// The maximum number of envelopes the CPE accepts in one HTTP response,
+    /// as reported in the Inform. The session never has more than one
+    /// envelope in flight, so it always stays within this limit.
+    #[must_use]
+    pub fn max_envelopes(&self) -> u32 {
+        self.inform.as_ref().map_or(1, |i| i.max_envelopes)
+    }
+
+    /// The requests sent to the CPE so far, with the responses they got
+    #[must_use]
+    pub fn exchanges(&self) -> &[Exchange] {
+        &self.exchanges
+    }
+
+    /// The requests the CPE sent after the Inform, like `TransferComplete`
+    #[must_use]
+    pub fn cpe_requests(&self) -> &[BodyElement] {
+        &self.cpe_requests
+    }
+
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.phase == Phase::Finished
+    }
+
+    /// Handle an envelope received from the CPE, `None` or an envelope
+    /// without body elements being an empty HTTP POST.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `error::Session` if the envelope does not fit the session flow,
+    /// like a response that does not match the outstanding request.
+    pub fn handle(&mut self, envelope: Option<&Envelope>) -> Result<SessionAction, Session> {
+        if self.phase == Phase::Finished {
+            return Err(Session::Finished);
+        }
+        let body = single_body_element(envelope)?;
+        let id = envelope.and_then(envelope_id).map(ToString::to_string);
+
+        match (self.phase, body) {
+            (Phase::AwaitingInform, Some(BodyElement::Inform(inform))) => {
+                self.cwmp_version = envelope.and_then(|e| e.cwmp_version.clone());
+                let negotiated = envelope.and_then(|e| {
+                    negotiate(&self.supported_versions, &e.supported_cwmp_versions())
+                });
+                self.no_more_requests = envelope.is_some_and(|e| {
+                    e.header
+                        .iter()
+                        .any(|h| matches!(h, HeaderElement::NoMoreRequests(n) if n.value != 0))
+                });
+                self.inform = Some(inform.clone());
+                self.phase = Phase::CpeRequests;
+                // TR-069 requires MaxEnvelopes to be 1 in the InformResponse
+                let mut reply = self.envelope(
+                    id.as_deref(),
+                    BodyElement::InformResponse(InformResponse::new(1)),
+                );
+                if let Some(version) = negotiated {
+                    reply
+                        .header
+                        .push(HeaderElement::UseCWMPVersion(UseCWMPVersion::new(
+                            true,
+                            version.to_string(),
+                        )));
+                    self.cwmp_version = Some(version.clone());
+                    reply.cwmp_version = Some(version);
+                }
+                Ok(SessionAction::Send(reply))
+            }
+            (Phase::AwaitingInform, other) => Err(Session::InformExpected(
+                other
+                    .map_or("an empty envelope", BodyElement::rpc_name)
+                    .to_string(),
+            )),
+            (Phase::CpeRequests, Some(request)) => {
+                if self.no_more_requests || self.hold_requests {
+                    return Err(Session::Unexpected(request.rpc_name().to_string()));
+                }
+                let response = Self::answer(request)?;
+                self.cpe_requests.push(request.clone());
+                Ok(SessionAction::Send(self.envelope(id.as_deref(), response)))
+            }
+            (Phase::CpeRequests, None) => {
+                self.phase = Phase::AcsRequests;
+                Ok(self.next_request())
+            }
+            (Phase::AcsRequests, Some(response)) => {
+                let exchange = settle(&mut self.outstanding, id, response)?;
+                self.exchanges.push(exchange);
+                Ok(self.next_request())
+            }
+            (Phase::AcsRequests, None) => {
+                if let Some((_, request)) = &self.outstanding {
+                    return Err(Session::Unexpected(format!(
+                        "an empty envelope while waiting for a response to {}",
+                        request.rpc_name()
+                    )));
+                }
+                Ok(self.next_request())
+            }
+            (Phase::Finished, _) => Err(Session::Finished),
+        }
+    }
+
+    // the ACS response to a CPE originated request
+    fn answer(request: &BodyElement) -> Result<BodyElement, Session> {
+        let response = match request {
+            BodyElement::AutonomousDUStateChangeComplete(_) => {
+                BodyElement::AutonomousDUStateChangeCompleteResponse(
+                    AutonomousDUStateChangeCompleteResponse {},
+                )
+            }
+            BodyElement::AutonomousTransferComplete(_) => {
+                BodyElement::AutonomousTransferCompleteResponse(
+                    AutonomousTransferCompleteResponse {},
+                )
+            }
+            BodyElement::DUStateChangeComplete(_) => {
+                BodyElement::DUStateChangeCompleteResponse(DUStateChangeCompleteResponse {})
+            }
+            BodyElement::GetRPCMethods(_) => {
+                BodyElement::GetRPCMethodsResponse(GetRPCMethodsResponse::new(&ACS_METHODS))
+            }
+            BodyElement::Kicked(k) => {
+                BodyElement::KickedResponse(KickedResponse::new(k.next.0.as_ref()))
+            }
+            BodyElement::RequestDownload(_) => {
+                BodyElement::RequestDownloadResponse(RequestDownloadResponse {})
+            }
+            BodyElement::TransferComplete(_) => {
+                BodyElement::TransferCompleteResponse(TransferCompleteResponse {})
+            }
+            other if other.is_response() || matches!(other, BodyElement::Inform(_)) => {
+                return Err(Session::Unexpected(request.rpc_name().to_string()))
+            }
+            // a request the ACS does not implement
+            _ => BodyElement::Fault(Fault::from_code(FaultCode::AcsMethodNotSupported)),
+        };
+        Ok(response)
+    }
+
+    // send the next queued request, or end the session if there is none
+    fn next_request(&mut self) -> SessionAction {
+        match self.queue.pop_front() {
+            Some(request) => {
+                let id = self.next_id.to_string();
+                self.next_id += 1;
+                let envelope = self.envelope(Some(&id), request.clone());
+                self.outstanding = Some((id, request));
+                SessionAction::Send(envelope)
+            }
+            None => {
+                self.phase = Phase::Finished;
+                SessionAction::End
+            }
+        }
+    }
+
+    fn envelope(&self, id: Option<&str>, body: BodyElement) -> Envelope {
+        let mut header = vec![];
+        if let Some(id) = id {
+            header.push(HeaderElement::ID(ID::new(true, id)));
+        }
+        if self.hold_requests {
+            header.push(HeaderElement::HoldRequests(HoldRequests::new(true, true)));
+        }
+        Envelope::new(self.cwmp_version.clone(), header, vec![body])
+    }
+}
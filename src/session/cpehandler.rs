@@ -0,0 +1,242 @@
+use crate::datamodel::DataModel;
+use crate::protocol::{
+    AddObject, AddObjectResponse, BodyElement, DeleteObject, DeleteObjectResponse, Download,
+    DownloadResponse, FactoryResetResponse, Fault, FaultCode, GetParameterAttributes,
+    GetParameterAttributesResponse, GetParameterNames, GetParameterNamesResponse,
+    GetParameterValues, GetParameterValuesResponse, GetRPCMethodsResponse, Reboot, RebootResponse,
+    ScheduleInform, ScheduleInformResponse, SetParameterAttributes, SetParameterAttributesResponse,
+    SetParameterValues, SetParameterValuesResponse, Upload, UploadResponse,
+};
+
+/// The methods a `CpeSession` answers through its handler, as reported
+/// in the default `GetRPCMethodsResponse`
+pub const CPE_METHODS: [&str; 13] = [
+    "GetRPCMethods",
+    "SetParameterValues",
+    "GetParameterValues",
+    "GetParameterNames",
+    "SetParameterAttributes",
+    "GetParameterAttributes",
+    "AddObject",
+    "DeleteObject",
+    "Reboot",
+    "Download",
+    "Upload",
+    "FactoryReset",
+    "ScheduleInform",
+];
+
+/// The device behind a `CpeSession`, answering the RPCs sent by the ACS.
+///
+/// Every method defaults to a 9000 "Method not supported" fault, so an
+/// implementation only has to provide the RPCs it cares about. RPCs without
+/// a method of their own end up in `other`.
+// the fault is the answer to send, built once per RPC, not worth a box
+#[allow(unused_variables, clippy::result_large_err)]
+pub trait CpeHandler {
+    /// # Errors
+    ///
+    /// The `Fault` to send back to the ACS instead of a response
+    fn get_rpc_methods(&mut self) -> Result<GetRPCMethodsResponse, Fault> {
+        Ok(GetRPCMethodsResponse::new(&CPE_METHODS))
+    }
+
+    /// # Errors
+    ///
+    /// The `Fault` to send back to the ACS instead of a response
+    fn get_parameter_values(
+        &mut self,
+        request: &GetParameterValues,
+    ) -> Result<GetParameterValuesResponse, Fault> {
+        Err(Fault::from_code(FaultCode::MethodNotSupported))
+    }
+
+    /// # Errors
+    ///
+    /// The `Fault` to send back to the ACS instead of a response
+    fn set_parameter_values(
+        &mut self,
+        request: &SetParameterValues,
+    ) -> Result<SetParameterValuesResponse, Fault> {
+        Err(Fault::from_code(FaultCode::MethodNotSupported))
+    }
+
+    /// # Errors
+    ///
+    /// The `Fault` to send back to the ACS instead of a response
+    fn get_parameter_names(
+        &mut self,
+        request: &GetParameterNames,
+    ) -> Result<GetParameterNamesResponse, Fault> {
+        Err(Fault::from_code(FaultCode::MethodNotSupported))
+    }
+
+    /// # Errors
+    ///
+    /// The `Fault` to send back to the ACS instead of a response
+    fn get_parameter_attributes(
+        &mut self,
+        request: &GetParameterAttributes,
+    ) -> Result<GetParameterAttributesResponse, Fault> {
+        Err(Fault::from_code(FaultCode::MethodNotSupported))
+    }
+
+    /// # Errors
+    ///
+    /// The `Fault` to send back to the ACS instead of a response
+    fn set_parameter_attributes(
+        &mut self,
+        request: &SetParameterAttributes,
+    ) -> Result<SetParameterAttributesResponse, Fault> {
+        Err(Fault::from_code(FaultCode::MethodNotSupported))
+    }
+
+    /// # Errors
+    ///
+    /// The `Fault` to send back to the ACS instead of a response
+    fn add_object(&mut self, request: &AddObject) -> Result<AddObjectResponse, Fault> {
+        Err(Fault::from_code(FaultCode::MethodNotSupported))
+    }
+
+    /// # Errors
+    ///
+    /// The `Fault` to send back to the ACS instead of a response
+    fn delete_object(&mut self, request: &DeleteObject) -> Result<DeleteObjectResponse, Fault> {
+        Err(Fault::from_code(FaultCode::MethodNotSupported))
+    }
+
+    /// # Errors
+    ///
+    /// The `Fault` to send back to the ACS instead of a response
+    fn reboot(&mut self, request: &Reboot) -> Result<RebootResponse, Fault> {
+        Err(Fault::from_code(FaultCode::MethodNotSupported))
+    }
+
+    /// # Errors
+    ///
+    /// The `Fault` to send back to the ACS instead of a response
+    fn factory_reset(&mut self) -> Result<FactoryResetResponse, Fault> {
+        Err(Fault::from_code(FaultCode::MethodNotSupported))
+    }
+
+    /// # Errors
+    ///
+    /// The `Fault` to send back to the ACS instead of a response
+    fn download(&mut self, request: &Download) -> Result<DownloadResponse, Fault> {
+        Err(Fault::from_code(FaultCode::MethodNotSupported))
+    }
+
+    /// # Errors
+    ///
+    /// The `Fault` to send back to the ACS instead of a response
+    fn upload(&mut self, request: &Upload) -> Result<UploadResponse, Fault> {
+        Err(Fault::from_code(FaultCode::MethodNotSupported))
+    }
+
+    /// # Errors
+    ///
+    /// The `Fault` to send back to the ACS instead of a response
+    fn schedule_inform(
+        &mut self,
+        request: &ScheduleInform,
+    ) -> Result<ScheduleInformResponse, Fault> {
+        Err(Fault::from_code(FaultCode::MethodNotSupported))
+    }
+
+    /// Any other request from the ACS, like `ScheduleDownload` or `ChangeDUState`
+    ///
+    /// # Errors
+    ///
+    /// The `Fault` to send back to the ACS instead of a response
+    fn other(&mut self, request: &BodyElement) -> Result<BodyElement, Fault> {
+        Err(Fault::from_code(FaultCode::MethodNotSupported))
+    }
+}
+
+// hand an ACS request to the matching handler method
+pub(super) fn dispatch<H: CpeHandler + ?Sized>(
+    handler: &mut H,
+    request: &BodyElement,
+) -> BodyElement {
+    let response = match request {
+        BodyElement::GetRPCMethods(_) => handler
+            .get_rpc_methods()
+            .map(BodyElement::GetRPCMethodsResponse),
+        BodyElement::GetParameterValues(r) => handler
+            .get_parameter_values(r)
+            .map(BodyElement::GetParameterValuesResponse),
+        BodyElement::SetParameterValues(r) => handler
+            .set_parameter_values(r)
+            .map(BodyElement::SetParameterValuesResponse),
+        BodyElement::GetParameterNames(r) => handler
+            .get_parameter_names(r)
+            .map(BodyElement::GetParameterNamesResponse),
+        BodyElement::GetParameterAttributes(r) => handler
+            .get_parameter_attributes(r)
+            .map(BodyElement::GetParameterAttributesResponse),
+        BodyElement::SetParameterAttributes(r) => handler
+            .set_parameter_attributes(r)
+            .map(BodyElement::SetParameterAttributesResponse),
+        BodyElement::AddObject(r) => handler.add_object(r).map(BodyElement::AddObjectResponse),
+        BodyElement::DeleteObject(r) => handler
+            .delete_object(r)
+            .map(BodyElement::DeleteObjectResponse),
+        BodyElement::Reboot(r) => handler.reboot(r).map(BodyElement::RebootResponse),
+        BodyElement::FactoryReset(_) => handler
+            .factory_reset()
+            .map(BodyElement::FactoryResetResponse),
+        BodyElement::Download(r) => handler.download(r).map(BodyElement::DownloadResponse),
+        BodyElement::Upload(r) => handler.upload(r).map(BodyElement::UploadResponse),
+        BodyElement::ScheduleInform(r) => handler
+            .schedule_inform(r)
+            .map(BodyElement::ScheduleInformResponse),
+        other => handler.other(other),
+    };
+    response.unwrap_or_else(BodyElement::Fault)
+}
+
+// the data model answers the parameter RPCs by itself
+impl CpeHandler for DataModel {
+    fn get_parameter_values(
+        &mut self,
+        request: &GetParameterValues,
+    ) -> Result<GetParameterValuesResponse, Fault> {
+        DataModel::get_parameter_values(self, request)
+    }
+
+    fn set_parameter_values(
+        &mut self,
+        request: &SetParameterValues,
+    ) -> Result<SetParameterValuesResponse, Fault> {
+        DataModel::set_parameter_values(self, request)
+    }
+
+    fn get_parameter_names(
+        &mut self,
+        request: &GetParameterNames,
+    ) -> Result<GetParameterNamesResponse, Fault> {
+        DataModel::get_parameter_names(self, request)
+    }
+
+    fn get_parameter_attributes(
+        &mut self,
+        request: &GetParameterAttributes,
+    ) -> Result<GetParameterAttributesResponse, Fault> {
+        DataModel::get_parameter_attributes(self, request)
+    }
+
+    fn set_parameter_attributes(
+        &mut self,
+        request: &SetParameterAttributes,
+    ) -> Result<SetParameterAttributesResponse, Fault> {
+        DataModel::set_parameter_attributes(self, request)
+    }
+
+    fn add_object(&mut self, request: &AddObject) -> Result<AddObjectResponse, Fault> {
+        DataModel::add_object(self, request)
+    }
+
+    fn delete_object(&mut self, request: &DeleteObject) -> Result<DeleteObjectResponse, Fault> {
+        DataModel::delete_object(self, request)
+    }
+}
@@ -0,0 +1,271 @@
+use std::collections::VecDeque;
+
+use chrono::Utc;
+
+use crate::error::Session;
+use crate::protocol::{
+    BodyElement, CwmpVersion, DeviceId, Envelope, EventStruct, HeaderElement, Inform,
+    ParameterValue, SupportedCWMPVersions, ID,
+};
+
+use super::cpehandler::dispatch;
+use super::{envelope_id, settle, single_body_element, CpeHandler, Exchange, SessionAction};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Phase {
+    // the Inform has not been sent yet
+    Idle,
+    // the CPE is sending the Inform and its own requests, one at a time
+    CpeRequests,
+    // the empty envelope has been sent, the ACS is sending its requests
+    AcsRequests,
+    Finished,
+}
+
+/// The CPE side of a TR-069 session, the mirror image of `AcsSession`.
+///
+/// `start` produces the Inform, built from the configured `DeviceId`, events
+/// and parameters. Every envelope received from the ACS is then fed to
+/// `handle` (`None` for an empty HTTP response), and whatever it returns is
+/// sent back. Once the ACS has answered the Inform, the requests queued with
+/// `queue` (like `TransferComplete`) are sent, followed by an envelope without
+/// body elements standing for the empty POST. From then on every ACS request
+/// is answered by the `CpeHandler`, until the ACS ends the session.
+#[derive(Debug, Clone)]
+pub struct CpeSession<H: CpeHandler> {
+    handler: H,
+    phase: Phase,
+    cwmp_version: CwmpVersion,
+    supported_versions: Vec<CwmpVersion>,
+    device_id: DeviceId,
+    events: Vec<EventStruct>,
+    parameter_list: Vec<ParameterValue>,
+    retry_count: u32,
+    queue: VecDeque<BodyElement>,
+    outstanding: Option<(String, BodyElement)>,
+    exchanges: Vec<Exchange>,
+    acs_requests: Vec<BodyElement>,
+    hold_requests: bool,
+    next_id: u32,
+}
+
+impl<H: CpeHandler> CpeSession<H> {
+    #[must_use]
+    pub fn new(device_id: DeviceId, handler: H) -> Self {
+        CpeSession {
+            handler,
+            phase: Phase::Idle,
+            cwmp_version: CwmpVersion::new(1, 0),
+            supported_versions: vec![],
+            device_id,
+            events: vec![],
+            parameter_list: vec![],
+            retry_count: 0,
+            queue: VecDeque::new(),
+            outstanding: None,
+            exchanges: vec![],
+            acs_requests: vec![],
+            hold_requests: false,
+            next_id: 1,
+        }
+    }
+
+    /// The CWMP version used for every envelope sent in the session, 1.0 by default
+    pub fn set_cwmp_version(&mut self, cwmp_version: CwmpVersion) {
+        self.cwmp_version = cwmp_version;
+    }
+
+    /// List `versions` in a `SupportedCWMPVersions` header of the Inform, and
+    /// switch to the version the ACS picks from them with `UseCWMPVersion`
+    pub fn set_supported_versions(&mut self, versions: &[CwmpVersion]) {
+        self.supported_versions = versions.to_vec();
+    }
+
+    /// Add an event to the Inform, like "1 BOOT" or "2 PERIODIC"
+    pub fn add_event(&mut self, event: EventStruct) {
+        self.events.push(event);
+    }
+
+    /// Add a parameter to the Inform `ParameterList`
+    pub fn add_parameter(&mut self, parameter: ParameterValue) {
+        self.parameter_list.push(parameter);
+    }
+
+    pub fn set_retry_count(&mut self, retry_count: u32) {
+        self.retry_count = retry_count;
+    }
+
+    /// Queue a CPE originated request, sent after the Inform has been answered
+    pub fn queue(&mut self, rpc: BodyElement) {
+        self.queue.push_back(rpc);
+    }
+
+    /// Number of queued requests not yet sent to the ACS
+    #[must_use]
+    pub fn pending(&self) -> usize {
+        self.queue.len()
+    }
+
+    #[must_use]
+    pub fn handler(&self) -> &H {
+        &self.handler
+    }
+
+    pub fn handler_mut(&mut self) -> &mut H {
+        &mut self.handler
+    }
+
+    #[must_use]
+    pub fn into_handler(self) -> H {
+        self.handler
+    }
+
+    /// The requests sent to the ACS so far, the Inform included, with the responses they got
+    #[must_use]
+    pub fn exchanges(&self) -> &[Exchange] {
+        &self.exchanges
+    }
+
+    /// The requests the ACS sent, in the order they were answered
+    #[must_use]
+    pub fn acs_requests(&self) -> &[BodyElement] {
+        &self.acs_requests
+    }
+
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.phase == Phase::Finished
+    }
+
+    /// Start the session, returning the Inform envelope to send to the ACS
+    ///
+    /// # Errors
+    ///
+    /// Returns an `error::Session` if the session has already been started
+    pub fn start(&mut self) -> Result<Envelope, Session> {
+        match self.phase {
+            Phase::Idle => {}
+            Phase::Finished => return Err(Session::Finished),
+            _ => return Err(Session::Unexpected("Inform".to_string())),
+        }
+        let inform = BodyElement::Inform(Inform::new(
+            self.device_id.clone(),
+            self.events.clone(),
+            1,
+            Utc::now(),
+            self.retry_count,
+            self.parameter_list.clone(),
+        ));
+        self.phase = Phase::CpeRequests;
+        let mut envelope = self.send_request(inform);
+        if !self.supported_versions.is_empty() {
+            envelope.header.push(HeaderElement::SupportedCWMPVersions(
+                SupportedCWMPVersions::from_versions(false, &self.supported_versions),
+            ));
+        }
+        Ok(envelope)
+    }
+
+    /// Handle an envelope received from the ACS, `None` or an envelope
+    /// without body elements being an empty HTTP response.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `error::Session` if the envelope does not fit the session flow,
+    /// like a response that does not match the outstanding request.
+    pub fn handle(&mut self, envelope: Option<&Envelope>) -> Result<SessionAction, Session> {
+        match self.phase {
+            Phase::Idle => {
+                return Err(Session::Unexpected(
+                    "an envelope before the Inform".to_string(),
+                ))
+            }
+            Phase::Finished => return Err(Session::Finished),
+            _ => {}
+        }
+        let body = single_body_element(envelope)?;
+        let id = envelope.and_then(envelope_id).map(ToString::to_string);
+        if let Some(version) = envelope
+            .and_then(Envelope::use_cwmp_version)
+            .filter(|v| self.supported_versions.contains(v))
+        {
+            self.cwmp_version = version;
+        }
+        if let Some(e) = envelope {
+            self.hold_requests = e
+                .header
+                .iter()
+                .any(|h| matches!(h, HeaderElement::HoldRequests(h) if h.hold));
+        }
+
+        match (self.phase, body) {
+            (Phase::CpeRequests, Some(response)) => {
+                let exchange = settle(&mut self.outstanding, id, response)?;
+                let refused = matches!(
+                    (&exchange.request, &exchange.response),
+                    (BodyElement::Inform(_), BodyElement::Fault(_))
+                );
+                self.exchanges.push(exchange);
+                if refused {
+                    // the ACS did not accept the Inform, there is no session
+                    self.phase = Phase::Finished;
+                    return Ok(SessionAction::End);
+                }
+                Ok(SessionAction::Send(self.next_request()))
+            }
+            (Phase::CpeRequests, None) => Err(Session::Unexpected(format!(
+                "an empty response while waiting for a response to {}",
+                self.outstanding
+                    .as_ref()
+                    .map_or("a request", |(_, r)| r.rpc_name())
+            ))),
+            (Phase::AcsRequests, Some(request)) => {
+                if request.is_response() {
+                    return Err(Session::Unexpected(request.rpc_name().to_string()));
+                }
+                let response = dispatch(&mut self.handler, request);
+                self.acs_requests.push(request.clone());
+                Ok(SessionAction::Send(
+                    self.envelope(id.as_deref(), vec![response]),
+                ))
+            }
+            (Phase::AcsRequests, None) => {
+                self.phase = Phase::Finished;
+                Ok(SessionAction::End)
+            }
+            (Phase::Idle | Phase::Finished, _) => Err(Session::Finished),
+        }
+    }
+
+    // send the next queued request, or the empty envelope handing over to the ACS
+    fn next_request(&mut self) -> Envelope {
+        // the ACS may ask the CPE to hold back its own requests
+        let request = if self.hold_requests {
+            None
+        } else {
+            self.queue.pop_front()
+        };
+        match request {
+            Some(request) => self.send_request(request),
+            None => {
+                self.phase = Phase::AcsRequests;
+                self.envelope(None, vec![])
+            }
+        }
+    }
+
+    fn send_request(&mut self, request: BodyElement) -> Envelope {
+        let id = self.next_id.to_string();
+        self.next_id += 1;
+        let envelope = self.envelope(Some(&id), vec![request.clone()]);
+        self.outstanding = Some((id, request));
+        envelope
+    }
+
+    fn envelope(&self, id: Option<&str>, body: Vec<BodyElement>) -> Envelope {
+        let header = id
+            .map(|id| vec![HeaderElement::ID(ID::new(true, id))])
+            .unwrap_or_default();
+        Envelope::new(Some(self.cwmp_version.clone()), header, body)
+    }
+}
@@ -0,0 +1,78 @@
+//! Transport agnostic TR-069 session handling.
+//!
+//! A session is driven by feeding it the envelopes received from the peer,
+//! and sending whatever it hands back. How the envelopes travel (HTTP, a test
+//! harness, ...) is up to the caller.
+use crate::error::Session;
+use crate::protocol::{BodyElement, Envelope, HeaderElement};
+
+mod acssession;
+mod cpehandler;
+mod cpesession;
+mod simulateddevice;
+
+pub use acssession::AcsSession;
+pub use cpehandler::{CpeHandler, CPE_METHODS};
+pub use cpesession::CpeSession;
+pub use simulateddevice::SimulatedDevice;
+
+/// What to do after an envelope has been handled by a session
+#[derive(Debug, PartialEq, Clone)]
+pub enum SessionAction {
+    /// Send this envelope to the peer
+    Send(Envelope),
+    /// Nothing more to send, end the session (HTTP 204 No Content on the ACS side)
+    End,
+}
+
+/// A request sent during a session, together with the response it got
+#[derive(Debug, PartialEq, Clone)]
+pub struct Exchange {
+    pub id: String,
+    pub request: BodyElement,
+    pub response: BodyElement,
+}
+
+// the ID header of an envelope, if any
+fn envelope_id(envelope: &Envelope) -> Option<&str> {
+    envelope.header.iter().find_map(|h| match h {
+        HeaderElement::ID(id) => Some(id.id.0.as_ref()),
+        _ => None,
+    })
+}
+
+// the single body element of an envelope, None for an empty envelope
+fn single_body_element(envelope: Option<&Envelope>) -> Result<Option<&BodyElement>, Session> {
+    match envelope.map(|e| e.body.as_slice()) {
+        None | Some([]) => Ok(None),
+        Some([be]) => Ok(Some(be)),
+        Some(body) => Err(Session::BodyElementCount(body.len())),
+    }
+}
+
+// match a received response against the outstanding request
+fn settle(
+    outstanding: &mut Option<(String, BodyElement)>,
+    id: Option<String>,
+    response: &BodyElement,
+) -> Result<Exchange, Session> {
+    let Some((expected, request)) = outstanding.take() else {
+        return Err(Session::Unexpected(response.rpc_name().to_string()));
+    };
+    if let Some(received) = id {
+        if received != expected {
+            return Err(Session::IdMismatch { expected, received });
+        }
+    }
+    if !response.matches_request(&request) {
+        return Err(Session::ResponseMismatch {
+            request: request.rpc_name().to_string(),
+            response: response.rpc_name().to_string(),
+        });
+    }
+    Ok(Exchange {
+        id: expected,
+        request,
+        response: response.clone(),
+    })
+}
@@ -0,0 +1,112 @@
+use crate::datamodel::{DataModel, NodeKind};
+use crate::protocol::{
+    AddObject, AddObjectResponse, DeleteObject, DeleteObjectResponse, FactoryResetResponse, Fault,
+    GetParameterAttributes, GetParameterAttributesResponse, GetParameterNames,
+    GetParameterNamesResponse, GetParameterValues, GetParameterValuesResponse, ParameterValue,
+    Reboot, RebootResponse, SetParameterAttributes, SetParameterAttributesResponse,
+    SetParameterValues, SetParameterValuesResponse,
+};
+
+use super::CpeHandler;
+
+/// A minimal `CpeHandler` keeping its parameters in a `DataModel`,
+/// enough to run ACS conversations against in tests.
+///
+/// The parameter RPCs, `AddObject` and `DeleteObject` are answered by the
+/// data model. `Reboot` and `FactoryReset` are recorded, not acted upon.
+#[derive(Debug, Default, Clone)]
+pub struct SimulatedDevice {
+    pub data_model: DataModel,
+    /// The command keys of the `Reboot` requests received
+    pub reboots: Vec<String>,
+    /// The number of `FactoryReset` requests received
+    pub factory_resets: u32,
+}
+
+impl SimulatedDevice {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn with_data_model(data_model: DataModel) -> Self {
+        SimulatedDevice {
+            data_model,
+            reboots: vec![],
+            factory_resets: 0,
+        }
+    }
+
+    pub fn add_parameter(&mut self, parameter: ParameterValue, writable: bool) {
+        self.data_model.insert_parameter(
+            &parameter.name.0,
+            &parameter.r#type.0,
+            &parameter.value.0,
+            writable,
+        );
+    }
+
+    #[must_use]
+    pub fn parameter(&self, name: &str) -> Option<ParameterValue> {
+        match &self.data_model.node(name)?.kind {
+            NodeKind::Parameter { r#type, value } => Some(ParameterValue::new(name, r#type, value)),
+            _ => None,
+        }
+    }
+}
+
+impl CpeHandler for SimulatedDevice {
+    fn get_parameter_values(
+        &mut self,
+        request: &GetParameterValues,
+    ) -> Result<GetParameterValuesResponse, Fault> {
+        self.data_model.get_parameter_values(request)
+    }
+
+    fn set_parameter_values(
+        &mut self,
+        request: &SetParameterValues,
+    ) -> Result<SetParameterValuesResponse, Fault> {
+        self.data_model.set_parameter_values(request)
+    }
+
+    fn get_parameter_names(
+        &mut self,
+        request: &GetParameterNames,
+    ) -> Result<GetParameterNamesResponse, Fault> {
+        self.data_model.get_parameter_names(request)
+    }
+
+    fn get_parameter_attributes(
+        &mut self,
+        request: &GetParameterAttributes,
+    ) -> Result<GetParameterAttributesResponse, Fault> {
+        self.data_model.get_parameter_attributes(request)
+    }
+
+    fn set_parameter_attributes(
+        &mut self,
+        request: &SetParameterAttributes,
+    ) -> Result<SetParameterAttributesResponse, Fault> {
+        self.data_model.set_parameter_attributes(request)
+    }
+
+    fn add_object(&mut self, request: &AddObject) -> Result<AddObjectResponse, Fault> {
+        self.data_model.add_object(request)
+    }
+
+    fn delete_object(&mut self, request: &DeleteObject) -> Result<DeleteObjectResponse, Fault> {
+        self.data_model.delete_object(request)
+    }
+
+    fn reboot(&mut self, request: &Reboot) -> Result<RebootResponse, Fault> {
+        self.reboots.push(request.command_key.0.clone());
+        Ok(RebootResponse {})
+    }
+
+    fn factory_reset(&mut self) -> Result<FactoryResetResponse, Fault> {
+        self.factory_resets += 1;
+        Ok(FactoryResetResponse)
+    }
+}
@@ -0,0 +1,255 @@
+use std::collections::HashMap;
+use std::io::BufReader;
+use std::net::TcpStream;
+use std::time::{Duration, Instant};
+
+use http::header::{AUTHORIZATION, CONNECTION, CONTENT_TYPE, SET_COOKIE, WWW_AUTHENTICATE};
+use http::{Method, Request, Response, StatusCode};
+use log::warn;
+
+use crate::error::{Cwmp, Transport};
+use crate::protocol::{Envelope, GenerateOptions, ParseOptions};
+use crate::session::{AcsSession, SessionAction};
+
+use super::auth::{Authenticator, Credentials};
+use super::{acs_response, envelope_from_body_with, http1, session_cookie, SESSION_COOKIE};
+
+/// How long `AcsServer` waits for the next request of a session by default,
+/// TR-069 section 3.4 asks for at least 30 seconds
+pub const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The ACS end of the HTTP transport, running one `AcsSession` per CPE.
+///
+/// Every POST is matched to its session by the `SESSION_COOKIE` cookie. A
+/// POST without a known cookie starts a new session: `new_session` is given
+/// the envelope received (normally the Inform) and returns the `AcsSession`
+/// to run, with its RPCs queued. The cookie is handed to the CPE with the
+/// first response. A session ends with a 204 No Content, after which it can
+/// be collected with `take_finished`.
+///
+/// Malformed requests and envelopes that break the session flow get a
+/// 400 Bad Request, and end the session they belong to. Requests beyond the
+/// `ParseLimits` of the parse options get a 413 Payload Too Large, before
+/// their body is read when served by `serve`.
+///
+/// A session without a request for longer than the idle timeout,
+/// `DEFAULT_IDLE_TIMEOUT` unless set with `set_idle_timeout`, is ended and
+/// moved to the finished sessions.
+///
+/// With `require_authentication`, a session only starts once the CPE has
+/// authenticated, until then it gets a 401 Unauthorized with a challenge.
+pub struct AcsServer<F> {
+    new_session: F,
+    // each session with the time of its last request
+    sessions: HashMap<String, (AcsSession, Instant)>,
+    finished: Vec<AcsSession>,
+    options: GenerateOptions,
+    parse_options: ParseOptions,
+    idle_timeout: Duration,
+    auth: Option<(Authenticator, Box<dyn Credentials + Send>)>,
+}
+
+impl<F: FnMut(&Envelope) -> AcsSession> AcsServer<F> {
+    #[must_use]
+    pub fn new(new_session: F) -> Self {
+        AcsServer {
+            new_session,
+            sessions: HashMap::new(),
+            finished: vec![],
+            options: GenerateOptions::default(),
+            parse_options: ParseOptions::default(),
+            idle_timeout: DEFAULT_IDLE_TIMEOUT,
+            auth: None,
+        }
+    }
+
+    /// Authenticate CPEs before starting their session
+    pub fn require_authentication<C: Credentials + Send + 'static>(
+        &mut self,
+        authenticator: Authenticator,
+        credentials: C,
+    ) {
+        self.auth = Some((authenticator, Box::new(credentials)));
+    }
+
+    /// The options used to generate the envelopes sent to the CPEs
+    pub fn set_generate_options(&mut self, options: GenerateOptions) {
+        self.options = options;
+    }
+
+    /// The options used to parse the envelopes received, their `ParseLimits`
+    /// also bounding the HTTP body read by `serve`
+    pub fn set_parse_options(&mut self, options: ParseOptions) {
+        self.parse_options = options;
+    }
+
+    /// How long a session may go without a request before it is ended
+    pub fn set_idle_timeout(&mut self, timeout: Duration) {
+        self.idle_timeout = timeout;
+    }
+
+    /// End the sessions idle for longer than the idle timeout, moving them
+    /// to the finished sessions, and return how many were ended.
+    /// `handle` does this before every request.
+    pub fn expire_idle(&mut self) -> usize {
+        let timeout = self.idle_timeout;
+        let idle: Vec<String> = self
+            .sessions
+            .iter()
+            .filter(|(_, (_, last_seen))| last_seen.elapsed() >= timeout)
+            .map(|(cookie, _)| cookie.clone())
+            .collect();
+        for cookie in &idle {
+            warn!("Ending idle session {cookie}");
+            self.drop_session(Some(cookie));
+        }
+        idle.len()
+    }
+
+    /// Number of sessions in progress
+    #[must_use]
+    pub fn active(&self) -> usize {
+        self.sessions.len()
+    }
+
+    /// The sessions that ended since the last call, with their exchanges
+    pub fn take_finished(&mut self) -> Vec<AcsSession> {
+        std::mem::take(&mut self.finished)
+    }
+
+    /// Handle an HTTP request from a CPE, returning the response to send
+    pub fn handle(&mut self, request: &Request<Vec<u8>>) -> Response<Vec<u8>> {
+        self.expire_idle();
+        if request.method() != Method::POST {
+            return error_response(StatusCode::METHOD_NOT_ALLOWED, "CWMP requires POST");
+        }
+        let cookie = session_cookie(request).filter(|c| self.sessions.contains_key(c));
+        if cookie.is_none() {
+            if let Some((authenticator, credentials)) = &mut self.auth {
+                let authorization = request
+                    .headers()
+                    .get(AUTHORIZATION)
+                    .and_then(|v| v.to_str().ok());
+                if let Err(e) = authenticator.verify(
+                    request.method().as_str(),
+                    &request.uri().to_string(),
+                    authorization,
+                    &**credentials,
+                ) {
+                    let mut response = error_response(StatusCode::UNAUTHORIZED, &e.to_string());
+                    if let Ok(v) = http::HeaderValue::from_str(&authenticator.challenge_for(&e)) {
+                        response.headers_mut().insert(WWW_AUTHENTICATE, v);
+                    }
+                    return response;
+                }
+            }
+        }
+        let envelope = match envelope_from_body_with(request.body(), &self.parse_options) {
+            Ok(envelope) => envelope,
+            Err(e) => {
+                self.drop_session(cookie.as_deref());
+                let status = match e {
+                    Cwmp::LimitExceeded(_) => StatusCode::PAYLOAD_TOO_LARGE,
+                    _ => StatusCode::BAD_REQUEST,
+                };
+                return error_response(status, &e.to_string());
+            }
+        };
+        let (cookie, new) = match (cookie, &envelope) {
+            (Some(cookie), _) => (cookie, false),
+            (None, Some(envelope)) => {
+                let cookie = format!("{:016x}", rand::random::<u64>());
+                let session = (self.new_session)(envelope);
+                self.sessions
+                    .insert(cookie.clone(), (session, Instant::now()));
+                (cookie, true)
+            }
+            (None, None) => {
+                return error_response(
+                    StatusCode::BAD_REQUEST,
+                    "an empty POST outside of a session",
+                )
+            }
+        };
+        let Some((session, last_seen)) = self.sessions.get_mut(&cookie) else {
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "lost session");
+        };
+        *last_seen = Instant::now();
+        let action = match session.handle(envelope.as_ref()) {
+            Ok(action) => action,
+            Err(e) => {
+                warn!("Ending session {cookie}: {e}");
+                self.drop_session(Some(&cookie));
+                return error_response(StatusCode::BAD_REQUEST, &e.to_string());
+            }
+        };
+        if action == SessionAction::End {
+            self.drop_session(Some(&cookie));
+        }
+        match acs_response(&action, new.then_some(cookie.as_str()), &self.options) {
+            Ok(response) => response,
+            Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+        }
+    }
+
+    /// Serve the requests of a TCP connection, until the CPE closes it
+    ///
+    /// # Errors
+    ///
+    /// Returns an `error::Transport` on I/O errors and malformed HTTP. A
+    /// request too large to be read gets an error response before the
+    /// connection is given up.
+    pub fn serve(&mut self, stream: &TcpStream) -> Result<(), Transport> {
+        let mut reader = BufReader::new(stream);
+        let mut writer = stream;
+        let max_body = self.parse_options.limits.max_bytes;
+        loop {
+            let request = match http1::read_request_with(&mut reader, max_body) {
+                Ok(Some(request)) => request,
+                Ok(None) => break,
+                Err(e) => {
+                    let status = match e {
+                        Transport::TooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+                        Transport::LineTooLong(_) | Transport::TooManyHeaders(_) => {
+                            StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE
+                        }
+                        _ => return Err(e),
+                    };
+                    let mut response = error_response(status, &e.to_string());
+                    response
+                        .headers_mut()
+                        .insert(CONNECTION, http::HeaderValue::from_static("close"));
+                    // the peer may not be reading any more
+                    let _ = http1::write_response(&mut writer, &response);
+                    return Err(e);
+                }
+            };
+            let response = self.handle(&request);
+            http1::write_response(&mut writer, &response)?;
+            if http1::wants_close(request.headers()) {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn drop_session(&mut self, cookie: Option<&str>) {
+        if let Some((session, _)) = cookie.and_then(|c| self.sessions.remove(c)) {
+            self.finished.push(session);
+        }
+    }
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response<Vec<u8>> {
+    let mut response = Response::new(message.as_bytes().to_vec());
+    *response.status_mut() = status;
+    response.headers_mut().insert(
+        CONTENT_TYPE,
+        http::HeaderValue::from_static("text/plain; charset=utf-8"),
+    );
+    // forget the cookie, the session is gone
+    if let Ok(v) = http::HeaderValue::from_str(&format!("{SESSION_COOKIE}=; Max-Age=0")) {
+        response.headers_mut().insert(SET_COOKIE, v);
+    }
+    response
+}
@@ -0,0 +1,533 @@
+//! HTTP Digest (RFC 7616) and Basic (RFC 7617) authentication.
+//!
+//! `Authenticator` is the server end: the ACS authenticating CPEs, or a CPE
+//! authenticating Connection Requests. It issues `WWW-Authenticate`
+//! challenges and verifies `Authorization` headers against `Credentials`,
+//! refusing expired nonces, reused nonce counts and digests for another
+//! URI. `ClientAuth` is the client end, answering the challenges it is
+//! given.
+use std::collections::HashMap;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use md5::Md5;
+use sha2::{Digest, Sha256};
+
+use crate::error::Auth;
+use crate::protocol::DeviceId;
+
+/// Where an `Authenticator` finds the password of a user
+pub trait Credentials {
+    /// The password of `username`, `None` if the user is unknown
+    fn password(&self, username: &str) -> Option<String>;
+}
+
+impl Credentials for HashMap<String, String> {
+    fn password(&self, username: &str) -> Option<String> {
+        self.get(username).cloned()
+    }
+}
+
+/// The user name a CPE usually authenticates with, "<OUI>-<SerialNumber>",
+/// as recommended by TR-069 section 3.4.4
+#[must_use]
+pub fn device_username(device_id: &DeviceId) -> String {
+    format!("{}-{}", device_id.oui.0, device_id.serial_number.0)
+}
+
+/// The digest algorithms TR-069 peers use
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Algorithm {
+    Md5,
+    Sha256,
+}
+
+impl Algorithm {
+    fn hash(self, data: &str) -> String {
+        let bytes = match self {
+            Algorithm::Md5 => Md5::digest(data.as_bytes()).to_vec(),
+            Algorithm::Sha256 => Sha256::digest(data.as_bytes()).to_vec(),
+        };
+        bytes.iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    fn from_name(name: &str) -> Result<Self, Auth> {
+        match name.to_ascii_uppercase().as_str() {
+            "MD5" => Ok(Algorithm::Md5),
+            "SHA-256" => Ok(Algorithm::Sha256),
+            _ => Err(Auth::Unsupported(name.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Algorithm::Md5 => write!(f, "MD5"),
+            Algorithm::Sha256 => write!(f, "SHA-256"),
+        }
+    }
+}
+
+/// A Digest challenge, as found in a `WWW-Authenticate` header
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct DigestChallenge {
+    pub realm: String,
+    pub nonce: String,
+    pub opaque: Option<String>,
+    pub algorithm: Algorithm,
+    /// true if qop="auth" was offered, false for the RFC 2069 compatible mode
+    pub qop_auth: bool,
+    /// the nonce of an earlier challenge expired, the credentials were right
+    pub stale: bool,
+}
+
+impl DigestChallenge {
+    /// Parse the value of a `WWW-Authenticate: Digest ...` header
+    ///
+    /// # Errors
+    ///
+    /// Returns an `error::Auth` for other schemes and malformed challenges
+    pub fn parse(header: &str) -> Result<Self, Auth> {
+        let params = scheme_params(header, "Digest")?;
+        let get = |name: &str| params.get(name).cloned();
+        let qop_auth = match get("qop") {
+            Some(qop) if qop.split(',').any(|q| q.trim() == "auth") => true,
+            Some(qop) => return Err(Auth::Unsupported(format!("qop {qop}"))),
+            None => false,
+        };
+        Ok(DigestChallenge {
+            realm: get("realm").ok_or_else(|| Auth::Malformed(header.to_string()))?,
+            nonce: get("nonce").ok_or_else(|| Auth::Malformed(header.to_string()))?,
+            opaque: get("opaque"),
+            algorithm: get("algorithm").map_or(Ok(Algorithm::Md5), |a| Algorithm::from_name(&a))?,
+            qop_auth,
+            stale: get("stale").is_some_and(|s| s.eq_ignore_ascii_case("true")),
+        })
+    }
+}
+
+impl fmt::Display for DigestChallenge {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Digest realm=\"{}\", nonce=\"{}\", algorithm={}",
+            self.realm, self.nonce, self.algorithm
+        )?;
+        if self.qop_auth {
+            write!(f, ", qop=\"auth\"")?;
+        }
+        if let Some(opaque) = &self.opaque {
+            write!(f, ", opaque=\"{opaque}\"")?;
+        }
+        if self.stale {
+            write!(f, ", stale=true")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct NonceState {
+    issued: Instant,
+    // highest nonce count accepted so far
+    nc: u32,
+}
+
+/// The server end of HTTP authentication
+///
+/// Nonces are valid for `nonce_lifetime` (5 minutes by default), and every
+/// nonce count is accepted once, so a captured `Authorization` header cannot
+/// be replayed. At most `max_nonces` (1024 by default) are remembered, the
+/// oldest being forgotten first, so unanswered challenges cannot pile up.
+#[derive(Debug, Clone)]
+pub struct Authenticator {
+    realm: String,
+    algorithm: Algorithm,
+    allow_basic: bool,
+    nonce_lifetime: Duration,
+    max_nonces: usize,
+    nonces: HashMap<String, NonceState>,
+}
+
+impl Authenticator {
+    #[must_use]
+    pub fn new(realm: &str) -> Self {
+        Authenticator {
+            realm: realm.to_string(),
+            algorithm: Algorithm::Md5,
+            allow_basic: false,
+            nonce_lifetime: Duration::from_secs(300),
+            max_nonces: 1024,
+            nonces: HashMap::new(),
+        }
+    }
+    #[must_use]
+    pub fn algorithm(mut self, algorithm: Algorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+    /// Also accept Basic authentication, which sends the password in clear
+    /// and should only be used over TLS
+    #[must_use]
+    pub fn allow_basic(mut self, allow_basic: bool) -> Self {
+        self.allow_basic = allow_basic;
+        self
+    }
+    #[must_use]
+    pub fn nonce_lifetime(mut self, nonce_lifetime: Duration) -> Self {
+        self.nonce_lifetime = nonce_lifetime;
+        self
+    }
+    #[must_use]
+    pub fn max_nonces(mut self, max_nonces: usize) -> Self {
+        self.max_nonces = max_nonces.max(1);
+        self
+    }
+
+    /// A fresh Digest challenge, for a `WWW-Authenticate` header
+    pub fn challenge(&mut self) -> String {
+        self.new_challenge(false)
+    }
+
+    /// The `WWW-Authenticate` header to answer a failed `verify` with
+    pub fn challenge_for(&mut self, error: &Auth) -> String {
+        self.new_challenge(*error == Auth::StaleNonce)
+    }
+
+    /// A Basic challenge, for a `WWW-Authenticate` header
+    #[must_use]
+    pub fn basic_challenge(&self) -> String {
+        format!("Basic realm=\"{}\"", self.realm)
+    }
+
+    /// Verify the `Authorization` header of a request made with `method` to
+    /// `uri`, its request target, returning the authenticated user name
+    ///
+    /// # Errors
+    ///
+    /// Returns an `error::Auth` if the header is missing, malformed, is for
+    /// another URI, uses an expired or unknown nonce or an already used
+    /// nonce count, or if the credentials are wrong
+    pub fn verify<C: Credentials + ?Sized>(
+        &mut self,
+        method: &str,
+        uri: &str,
+        authorization: Option<&str>,
+        credentials: &C,
+    ) -> Result<String, Auth> {
+        let authorization = authorization.ok_or(Auth::Missing)?.trim();
+        if authorization.get(..6).is_some_and(|s| s.eq_ignore_ascii_case("basic ")) {
+            if !self.allow_basic {
+                return Err(Auth::Unsupported("Basic".to_string()));
+            }
+            return verify_basic(&authorization[6..], credentials);
+        }
+
+        let params = scheme_params(authorization, "Digest")?;
+        let get = |name: &str| {
+            params
+                .get(name)
+                .map(String::as_str)
+                .ok_or_else(|| Auth::Malformed(format!("{name} is missing")))
+        };
+        let username = get("username")?;
+        let nonce = get("nonce")?;
+        let digest_uri = get("uri")?;
+        let response = get("response")?;
+        if get("realm")? != self.realm {
+            return Err(Auth::Rejected(username.to_string()));
+        }
+        // RFC 7616 section 3.4.6, the digest must be for the URI requested
+        if !same_uri(digest_uri, uri) {
+            return Err(Auth::UriMismatch(digest_uri.to_string()));
+        }
+        if let Some(algorithm) = params.get("algorithm") {
+            if Algorithm::from_name(algorithm)? != self.algorithm {
+                return Err(Auth::Unsupported(algorithm.clone()));
+            }
+        }
+        let state = self.nonces.get(nonce).ok_or(Auth::UnknownNonce)?;
+        if state.issued.elapsed() > self.nonce_lifetime {
+            self.nonces.remove(nonce);
+            self.forget_expired();
+            return Err(Auth::StaleNonce);
+        }
+        let qop = match params.get("qop") {
+            Some(qop) if qop == "auth" => {
+                let nc = u32::from_str_radix(get("nc")?, 16)
+                    .map_err(|_| Auth::Malformed("nc".to_string()))?;
+                if nc <= state.nc {
+                    return Err(Auth::Replay(nc));
+                }
+                Some((nc, get("cnonce")?))
+            }
+            Some(qop) => return Err(Auth::Unsupported(format!("qop {qop}"))),
+            None => None,
+        };
+
+        let password = credentials
+            .password(username)
+            .ok_or_else(|| Auth::Rejected(username.to_string()))?;
+        let expected = digest_response(
+            self.algorithm,
+            &[username, &self.realm, &password],
+            &[method, digest_uri],
+            nonce,
+            qop,
+        );
+        if !constant_time_eq(expected.as_bytes(), response.as_bytes()) {
+            return Err(Auth::Rejected(username.to_string()));
+        }
+        if let (Some((nc, _)), Some(state)) = (qop, self.nonces.get_mut(nonce)) {
+            state.nc = nc;
+        } else {
+            // without a nonce count, a nonce can only be used once
+            self.nonces.remove(nonce);
+        }
+        Ok(username.to_string())
+    }
+
+    fn new_challenge(&mut self, stale: bool) -> String {
+        self.forget_expired();
+        while self.nonces.len() >= self.max_nonces {
+            let oldest = self
+                .nonces
+                .iter()
+                .min_by_key(|(_, s)| s.issued)
+                .map(|(nonce, _)| nonce.clone());
+            if let Some(oldest) = oldest {
+                self.nonces.remove(&oldest);
+            }
+        }
+        let nonce = format!("{:032x}", rand::random::<u128>());
+        self.nonces.insert(
+            nonce.clone(),
+            NonceState {
+                issued: Instant::now(),
+                nc: 0,
+            },
+        );
+        DigestChallenge {
+            realm: self.realm.clone(),
+            nonce,
+            opaque: None,
+            algorithm: self.algorithm,
+            qop_auth: true,
+            stale,
+        }
+        .to_string()
+    }
+
+    // expired nonces are kept for another lifetime, to tell their users
+    // they are stale rather than unknown
+    fn forget_expired(&mut self) {
+        let lifetime = self.nonce_lifetime * 2;
+        self.nonces.retain(|_, s| s.issued.elapsed() <= lifetime);
+    }
+}
+
+// the uri of a digest is the request target, absolute or only a path, see
+// RFC 7616 section 3.4.6
+fn same_uri(digest_uri: &str, request_uri: &str) -> bool {
+    if digest_uri == request_uri {
+        return true;
+    }
+    let (Ok(digest_uri), Ok(request_uri)) = (
+        digest_uri.parse::<http::Uri>(),
+        request_uri.parse::<http::Uri>(),
+    ) else {
+        return false;
+    };
+    let authorities_match = match (digest_uri.authority(), request_uri.authority()) {
+        (Some(a), Some(b)) => a == b,
+        _ => true,
+    };
+    authorities_match && digest_uri.path_and_query() == request_uri.path_and_query()
+}
+
+/// The client end of HTTP authentication
+///
+/// Give it the `WWW-Authenticate` header of a 401 response with `challenge`,
+/// then put what `authorization` returns in the `Authorization` header of
+/// every following request. The nonce count goes up with every request.
+#[derive(Debug, Clone)]
+pub struct ClientAuth {
+    username: String,
+    password: String,
+    challenge: Option<Challenge>,
+    nc: u32,
+}
+
+#[derive(Debug, Clone)]
+enum Challenge {
+    Basic,
+    Digest(DigestChallenge),
+}
+
+impl ClientAuth {
+    #[must_use]
+    pub fn new(username: &str, password: &str) -> Self {
+        ClientAuth {
+            username: username.to_string(),
+            password: password.to_string(),
+            challenge: None,
+            nc: 0,
+        }
+    }
+
+    /// Take the challenge of a `WWW-Authenticate` header
+    ///
+    /// # Errors
+    ///
+    /// Returns an `error::Auth` for unsupported schemes and malformed challenges
+    pub fn challenge(&mut self, www_authenticate: &str) -> Result<(), Auth> {
+        let header = www_authenticate.trim();
+        self.challenge = if header.get(..5).is_some_and(|s| s.eq_ignore_ascii_case("basic")) {
+            Some(Challenge::Basic)
+        } else {
+            Some(Challenge::Digest(DigestChallenge::parse(header)?))
+        };
+        self.nc = 0;
+        Ok(())
+    }
+
+    /// The `Authorization` header for a request, `None` before a challenge was taken
+    pub fn authorization(&mut self, method: &str, uri: &str) -> Option<String> {
+        match self.challenge.as_ref()? {
+            Challenge::Basic => Some(basic_authorization(&self.username, &self.password)),
+            Challenge::Digest(challenge) => {
+                self.nc += 1;
+                let cnonce = format!("{:016x}", rand::random::<u64>());
+                Some(digest_authorization(
+                    challenge,
+                    &self.username,
+                    &self.password,
+                    method,
+                    uri,
+                    self.nc,
+                    &cnonce,
+                ))
+            }
+        }
+    }
+}
+
+/// The `Authorization` header value for Basic authentication
+#[must_use]
+pub fn basic_authorization(username: &str, password: &str) -> String {
+    format!(
+        "Basic {}",
+        STANDARD.encode(format!("{username}:{password}"))
+    )
+}
+
+/// The `Authorization` header value answering a Digest challenge, with the
+/// nonce count `nc` and client nonce `cnonce` (only used with qop="auth")
+#[must_use]
+pub fn digest_authorization(
+    challenge: &DigestChallenge,
+    username: &str,
+    password: &str,
+    method: &str,
+    uri: &str,
+    nc: u32,
+    cnonce: &str,
+) -> String {
+    let qop = challenge.qop_auth.then_some((nc, cnonce));
+    let response = digest_response(
+        challenge.algorithm,
+        &[username, &challenge.realm, password],
+        &[method, uri],
+        &challenge.nonce,
+        qop,
+    );
+    let mut header = format!(
+        "Digest username=\"{username}\", realm=\"{}\", nonce=\"{}\", uri=\"{uri}\", algorithm={}, response=\"{response}\"",
+        challenge.realm, challenge.nonce, challenge.algorithm
+    );
+    if qop.is_some() {
+        header.push_str(&format!(", qop=auth, nc={nc:08x}, cnonce=\"{cnonce}\""));
+    }
+    if let Some(opaque) = &challenge.opaque {
+        header.push_str(&format!(", opaque=\"{opaque}\""));
+    }
+    header
+}
+
+fn digest_response(
+    algorithm: Algorithm,
+    a1: &[&str],
+    a2: &[&str],
+    nonce: &str,
+    qop: Option<(u32, &str)>,
+) -> String {
+    let ha1 = algorithm.hash(&a1.join(":"));
+    let ha2 = algorithm.hash(&a2.join(":"));
+    match qop {
+        Some((nc, cnonce)) => {
+            algorithm.hash(&format!("{ha1}:{nonce}:{nc:08x}:{cnonce}:auth:{ha2}"))
+        }
+        None => algorithm.hash(&format!("{ha1}:{nonce}:{ha2}")),
+    }
+}
+
+fn verify_basic<C: Credentials + ?Sized>(encoded: &str, credentials: &C) -> Result<String, Auth> {
+    let decoded = STANDARD
+        .decode(encoded.trim())
+        .ok()
+        .and_then(|d| String::from_utf8(d).ok())
+        .ok_or_else(|| Auth::Malformed("Basic credentials".to_string()))?;
+    let (username, password) = decoded
+        .split_once(':')
+        .ok_or_else(|| Auth::Malformed("Basic credentials".to_string()))?;
+    match credentials.password(username) {
+        Some(p) if constant_time_eq(p.as_bytes(), password.as_bytes()) => Ok(username.to_string()),
+        _ => Err(Auth::Rejected(username.to_string())),
+    }
+}
+
+// the name=value parameters following the scheme name, values unquoted
+fn scheme_params(header: &str, scheme: &str) -> Result<HashMap<String, String>, Auth> {
+    let header = header.trim();
+    let rest = match header.split_once(char::is_whitespace) {
+        Some((s, rest)) if s.eq_ignore_ascii_case(scheme) => rest,
+        _ => return Err(Auth::Unsupported(header.to_string())),
+    };
+    let mut params = HashMap::new();
+    let mut chars = rest.chars().peekable();
+    loop {
+        while chars.next_if(|c| c.is_whitespace() || *c == ',').is_some() {}
+        let name: String = std::iter::from_fn(|| chars.next_if(|c| *c != '=')).collect();
+        if name.trim().is_empty() {
+            break;
+        }
+        if chars.next() != Some('=') {
+            return Err(Auth::Malformed(header.to_string()));
+        }
+        let mut value = String::new();
+        if chars.next_if_eq(&'"').is_some() {
+            loop {
+                match chars.next() {
+                    Some('\\') => value.extend(chars.next()),
+                    Some('"') => break,
+                    Some(c) => value.push(c),
+                    None => return Err(Auth::Malformed(header.to_string())),
+                }
+            }
+        } else {
+            value = std::iter::from_fn(|| chars.next_if(|c| *c != ','))
+                .collect::<String>()
+                .trim()
+                .to_string();
+        }
+        params.insert(name.trim().to_ascii_lowercase(), value);
+    }
+    Ok(params)
+}
+
+pub(super) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
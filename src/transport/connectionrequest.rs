@@ -0,0 +1,404 @@
+//! Connection Requests, the way an ACS asks a CPE to start a session with
+//! a "6 CONNECTION REQUEST" event.
+//!
+//! Over HTTP (TR-069 section 3.2.2) the ACS sends an authenticated GET to
+//! the `ConnectionRequestURL` of the CPE, with `send_connection_request`,
+//! and the CPE answers it with a `ConnectionRequestServer`. A CPE behind a
+//! NAT gateway is reached over UDP instead (TR-069 Annex G), with a signed
+//! `UdpConnectionRequest` received by a `UdpConnectionRequestListener`.
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::io::BufReader;
+use std::net::{TcpStream, ToSocketAddrs, UdpSocket};
+use std::time::Duration;
+
+use hmac::{Hmac, Mac};
+use http::header::{AUTHORIZATION, WWW_AUTHENTICATE};
+use http::{Method, Request, Response, StatusCode, Uri};
+use log::warn;
+use sha1::Sha1;
+
+use crate::error::{Auth, Cwmp, Transport};
+use crate::protocol::Inform;
+
+use super::auth::{constant_time_eq, Authenticator, ClientAuth, Credentials};
+use super::http1;
+
+/// The `ConnectionRequestURL` a CPE reported in an Inform, from either
+/// the Device or the InternetGatewayDevice data model
+#[must_use]
+pub fn connection_request_url(inform: &Inform) -> Option<&str> {
+    management_server_value(inform, "ConnectionRequestURL")
+}
+
+/// The `UDPConnectionRequestAddress` ("host:port") a CPE behind a NAT
+/// gateway reported in an Inform
+#[must_use]
+pub fn udp_connection_request_address(inform: &Inform) -> Option<&str> {
+    management_server_value(inform, "UDPConnectionRequestAddress")
+}
+
+fn management_server_value<'a>(inform: &'a Inform, name: &str) -> Option<&'a str> {
+    let suffix = format!(".ManagementServer.{name}");
+    inform
+        .parameter_list
+        .iter()
+        .find(|p| p.name.0.ends_with(&suffix))
+        .map(|p| p.value.0.as_str())
+        .filter(|v| !v.is_empty())
+}
+
+/// Send a Connection Request to the CPE at `url`, answering its
+/// authentication challenge with `auth`
+///
+/// # Errors
+///
+/// Returns an `error::Cwmp` if `url` is not an http URL, on I/O errors,
+/// if the challenge cannot be answered, or with `error::Transport::Status`
+/// if the CPE refuses the request
+pub fn send_connection_request(
+    url: &str,
+    auth: &mut ClientAuth,
+    timeout: Duration,
+) -> Result<(), Cwmp> {
+    let uri: Uri = url
+        .parse()
+        .map_err(|_| Transport::Malformed(format!("invalid URL {url}")))?;
+    if uri.scheme_str() != Some("http") {
+        return Err(Transport::Malformed(format!("not an http URL: {url}")).into());
+    }
+    let host = uri
+        .host()
+        .ok_or_else(|| Transport::Malformed(format!("no host in {url}")))?;
+    let address = (host.trim_matches(['[', ']']), uri.port_u16().unwrap_or(80))
+        .to_socket_addrs()
+        .map_err(Transport::Io)?
+        .next()
+        .ok_or_else(|| Transport::Malformed(format!("cannot resolve {host}")))?;
+    let target = uri.path_and_query().map_or("/", |p| p.as_str()).to_string();
+
+    let mut stream: Option<(TcpStream, BufReader<TcpStream>)> = None;
+    // the first attempt may be refused with a challenge, the second may not
+    for _ in 0..2 {
+        if stream.is_none() {
+            let s = TcpStream::connect_timeout(&address, timeout).map_err(Transport::Io)?;
+            s.set_read_timeout(Some(timeout)).map_err(Transport::Io)?;
+            let reader = BufReader::new(s.try_clone().map_err(Transport::Io)?);
+            stream = Some((s, reader));
+        }
+        let Some((writer, reader)) = stream.as_mut() else {
+            continue;
+        };
+        let mut builder = Request::builder().method(Method::GET).uri(&uri);
+        if let Some(authorization) = auth.authorization("GET", &target) {
+            builder = builder.header(AUTHORIZATION, authorization);
+        }
+        let request = builder
+            .body(vec![])
+            .map_err(|e| Transport::Malformed(e.to_string()))?;
+        http1::write_request(writer, &request)?;
+        let response = http1::read_response(reader)?;
+        match response.status() {
+            StatusCode::OK | StatusCode::NO_CONTENT => return Ok(()),
+            StatusCode::UNAUTHORIZED => {
+                let challenge = response
+                    .headers()
+                    .get(WWW_AUTHENTICATE)
+                    .and_then(|v| v.to_str().ok())
+                    .ok_or(Auth::Missing)?;
+                auth.challenge(challenge)?;
+                if http1::wants_close(response.headers()) {
+                    stream = None;
+                }
+            }
+            status => return Err(Transport::Status(status.as_u16()).into()),
+        }
+    }
+    Err(Transport::Status(401).into())
+}
+
+/// The CPE end of HTTP Connection Requests
+///
+/// Every GET must authenticate against `credentials`, it is answered with
+/// a 200 OK, and `take_request` then tells the CPE to start a session.
+pub struct ConnectionRequestServer<C> {
+    authenticator: Authenticator,
+    credentials: C,
+    requested: bool,
+}
+
+impl<C: Credentials> ConnectionRequestServer<C> {
+    #[must_use]
+    pub fn new(authenticator: Authenticator, credentials: C) -> Self {
+        ConnectionRequestServer {
+            authenticator,
+            credentials,
+            requested: false,
+        }
+    }
+
+    /// True if a Connection Request was accepted since the last call
+    pub fn take_request(&mut self) -> bool {
+        std::mem::take(&mut self.requested)
+    }
+
+    /// Handle an HTTP request from the ACS, returning the response to send
+    pub fn handle<B>(&mut self, request: &Request<B>) -> Response<Vec<u8>> {
+        if request.method() != Method::GET {
+            return status_response(StatusCode::METHOD_NOT_ALLOWED);
+        }
+        let authorization = request
+            .headers()
+            .get(AUTHORIZATION)
+            .and_then(|v| v.to_str().ok());
+        match self.authenticator.verify(
+            "GET",
+            &request.uri().to_string(),
+            authorization,
+            &self.credentials,
+        ) {
+            Ok(_) => {
+                self.requested = true;
+                status_response(StatusCode::OK)
+            }
+            Err(e) => {
+                let mut response = status_response(StatusCode::UNAUTHORIZED);
+                if let Ok(v) = http::HeaderValue::from_str(&self.authenticator.challenge_for(&e)) {
+                    response.headers_mut().insert(WWW_AUTHENTICATE, v);
+                }
+                response
+            }
+        }
+    }
+
+    /// Serve the requests of a TCP connection, until the ACS closes it
+    ///
+    /// # Errors
+    ///
+    /// Returns an `error::Transport` on I/O errors and malformed HTTP
+    pub fn serve(&mut self, stream: &TcpStream) -> Result<(), Transport> {
+        let mut reader = BufReader::new(stream);
+        let mut writer = stream;
+        while let Some(request) = http1::read_request(&mut reader)? {
+            let response = self.handle(&request);
+            http1::write_response(&mut writer, &response)?;
+            if http1::wants_close(request.headers()) {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn status_response(status: StatusCode) -> Response<Vec<u8>> {
+    let mut response = Response::new(vec![]);
+    *response.status_mut() = status;
+    response
+}
+
+/// A UDP Connection Request, as defined by TR-069 Annex G
+///
+/// `sig` is the hex encoded HMAC-SHA1 of `ts`, `id`, `un` and `cn`, keyed
+/// with the Connection Request password.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct UdpConnectionRequest {
+    /// seconds since the Unix epoch
+    pub ts: u64,
+    /// unique for every request, retransmissions keep it
+    pub id: u32,
+    pub un: String,
+    pub cn: String,
+    pub sig: String,
+}
+
+impl UdpConnectionRequest {
+    /// A request signed with `password`, with a random `cn`
+    #[must_use]
+    pub fn new(ts: u64, id: u32, username: &str, password: &str) -> Self {
+        let cn = format!("{:016x}", rand::random::<u64>());
+        let sig = signature(ts, id, username, &cn, password);
+        UdpConnectionRequest {
+            ts,
+            id,
+            un: username.to_string(),
+            cn,
+            sig,
+        }
+    }
+
+    /// True if `sig` was made with `password`
+    #[must_use]
+    pub fn verify(&self, password: &str) -> bool {
+        let expected = signature(self.ts, self.id, &self.un, &self.cn, password);
+        // the hex digest is lower case, compared in constant time
+        constant_time_eq(expected.as_bytes(), self.sig.to_ascii_lowercase().as_bytes())
+    }
+
+    /// The datagram to send to the CPE at `address` ("host:port")
+    #[must_use]
+    pub fn to_message(&self, address: &str) -> String {
+        format!(
+            "GET http://{address}?ts={}&id={}&un={}&cn={}&sig={} HTTP/1.1\r\nHost: {address}\r\n\r\n",
+            self.ts,
+            self.id,
+            percent_encode(&self.un),
+            percent_encode(&self.cn),
+            self.sig
+        )
+    }
+
+    /// Parse a received datagram
+    ///
+    /// # Errors
+    ///
+    /// Returns `error::Transport::Malformed` if the datagram is not a UDP
+    /// Connection Request
+    pub fn parse(datagram: &[u8]) -> Result<Self, Transport> {
+        let malformed = || Transport::Malformed(String::from_utf8_lossy(datagram).to_string());
+        let message = std::str::from_utf8(datagram).map_err(|_| malformed())?;
+        let start = message.lines().next().ok_or_else(malformed)?;
+        let mut parts = start.split_whitespace();
+        let (Some("GET"), Some(target), Some(version)) = (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(malformed());
+        };
+        if !version.starts_with("HTTP/1.") {
+            return Err(malformed());
+        }
+        let (_, query) = target.split_once('?').ok_or_else(malformed)?;
+        let params: HashMap<&str, String> = query
+            .split('&')
+            .filter_map(|p| p.split_once('='))
+            .map(|(k, v)| (k, percent_decode(v)))
+            .collect();
+        let get = |name: &str| params.get(name).cloned().ok_or_else(malformed);
+        Ok(UdpConnectionRequest {
+            ts: get("ts")?.parse().map_err(|_| malformed())?,
+            id: get("id")?.parse().map_err(|_| malformed())?,
+            un: get("un")?,
+            cn: get("cn")?,
+            sig: get("sig")?,
+        })
+    }
+
+    /// Send the request to the CPE at `address` ("host:port")
+    ///
+    /// # Errors
+    ///
+    /// Returns an `error::Transport` on I/O errors
+    pub fn send(&self, socket: &UdpSocket, address: &str) -> Result<(), Transport> {
+        socket.send_to(self.to_message(address).as_bytes(), address)?;
+        Ok(())
+    }
+}
+
+/// The CPE end of UDP Connection Requests
+///
+/// A request is only accepted if it is signed with the password of
+/// `username`, if its `ts` is not older than the last accepted one and its
+/// `id` differs from it, so that retransmissions and replays are ignored.
+#[derive(Debug, Clone)]
+pub struct UdpConnectionRequestListener {
+    username: String,
+    password: String,
+    last: Option<(u64, u32)>,
+}
+
+impl UdpConnectionRequestListener {
+    #[must_use]
+    pub fn new(username: &str, password: &str) -> Self {
+        UdpConnectionRequestListener {
+            username: username.to_string(),
+            password: password.to_string(),
+            last: None,
+        }
+    }
+
+    /// Check a received datagram
+    ///
+    /// # Errors
+    ///
+    /// Returns an `error::Cwmp` if the datagram is malformed, is not signed
+    /// for this CPE, or repeats an earlier request
+    pub fn accept(&mut self, datagram: &[u8]) -> Result<UdpConnectionRequest, Cwmp> {
+        let request = UdpConnectionRequest::parse(datagram)?;
+        if request.un != self.username || !request.verify(&self.password) {
+            return Err(Auth::Rejected(request.un).into());
+        }
+        if let Some((ts, id)) = self.last {
+            if request.ts < ts || request.id == id {
+                return Err(Auth::Replay(request.id).into());
+            }
+        }
+        self.last = Some((request.ts, request.id));
+        Ok(request)
+    }
+
+    /// Wait on `socket` for the next acceptable request, ignoring the others
+    ///
+    /// # Errors
+    ///
+    /// Returns an `error::Transport` on I/O errors, including a read timeout
+    /// set on the socket
+    pub fn recv(&mut self, socket: &UdpSocket) -> Result<UdpConnectionRequest, Transport> {
+        let mut buf = [0u8; 2048];
+        loop {
+            let (len, from) = socket.recv_from(&mut buf)?;
+            match self.accept(&buf[..len]) {
+                Ok(request) => return Ok(request),
+                Err(e) => warn!("Ignoring UDP Connection Request from {from}: {e}"),
+            }
+        }
+    }
+}
+
+fn signature(ts: u64, id: u32, un: &str, cn: &str, password: &str) -> String {
+    // HMAC accepts keys of any length
+    let mut mac = Hmac::<Sha1>::new_from_slice(password.as_bytes()).expect("any key length");
+    mac.update(format!("{ts}{id}{un}{cn}").as_bytes());
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .fold(String::new(), |mut s, b| {
+            let _ = write!(s, "{b:02x}");
+            s
+        })
+}
+
+fn percent_encode(value: &str) -> String {
+    value.bytes().fold(String::new(), |mut s, b| {
+        if b.is_ascii_alphanumeric() || b"-._~".contains(&b) {
+            s.push(b as char);
+        } else {
+            let _ = write!(s, "%{b:02X}");
+        }
+        s
+    })
+}
+
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(b)) => {
+                decoded.push(b);
+                i += 3;
+            }
+            (b'+', _) => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            (b, _) => {
+                decoded.push(b);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
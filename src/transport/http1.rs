@@ -0,0 +1,310 @@
+//! A small blocking HTTP/1.1 implementation, enough to carry a TR-069
+//! session over a `TcpStream`: Content-Length and chunked bodies, and
+//! persistent connections.
+//!
+//! Everything read is bounded: bodies by a maximum size, `ParseLimits::max_bytes`
+//! unless told otherwise, and the head by `MAX_LINE_LENGTH` and `MAX_HEADERS`.
+use std::io::{BufRead, Read, Write};
+
+use http::header::{CONNECTION, CONTENT_LENGTH, HOST, TRANSFER_ENCODING};
+use http::{HeaderMap, HeaderName, HeaderValue, Method, Request, Response, StatusCode, Version};
+
+use crate::error::Transport;
+use crate::protocol::ParseLimits;
+
+/// The longest start or header line read, in bytes
+pub const MAX_LINE_LENGTH: usize = 8 * 1024;
+
+/// The most headers a message may have
+pub const MAX_HEADERS: usize = 100;
+
+/// Read the next request of a connection, `None` if the peer closed it,
+/// with a body of at most `ParseLimits::max_bytes`
+///
+/// # Errors
+///
+/// Returns an `error::Transport` on I/O errors and malformed requests
+pub fn read_request<R: BufRead>(reader: &mut R) -> Result<Option<Request<Vec<u8>>>, Transport> {
+    read_request_with(reader, ParseLimits::default().max_bytes)
+}
+
+/// Read the next request of a connection, `None` if the peer closed it,
+/// with a body of at most `max_body` bytes
+///
+/// # Errors
+///
+/// Returns an `error::Transport` on I/O errors and malformed requests,
+/// `error::Transport::TooLarge` for a larger body, before reading it
+pub fn read_request_with<R: BufRead>(
+    reader: &mut R,
+    max_body: usize,
+) -> Result<Option<Request<Vec<u8>>>, Transport> {
+    let Some((start, headers)) = read_head(reader)? else {
+        return Ok(None);
+    };
+    let mut parts = start.split_whitespace();
+    let (Some(method), Some(uri), Some(version)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(malformed(&start));
+    };
+    let mut request = Request::builder()
+        .method(Method::from_bytes(method.as_bytes()).map_err(|_| malformed(&start))?)
+        .uri(uri)
+        .version(parse_version(version)?)
+        .body(vec![])
+        .map_err(|e| Transport::Malformed(e.to_string()))?;
+    *request.body_mut() = read_body(reader, &headers, false, max_body)?;
+    *request.headers_mut() = headers;
+    Ok(Some(request))
+}
+
+/// Read a response, with a body of at most `ParseLimits::max_bytes`
+///
+/// # Errors
+///
+/// Returns an `error::Transport` on I/O errors and malformed responses,
+/// including a connection closed before the response
+pub fn read_response<R: BufRead>(reader: &mut R) -> Result<Response<Vec<u8>>, Transport> {
+    read_response_with(reader, ParseLimits::default().max_bytes)
+}
+
+/// Read a response, with a body of at most `max_body` bytes
+///
+/// # Errors
+///
+/// Returns an `error::Transport` on I/O errors and malformed responses,
+/// including a connection closed before the response, and
+/// `error::Transport::TooLarge` for a larger body
+pub fn read_response_with<R: BufRead>(
+    reader: &mut R,
+    max_body: usize,
+) -> Result<Response<Vec<u8>>, Transport> {
+    let Some((start, headers)) = read_head(reader)? else {
+        return Err(Transport::Malformed(
+            "connection closed before the response".to_string(),
+        ));
+    };
+    let mut parts = start.splitn(3, ' ');
+    let (Some(version), Some(status)) = (parts.next(), parts.next()) else {
+        return Err(malformed(&start));
+    };
+    let status = StatusCode::from_bytes(status.as_bytes()).map_err(|_| malformed(&start))?;
+    let mut response = Response::builder()
+        .status(status)
+        .version(parse_version(version)?)
+        .body(vec![])
+        .map_err(|e| Transport::Malformed(e.to_string()))?;
+    if !bodyless(status) {
+        *response.body_mut() = read_body(reader, &headers, true, max_body)?;
+    }
+    *response.headers_mut() = headers;
+    Ok(response)
+}
+
+/// Write a request, adding the `Host` and `Content-Length` headers if they are missing
+///
+/// # Errors
+///
+/// Returns an `error::Transport` on I/O errors
+pub fn write_request<W: Write>(
+    writer: &mut W,
+    request: &Request<Vec<u8>>,
+) -> Result<(), Transport> {
+    let target = request
+        .uri()
+        .path_and_query()
+        .map_or("/", http::uri::PathAndQuery::as_str);
+    let mut head = format!("{} {target} HTTP/1.1\r\n", request.method());
+    if !request.headers().contains_key(HOST) {
+        if let Some(authority) = request.uri().authority() {
+            head.push_str(&format!("Host: {authority}\r\n"));
+        }
+    }
+    write_message(writer, head, request.headers(), Some(request.body()))
+}
+
+/// Write a response, adding the `Content-Length` header if it is missing.
+/// 1xx, 204 and 304 responses are written without a body or `Content-Length`.
+///
+/// # Errors
+///
+/// Returns an `error::Transport` on I/O errors
+pub fn write_response<W: Write>(
+    writer: &mut W,
+    response: &Response<Vec<u8>>,
+) -> Result<(), Transport> {
+    let status = response.status();
+    let head = format!(
+        "HTTP/1.1 {} {}\r\n",
+        status.as_str(),
+        status.canonical_reason().unwrap_or("")
+    );
+    let body = if bodyless(status) {
+        None
+    } else {
+        Some(&response.body()[..])
+    };
+    write_message(writer, head, response.headers(), body)
+}
+
+/// True if the message asks for the connection to be closed after it
+#[must_use]
+pub fn wants_close(headers: &HeaderMap) -> bool {
+    headers
+        .get(CONNECTION)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.eq_ignore_ascii_case("close"))
+}
+
+// 1xx, 204 and 304 responses never have a body
+fn bodyless(status: StatusCode) -> bool {
+    status.is_informational()
+        || status == StatusCode::NO_CONTENT
+        || status == StatusCode::NOT_MODIFIED
+}
+
+// `body` is None for a message that cannot have one, written without a Content-Length
+fn write_message<W: Write>(
+    writer: &mut W,
+    mut head: String,
+    headers: &HeaderMap,
+    body: Option<&[u8]>,
+) -> Result<(), Transport> {
+    for (name, value) in headers {
+        // the body is always written in one piece
+        if name == TRANSFER_ENCODING || (body.is_none() && name == CONTENT_LENGTH) {
+            continue;
+        }
+        let value = value
+            .to_str()
+            .map_err(|e| Transport::Malformed(e.to_string()))?;
+        head.push_str(&format!("{name}: {value}\r\n"));
+    }
+    if let Some(body) = body {
+        if !headers.contains_key(CONTENT_LENGTH) {
+            head.push_str(&format!("content-length: {}\r\n", body.len()));
+        }
+    }
+    head.push_str("\r\n");
+    writer.write_all(head.as_bytes())?;
+    writer.write_all(body.unwrap_or_default())?;
+    writer.flush()?;
+    Ok(())
+}
+
+// the start line and headers, None if the connection is closed before the first byte
+fn read_head<R: BufRead>(reader: &mut R) -> Result<Option<(String, HeaderMap)>, Transport> {
+    let mut start = String::new();
+    // tolerate empty lines before the start line
+    while start.trim().is_empty() {
+        match read_limited_line(reader)? {
+            Some(line) => start = line,
+            None => return Ok(None),
+        }
+    }
+    let mut headers = HeaderMap::new();
+    loop {
+        let line = read_line(reader)?;
+        if line.is_empty() {
+            break;
+        }
+        if headers.len() >= MAX_HEADERS {
+            return Err(Transport::TooManyHeaders(MAX_HEADERS));
+        }
+        let (name, value) = line.split_once(':').ok_or_else(|| malformed(&line))?;
+        headers.append(
+            HeaderName::from_bytes(name.trim().as_bytes()).map_err(|_| malformed(&line))?,
+            HeaderValue::from_str(value.trim()).map_err(|_| malformed(&line))?,
+        );
+    }
+    Ok(Some((start.trim_end().to_string(), headers)))
+}
+
+fn read_body<R: BufRead>(
+    reader: &mut R,
+    headers: &HeaderMap,
+    until_close: bool,
+    max_body: usize,
+) -> Result<Vec<u8>, Transport> {
+    let too_large = || Transport::TooLarge(max_body);
+    let chunked = headers
+        .get(TRANSFER_ENCODING)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.to_ascii_lowercase().contains("chunked"));
+    let mut body = vec![];
+    if chunked {
+        loop {
+            let line = read_line(reader)?;
+            let size = line.split(';').next().unwrap_or("").trim();
+            let size = usize::from_str_radix(size, 16).map_err(|_| malformed(&line))?;
+            if size == 0 {
+                // skip the trailers
+                while !read_line(reader)?.is_empty() {}
+                break;
+            }
+            let start = body.len();
+            let end = start
+                .checked_add(size)
+                .filter(|end| *end <= max_body)
+                .ok_or_else(too_large)?;
+            body.resize(end, 0);
+            reader.read_exact(&mut body[start..])?;
+            read_line(reader)?;
+        }
+    } else if let Some(length) = headers.get(CONTENT_LENGTH) {
+        let length = length
+            .to_str()
+            .ok()
+            .and_then(|l| l.trim().parse::<usize>().ok())
+            .ok_or_else(|| Transport::Malformed("invalid Content-Length".to_string()))?;
+        if length > max_body {
+            return Err(too_large());
+        }
+        body.resize(length, 0);
+        reader.read_exact(&mut body)?;
+    } else if until_close {
+        let limit = u64::try_from(max_body)
+            .unwrap_or(u64::MAX)
+            .saturating_add(1);
+        reader.take(limit).read_to_end(&mut body)?;
+        if body.len() > max_body {
+            return Err(too_large());
+        }
+    }
+    Ok(body)
+}
+
+fn read_line<R: BufRead>(reader: &mut R) -> Result<String, Transport> {
+    let line = read_limited_line(reader)?
+        .ok_or_else(|| Transport::Malformed("unexpected end of message".to_string()))?;
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+// a line of at most MAX_LINE_LENGTH bytes with its line break, None at the end of the stream
+fn read_limited_line<R: BufRead>(reader: &mut R) -> Result<Option<String>, Transport> {
+    let mut line = vec![];
+    reader
+        .take(MAX_LINE_LENGTH as u64 + 1)
+        .read_until(b'\n', &mut line)?;
+    if line.is_empty() {
+        return Ok(None);
+    }
+    if line.len() > MAX_LINE_LENGTH {
+        return Err(Transport::LineTooLong(MAX_LINE_LENGTH));
+    }
+    String::from_utf8(line)
+        .map(Some)
+        .map_err(|e| malformed(&String::from_utf8_lossy(e.as_bytes())))
+}
+
+fn parse_version(version: &str) -> Result<Version, Transport> {
+    match version {
+        "HTTP/1.1" => Ok(Version::HTTP_11),
+        "HTTP/1.0" => Ok(Version::HTTP_10),
+        _ => Err(malformed(version)),
+    }
+}
+
+fn malformed(what: &str) -> Transport {
+    Transport::Malformed(what.to_string())
+}
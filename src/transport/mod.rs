@@ -0,0 +1,176 @@
+//! HTTP transport for TR-069 sessions, following TR-069 section 3.4.
+//!
+//! The functions here map `http::Request`s and `http::Response`s carrying a
+//! `Vec<u8>` body to `Envelope`s and back, so they fit any HTTP server or
+//! client built on the `http` crate. `AcsServer` keeps the sessions of many
+//! CPEs apart with a cookie, `auth` authenticates them, and `http1` is a
+//! small blocking HTTP/1.1 implementation to run it over a `TcpStream`.
+//! `connectionrequest` lets an ACS ask a CPE to start a session.
+use http::header::{CONTENT_LENGTH, CONTENT_TYPE as CONTENT_TYPE_HEADER, COOKIE, SET_COOKIE};
+use http::{HeaderMap, Method, Request, Response, StatusCode};
+
+use crate::error::{Cwmp, Transport};
+use crate::protocol::{Envelope, GenerateOptions, ParseOptions};
+use crate::session::SessionAction;
+
+mod acsserver;
+pub mod auth;
+pub mod connectionrequest;
+pub mod http1;
+
+pub use acsserver::{AcsServer, DEFAULT_IDLE_TIMEOUT};
+
+/// The Content-Type of every HTTP message carrying an envelope
+pub const CONTENT_TYPE: &str = "text/xml; charset=\"utf-8\"";
+
+/// Name of the cookie `AcsServer` tracks sessions with
+pub const SESSION_COOKIE: &str = "CWMPSESSIONID";
+
+/// Parse an HTTP body, `None` for an empty body (the empty POST of a CPE,
+/// or the empty response ending a session)
+///
+/// # Errors
+///
+/// Returns an `error::Cwmp` if the body is not a CWMP envelope
+pub fn envelope_from_body(body: &[u8]) -> Result<Option<Envelope>, Cwmp> {
+    envelope_from_body_with(body, &ParseOptions::default())
+}
+
+/// Parse an HTTP body like `envelope_from_body`, with the given options
+///
+/// # Errors
+///
+/// Returns an `error::Cwmp` if the body is not a CWMP envelope, or goes
+/// beyond the `ParseLimits` of the options
+pub fn envelope_from_body_with(
+    body: &[u8],
+    options: &ParseOptions,
+) -> Result<Option<Envelope>, Cwmp> {
+    if body.iter().all(u8::is_ascii_whitespace) {
+        return Ok(None);
+    }
+    crate::parse_with(body, options).map(Some)
+}
+
+/// The method named by the `SOAPAction` header of a request, without quotes.
+/// `None` if there is no such header, or if it is empty.
+#[must_use]
+pub fn soap_action<B>(request: &Request<B>) -> Option<String> {
+    let action = request.headers().get("SOAPAction")?.to_str().ok()?;
+    let action = action.trim().trim_matches('"');
+    (!action.is_empty()).then(|| action.to_string())
+}
+
+/// The value of the `SESSION_COOKIE` cookie sent with a request
+#[must_use]
+pub fn session_cookie<B>(request: &Request<B>) -> Option<String> {
+    cookie_value(request.headers(), COOKIE)
+}
+
+/// The value of the `SESSION_COOKIE` cookie set by a response
+#[must_use]
+pub fn response_cookie<B>(response: &Response<B>) -> Option<String> {
+    cookie_value(response.headers(), SET_COOKIE)
+}
+
+/// Build the HTTP POST a CPE sends to the ACS at `uri`, `None` being the empty POST.
+/// Requests get a `SOAPAction` header naming the method, as TR-069 requires.
+///
+/// # Errors
+///
+/// Returns an `error::Cwmp` if the envelope cannot be generated, or `uri` is invalid
+pub fn cpe_request(
+    uri: &str,
+    envelope: Option<&Envelope>,
+    cookie: Option<&str>,
+    options: &GenerateOptions,
+) -> Result<Request<Vec<u8>>, Cwmp> {
+    let mut builder = Request::builder().method(Method::POST).uri(uri);
+    if let Some(cookie) = cookie {
+        builder = builder.header(COOKIE, format!("{SESSION_COOKIE}={cookie}"));
+    }
+    let body = match envelope {
+        Some(envelope) => {
+            if let Some(be) = envelope.body.first().filter(|be| !be.is_response()) {
+                builder = builder.header("SOAPAction", format!("\"{}\"", be.rpc_name()));
+            }
+            builder = builder.header(CONTENT_TYPE_HEADER, CONTENT_TYPE);
+            envelope_body(envelope, options)?
+        }
+        None => vec![],
+    };
+    builder
+        .header(CONTENT_LENGTH, body.len())
+        .body(body)
+        .map_err(|e| Transport::Malformed(e.to_string()).into())
+}
+
+/// Build the HTTP response an ACS sends for a `SessionAction`: the envelope
+/// with a 200 OK, or an empty 204 No Content ending the session. `cookie`
+/// is sent in a `Set-Cookie` header.
+///
+/// # Errors
+///
+/// Returns an `error::Cwmp` if the envelope cannot be generated
+pub fn acs_response(
+    action: &SessionAction,
+    cookie: Option<&str>,
+    options: &GenerateOptions,
+) -> Result<Response<Vec<u8>>, Cwmp> {
+    let mut builder = Response::builder();
+    if let Some(cookie) = cookie {
+        builder = builder.header(SET_COOKIE, format!("{SESSION_COOKIE}={cookie}; Path=/"));
+    }
+    let body = match action {
+        SessionAction::Send(envelope) => {
+            let body = envelope_body(envelope, options)?;
+            builder = builder
+                .status(StatusCode::OK)
+                .header(CONTENT_TYPE_HEADER, CONTENT_TYPE)
+                .header(CONTENT_LENGTH, body.len());
+            body
+        }
+        // a 204 must not have a Content-Length
+        SessionAction::End => {
+            builder = builder.status(StatusCode::NO_CONTENT);
+            vec![]
+        }
+    };
+    builder
+        .body(body)
+        .map_err(|e| Transport::Malformed(e.to_string()).into())
+}
+
+/// The envelope of an ACS response, `None` for a 204 No Content or an empty body
+///
+/// # Errors
+///
+/// Returns `error::Transport::Status` for any status other than 200 and 204,
+/// or an `error::Cwmp` if the body is not a CWMP envelope
+pub fn response_envelope(response: &Response<Vec<u8>>) -> Result<Option<Envelope>, Cwmp> {
+    match response.status() {
+        StatusCode::NO_CONTENT => Ok(None),
+        StatusCode::OK => envelope_from_body(response.body()),
+        status => Err(Transport::Status(status.as_u16()).into()),
+    }
+}
+
+fn envelope_body(envelope: &Envelope, options: &GenerateOptions) -> Result<Vec<u8>, Cwmp> {
+    let mut body = Vec::new();
+    envelope
+        .write_to(&mut body, options)
+        .map_err(Cwmp::GenerateError)?;
+    Ok(body)
+}
+
+// find SESSION_COOKIE in "Cookie: a=1; CWMPSESSIONID=x" or "Set-Cookie: CWMPSESSIONID=x; Path=/"
+fn cookie_value(headers: &HeaderMap, name: http::header::HeaderName) -> Option<String> {
+    headers
+        .get_all(name)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(k, _)| *k == SESSION_COOKIE)
+        .map(|(_, v)| v.trim_matches('"').to_string())
+}
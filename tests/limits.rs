@@ -0,0 +1,237 @@
+#![forbid(unsafe_code)]
+
+extern crate cwmp;
+use cwmp::error::{Cwmp, Limit};
+use cwmp::protocol::{BodyElement, ParseLimits, ParseOptions};
+
+const ENVELOPE_START: &str = r#"<SOAP-ENV:Envelope xmlns:SOAP-ENV="http://schemas.xmlsoap.org/soap/envelope/" xmlns:cwmp="urn:dslforum-org:cwmp-1-0"><SOAP-ENV:Body>"#;
+const ENVELOPE_END: &str = "</SOAP-ENV:Body></SOAP-ENV:Envelope>";
+
+fn envelope(body: &str) -> String {
+    format!("{ENVELOPE_START}{body}{ENVELOPE_END}")
+}
+
+fn parse_with_limits(xml: &str, limits: ParseLimits) -> Result<cwmp::protocol::Envelope, Cwmp> {
+    cwmp::parse_with(xml.as_bytes(), &ParseOptions::new().limits(limits))
+}
+
+fn limit(result: Result<cwmp::protocol::Envelope, Cwmp>) -> Option<Limit> {
+    match result {
+        Err(Cwmp::LimitExceeded(limit)) => Some(limit),
+        _ => None,
+    }
+}
+
+fn parameter_values(count: usize, value: &str) -> String {
+    let entries: String = (0..count)
+        .map(|i| format!("<ParameterValueStruct><Name>Device.P{i}</Name><Value>{value}</Value></ParameterValueStruct>"))
+        .collect();
+    envelope(&format!(
+        "<cwmp:GetParameterValuesResponse><ParameterList>{entries}</ParameterList></cwmp:GetParameterValuesResponse>"
+    ))
+}
+
+#[test]
+fn sample_within_default_limits() {
+    let xml = include_str!("samples/inform_1.xml");
+    assert_eq!(cwmp::parse(xml).unwrap().body.len(), 1);
+    let tight = ParseLimits::new()
+        .max_bytes(xml.len())
+        .max_depth(6)
+        .max_elements(20)
+        .max_attributes(4);
+    assert!(parse_with_limits(xml, tight).is_ok());
+}
+
+#[test]
+fn oversized_envelope() {
+    let xml = parameter_values(10, "x");
+    let limits = ParseLimits::new().max_bytes(xml.len() - 1);
+    assert_eq!(limit(parse_with_limits(&xml, limits)), Some(Limit::Bytes(xml.len() - 1)));
+    assert!(parse_with_limits(&xml, limits.max_bytes(xml.len())).is_ok());
+
+    let mut parser = cwmp::EnvelopeParser::with_options(ParseOptions::new().limits(limits));
+    let result = xml.as_bytes().chunks(64).try_for_each(|chunk| parser.feed(chunk));
+    assert!(matches!(result, Err(Cwmp::LimitExceeded(Limit::Bytes(_)))));
+}
+
+#[test]
+fn deeply_nested_elements() {
+    // a million levels would blow up the path without a depth limit
+    let depth = 1_000_000;
+    let xml = envelope(&format!(
+        "<cwmp:Reboot>{}{}</cwmp:Reboot>",
+        "<a>".repeat(depth),
+        "</a>".repeat(depth)
+    ));
+    assert_eq!(limit(cwmp::parse(&xml)), Some(Limit::Depth(64)));
+    // strict mode already stops at the first element Reboot does not have
+    assert!(matches!(cwmp::parse_strict(&xml), Err(Cwmp::StructuralError(_))));
+
+    let shallow = envelope("<cwmp:Reboot><CommandKey>k</CommandKey></cwmp:Reboot>");
+    assert_eq!(limit(parse_with_limits(&shallow, ParseLimits::new().max_depth(3))), Some(Limit::Depth(3)));
+    assert!(parse_with_limits(&shallow, ParseLimits::new().max_depth(4)).is_ok());
+}
+
+#[test]
+fn too_many_array_elements() {
+    let xml = parameter_values(1000, "1");
+    let limits = ParseLimits::new().max_elements(999);
+    assert_eq!(limit(parse_with_limits(&xml, limits)), Some(Limit::Elements(999)));
+    let parsed = parse_with_limits(&xml, limits.max_elements(1000)).unwrap();
+    let Some(BodyElement::GetParameterValuesResponse(r)) = parsed.body.first() else {
+        panic!("not a GetParameterValuesResponse")
+    };
+    assert_eq!(r.parameters.len(), 1000);
+
+    // empty unknown elements count too, even though they are never stored in a list
+    let flood = envelope(&format!("<cwmp:X_Flood>{}</cwmp:X_Flood>", "<x/>".repeat(1000)));
+    let limits = ParseLimits::new().max_elements(100);
+    assert_eq!(limit(parse_with_limits(&flood, limits)), Some(Limit::Elements(100)));
+}
+
+#[test]
+fn long_text() {
+    let long = "a".repeat(2000);
+    let xml = parameter_values(1, &long);
+    let limits = ParseLimits::new().max_text_length(1999);
+    assert_eq!(limit(parse_with_limits(&xml, limits)), Some(Limit::TextLength(1999)));
+    assert!(parse_with_limits(&xml, limits.max_text_length(2000)).is_ok());
+
+    // text split around entities is counted as a whole
+    let split = parameter_values(1, &"aaaa&amp;".repeat(400));
+    assert_eq!(limit(parse_with_limits(&split, limits)), Some(Limit::TextLength(1999)));
+
+    // far beyond the limit the reader stops by itself, in any parser
+    let huge = parameter_values(1, &"a".repeat(1_000_000));
+    assert_eq!(limit(parse_with_limits(&huge, limits)), Some(Limit::TextLength(1999)));
+    let mut parser = cwmp::EnvelopeParser::with_options(ParseOptions::new().limits(limits));
+    let result = huge.as_bytes().chunks(4096).try_for_each(|chunk| parser.feed(chunk)).and_then(|()| parser.finish().map(|_| ()));
+    assert!(matches!(result, Err(Cwmp::LimitExceeded(Limit::TextLength(1999)))));
+}
+
+#[test]
+fn many_attributes() {
+    let attributes: String = (0..100).map(|i| format!(r#" a{i}="{i}""#)).collect();
+    let xml = envelope(&format!("<cwmp:Reboot{attributes}><CommandKey>k</CommandKey></cwmp:Reboot>"));
+    assert_eq!(limit(cwmp::parse(&xml)), Some(Limit::Attributes(64)));
+    assert!(parse_with_limits(&xml, ParseLimits::unlimited()).is_ok());
+    assert_eq!(limit(parse_with_limits(&xml, ParseLimits::new().max_attributes(99))), Some(Limit::Attributes(99)));
+    assert!(parse_with_limits(&xml, ParseLimits::new().max_attributes(100)).is_ok());
+}
+
+#[test]
+fn long_attribute() {
+    let reboot = |value: &str| envelope(&format!(r#"<cwmp:Reboot a="{value}"><CommandKey>k</CommandKey></cwmp:Reboot>"#));
+    let xml = reboot(&"a".repeat(1000));
+    let limits = ParseLimits::new().max_attribute_length(999);
+    assert_eq!(limit(parse_with_limits(&xml, limits)), Some(Limit::AttributeLength(999)));
+    assert!(parse_with_limits(&xml, limits.max_attribute_length(1000)).is_ok());
+
+    let huge = reboot(&"a".repeat(1_000_000));
+    assert_eq!(limit(cwmp::parse(&huge)), Some(Limit::AttributeLength(64 * 1024)));
+    assert!(parse_with_limits(&huge, ParseLimits::unlimited()).is_ok());
+    // other errors of the reader stay parse errors
+    let broken = xml.replace("</cwmp:Reboot>", "");
+    assert!(matches!(parse_with_limits(&broken, limits.max_attribute_length(1000)), Err(Cwmp::ParseError(_))));
+}
+
+#[test]
+fn entity_expansion() {
+    // the billion laughs attack must fail rather than expand
+    let xml = r#"<?xml version="1.0"?>
+<!DOCTYPE lolz [
+  <!ENTITY lol "lol">
+  <!ENTITY lol1 "&lol;&lol;&lol;&lol;&lol;&lol;&lol;&lol;&lol;&lol;">
+  <!ENTITY lol2 "&lol1;&lol1;&lol1;&lol1;&lol1;&lol1;&lol1;&lol1;&lol1;&lol1;">
+  <!ENTITY lol3 "&lol2;&lol2;&lol2;&lol2;&lol2;&lol2;&lol2;&lol2;&lol2;&lol2;">
+  <!ENTITY lol4 "&lol3;&lol3;&lol3;&lol3;&lol3;&lol3;&lol3;&lol3;&lol3;&lol3;">
+  <!ENTITY lol5 "&lol4;&lol4;&lol4;&lol4;&lol4;&lol4;&lol4;&lol4;&lol4;&lol4;">
+  <!ENTITY lol6 "&lol5;&lol5;&lol5;&lol5;&lol5;&lol5;&lol5;&lol5;&lol5;&lol5;">
+  <!ENTITY lol7 "&lol6;&lol6;&lol6;&lol6;&lol6;&lol6;&lol6;&lol6;&lol6;&lol6;">
+  <!ENTITY lol8 "&lol7;&lol7;&lol7;&lol7;&lol7;&lol7;&lol7;&lol7;&lol7;&lol7;">
+  <!ENTITY lol9 "&lol8;&lol8;&lol8;&lol8;&lol8;&lol8;&lol8;&lol8;&lol8;&lol8;">
+]>
+<SOAP-ENV:Envelope xmlns:SOAP-ENV="http://schemas.xmlsoap.org/soap/envelope/" xmlns:cwmp="urn:dslforum-org:cwmp-1-0"><SOAP-ENV:Body><cwmp:Reboot><CommandKey>&lol9;</CommandKey></cwmp:Reboot></SOAP-ENV:Body></SOAP-ENV:Envelope>"#;
+    assert!(cwmp::parse(xml).is_err());
+}
+
+#[cfg(feature = "borrowed")]
+fn inform(parameters: &str) -> String {
+    envelope(&format!(
+        "<cwmp:Inform><DeviceId><Manufacturer>M</Manufacturer><OUI>AA1234</OUI><ProductClass>P</ProductClass><SerialNumber>S</SerialNumber></DeviceId><ParameterList>{parameters}</ParameterList></cwmp:Inform>"
+    ))
+}
+
+#[cfg(feature = "borrowed")]
+fn limit_ref<T>(result: Result<T, Cwmp>) -> Option<Limit> {
+    match result {
+        Err(Cwmp::LimitExceeded(limit)) => Some(limit),
+        _ => None,
+    }
+}
+
+#[cfg(feature = "borrowed")]
+#[test]
+fn inform_ref_limits() {
+    let sample = include_str!("samples/inform_1.xml");
+    assert!(cwmp::parse_inform_ref(sample).unwrap().is_some());
+    let tight = ParseLimits::new()
+        .max_bytes(sample.len())
+        .max_depth(6)
+        .max_elements(20)
+        .max_attributes(7);
+    assert!(cwmp::parse_inform_ref_with(sample, &tight).unwrap().is_some());
+    let limits = tight.max_bytes(sample.len() - 1);
+    assert_eq!(limit_ref(cwmp::parse_inform_ref_with(sample, &limits)), Some(Limit::Bytes(sample.len() - 1)));
+
+    // the path would grow with every level without a depth limit
+    let depth = 1_000_000;
+    let deep = inform(&format!("{}{}", "<a>".repeat(depth), "</a>".repeat(depth)));
+    assert_eq!(limit_ref(cwmp::parse_inform_ref(&deep)), Some(Limit::Depth(64)));
+    assert_eq!(limit_ref(cwmp::parse_inform_ref_with(&deep, &ParseLimits::new().max_depth(5))), Some(Limit::Depth(5)));
+}
+
+#[cfg(feature = "borrowed")]
+#[test]
+fn inform_ref_parameter_list() {
+    let entries = |count: usize, value: &str| -> String {
+        (0..count)
+            .map(|i| format!("<ParameterValueStruct><Name>Device.P{i}</Name><Value>{value}</Value></ParameterValueStruct>"))
+            .collect()
+    };
+    let xml = inform(&entries(1000, "1"));
+    let limits = ParseLimits::new().max_elements(999);
+    assert_eq!(limit_ref(cwmp::parse_inform_ref_with(&xml, &limits)), Some(Limit::Elements(999)));
+    let parsed = cwmp::parse_inform_ref_with(&xml, &limits.max_elements(1000)).unwrap().unwrap();
+    assert_eq!(parsed.parameter_list.len(), 1000);
+
+    // elements that are never stored count too
+    let flood = inform(&"<x/>".repeat(1000));
+    assert_eq!(limit_ref(cwmp::parse_inform_ref_with(&flood, &ParseLimits::new().max_elements(100))), Some(Limit::Elements(100)));
+
+    let long = inform(&entries(1, &"a".repeat(2000)));
+    let limits = ParseLimits::new().max_text_length(1999);
+    assert_eq!(limit_ref(cwmp::parse_inform_ref_with(&long, &limits)), Some(Limit::TextLength(1999)));
+    assert!(cwmp::parse_inform_ref_with(&long, &limits.max_text_length(2000)).is_ok());
+    // text split around entities and CDATA is counted as a whole
+    let split = inform(&entries(1, &"aaaa&amp;<![CDATA[bb]]>".repeat(300)));
+    assert_eq!(limit_ref(cwmp::parse_inform_ref_with(&split, &limits)), Some(Limit::TextLength(1999)));
+}
+
+#[cfg(feature = "borrowed")]
+#[test]
+fn inform_ref_attributes() {
+    let attributes: String = (0..100).map(|i| format!(r#" a{i}="{i}""#)).collect();
+    let many = inform(&format!("<ParameterValueStruct{attributes}/>"));
+    assert_eq!(limit_ref(cwmp::parse_inform_ref(&many)), Some(Limit::Attributes(64)));
+    assert_eq!(limit_ref(cwmp::parse_inform_ref_with(&many, &ParseLimits::new().max_attributes(99))), Some(Limit::Attributes(99)));
+    assert!(cwmp::parse_inform_ref_with(&many, &ParseLimits::new().max_attributes(100)).is_ok());
+
+    let long = |value: &str| inform(&format!(r#"<ParameterValueStruct><Name>N</Name><Value xsi:type="{value}">1</Value></ParameterValueStruct>"#));
+    let xml = long(&"a".repeat(1000));
+    let limits = ParseLimits::new().max_attribute_length(999);
+    assert_eq!(limit_ref(cwmp::parse_inform_ref_with(&xml, &limits)), Some(Limit::AttributeLength(999)));
+    assert!(cwmp::parse_inform_ref_with(&xml, &limits.max_attribute_length(1000)).is_ok());
+    assert_eq!(limit_ref(cwmp::parse_inform_ref(&long(&"a".repeat(1_000_000)))), Some(Limit::AttributeLength(64 * 1024)));
+}
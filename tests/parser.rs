@@ -65,3 +65,168 @@ fn test(input: &[u8], expected: cwmp::protocol::Envelope) {
         Err(e) => panic!("Could not parse utf8 string: {:?}", e),
     }
 }
+
+#[test]
+fn parse_strict_accepts_valid_inform() {
+    let sample = str::from_utf8(include_bytes!("samples/inform_1.xml")).unwrap();
+    assert_eq!(cwmp::parse_strict(sample).unwrap(), cwmp::parse(sample).unwrap());
+}
+
+fn strict_error(xml: &str) -> cwmp::protocol::StructuralError {
+    match cwmp::parse_strict(xml) {
+        Err(cwmp::error::Cwmp::StructuralError(e)) => e,
+        other => panic!("expected a structural error, got {:?}", other),
+    }
+}
+
+#[test]
+fn parse_strict_unknown_body_element() {
+    let sample = str::from_utf8(include_bytes!("samples/inform_1.xml"))
+        .unwrap()
+        .replace("cwmp:Inform>", "cwmp:X_ACME_Inform>");
+    // the lenient parser keeps the element as it is
+    let body = cwmp::parse(&sample).unwrap().body;
+    assert_eq!(body[0].rpc_name(), "X_ACME_Inform");
+
+    let e = strict_error(&sample);
+    assert_eq!(e.kind, cwmp::protocol::StructuralErrorKind::UnknownElement);
+    assert_eq!(e.path, "/Envelope/Body/X_ACME_Inform");
+    assert_eq!(e.line, 13);
+    assert_eq!(e.column, 7);
+}
+
+#[test]
+fn parse_strict_missing_element() {
+    let sample = str::from_utf8(include_bytes!("samples/inform_1.xml"))
+        .unwrap()
+        .replace("<RetryCount>0</RetryCount>", "");
+    let e = strict_error(&sample);
+    assert_eq!(
+        e.kind,
+        cwmp::protocol::StructuralErrorKind::MissingElement("RetryCount".to_string())
+    );
+    assert_eq!(e.path, "/Envelope/Body/Inform");
+}
+
+#[test]
+fn parse_strict_missing_mandatory_elements() {
+    let e = strict_error(
+        &str::from_utf8(include_bytes!("samples/inform_1.xml"))
+            .unwrap()
+            .replace("<CurrentTime>2015-01-19T23:08:24+00:00</CurrentTime>", ""),
+    );
+    assert_eq!(e.kind, cwmp::protocol::StructuralErrorKind::MissingElement("CurrentTime".to_string()));
+    // the members of the structs below the RPCs too
+    let e = strict_error(
+        &str::from_utf8(include_bytes!("samples/inform_1.xml"))
+            .unwrap()
+            .replace("<OUI>AA1234</OUI>", ""),
+    );
+    assert_eq!(e.kind, cwmp::protocol::StructuralErrorKind::MissingElement("OUI".to_string()));
+    assert_eq!(e.path, "/Envelope/Body/Inform/DeviceId");
+
+    let time = "2015-01-19T23:08:24Z";
+    let fault = "<FaultCode>0</FaultCode><FaultString></FaultString>";
+    let parameter = "<ParameterValueStruct><Name>A.B</Name><Value>x</Value></ParameterValueStruct>";
+    // the RPC with all of `children`, and without each of `mandatory` in turn
+    fn check(rpc: &str, children: &[(&str, &str)], mandatory: &[&str]) {
+        let xml = |without: &str| {
+            let children: String = children
+                .iter()
+                .filter(|(name, _)| *name != without)
+                .map(|(name, content)| format!("<{name}>{content}</{name}>"))
+                .collect();
+            format!(r#"<SOAP-ENV:Envelope xmlns:SOAP-ENV="http://schemas.xmlsoap.org/soap/envelope/" xmlns:cwmp="urn:dslforum-org:cwmp-1-0"><SOAP-ENV:Body><cwmp:{rpc}>{children}</cwmp:{rpc}></SOAP-ENV:Body></SOAP-ENV:Envelope>"#)
+        };
+        assert!(cwmp::parse_strict(&xml("")).is_ok(), "{rpc}");
+        for m in mandatory {
+            let e = strict_error(&xml(m));
+            assert_eq!(e.kind, cwmp::protocol::StructuralErrorKind::MissingElement(m.to_string()), "{rpc}");
+            assert_eq!(e.path, format!("/Envelope/Body/{rpc}"));
+        }
+    }
+    check("SetParameterValues", &[("ParameterList", parameter), ("ParameterKey", "k")], &["ParameterKey"]);
+    check(
+        "TransferComplete",
+        &[("CommandKey", "k"), ("FaultStruct", fault), ("StartTime", time), ("CompleteTime", time)],
+        &["StartTime", "CompleteTime"],
+    );
+    for rpc in ["DownloadResponse", "UploadResponse"] {
+        check(rpc, &[("Status", "0"), ("StartTime", time), ("CompleteTime", time)], &["StartTime", "CompleteTime"]);
+    }
+    check(
+        "AutonomousTransferComplete",
+        &[
+            ("AnnounceURL", ""),
+            ("TransferURL", "http://example.com/fw"),
+            ("IsDownload", "1"),
+            ("FileType", "1 Firmware Upgrade Image"),
+            ("FileSize", "100"),
+            ("TargetFileName", ""),
+            ("FaultStruct", fault),
+            ("StartTime", time),
+            ("CompleteTime", time),
+        ],
+        &["FaultStruct", "StartTime", "CompleteTime"],
+    );
+}
+
+#[test]
+fn parse_strict_invalid_integer() {
+    let sample = str::from_utf8(include_bytes!("samples/inform_1.xml"))
+        .unwrap()
+        .replace("<MaxEnvelopes>1</MaxEnvelopes>", "<MaxEnvelopes>one</MaxEnvelopes>");
+    // the lenient parser turns it into 0
+    match &cwmp::parse(&sample).unwrap().body[0] {
+        cwmp::protocol::BodyElement::Inform(i) => assert_eq!(i.max_envelopes, 0),
+        _ => panic!("expected an Inform"),
+    }
+
+    let e = strict_error(&sample);
+    assert_eq!(
+        e.kind,
+        cwmp::protocol::StructuralErrorKind::InvalidInteger("one".to_string())
+    );
+    assert_eq!(e.path, "/Envelope/Body/Inform/MaxEnvelopes");
+    assert_eq!(e.line, 26);
+}
+
+#[test]
+fn parse_strict_invalid_date_time() {
+    let sample = str::from_utf8(include_bytes!("samples/inform_1.xml"))
+        .unwrap()
+        .replace("2015-01-19T23:08:24+00:00", "yesterday");
+    let e = strict_error(&sample);
+    assert_eq!(
+        e.kind,
+        cwmp::protocol::StructuralErrorKind::InvalidDateTime("yesterday".to_string())
+    );
+    assert_eq!(e.path, "/Envelope/Body/Inform/CurrentTime");
+}
+
+#[test]
+fn parse_strict_unknown_child_element() {
+    let reboot = r#"<SOAP-ENV:Envelope xmlns:SOAP-ENV="http://schemas.xmlsoap.org/soap/envelope/" xmlns:cwmp="urn:dslforum-org:cwmp-1-0"><SOAP-ENV:Body><cwmp:Reboot><CommandKey>k</CommandKey><Bogus>x</Bogus></cwmp:Reboot></SOAP-ENV:Body></SOAP-ENV:Envelope>"#;
+    let e = strict_error(reboot);
+    assert_eq!(e.kind, cwmp::protocol::StructuralErrorKind::UnknownElement);
+    assert_eq!(e.path, "/Envelope/Body/Reboot/Bogus");
+
+    // deeper down, against the members of the struct
+    let sample = str::from_utf8(include_bytes!("samples/inform_1.xml"))
+        .unwrap()
+        .replace("<OUI>", "<X_ABC_Model>m</X_ABC_Model><OUI>");
+    let e = strict_error(&sample);
+    assert_eq!(e.kind, cwmp::protocol::StructuralErrorKind::UnknownElement);
+    assert_eq!(e.path, "/Envelope/Body/Inform/DeviceId/X_ABC_Model");
+}
+
+#[test]
+fn parse_strict_integer_with_whitespace() {
+    let sample = str::from_utf8(include_bytes!("samples/inform_1.xml"))
+        .unwrap()
+        .replace("<MaxEnvelopes>1</MaxEnvelopes>", "<MaxEnvelopes>\n1\n</MaxEnvelopes>");
+    match &cwmp::parse_strict(&sample).unwrap().body[0] {
+        cwmp::protocol::BodyElement::Inform(i) => assert_eq!(i.max_envelopes, 1),
+        _ => panic!("expected an Inform"),
+    }
+}